@@ -0,0 +1,1054 @@
+use clauses::*;
+use normal_forms::*;
+use proofs::*;
+use propositions::*;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::mem;
+
+/// A boolean function stored as a reduced ordered binary decision diagram in
+/// a `BddManager`.
+///
+/// Every node of the manager is unique, so two `Bdd`s from the same manager
+/// are equal exactly when they stand for the same function. A `Bdd` is only
+/// meaningful with the manager that made it.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let a = parse_proposition("p -> (q /\\ r)").unwrap();
+/// let b = parse_proposition("(~p \\/ q) /\\ (r \\/ ~p)").unwrap();
+///
+/// let mut manager = BddManager::for_propositions(&[a.clone(), b.clone()]);
+/// let a2 = Bdd::from_proposition(&mut manager, &a);
+/// let b2 = Bdd::from_proposition(&mut manager, &b);
+///
+/// assert_eq!(a2, b2);
+/// assert_eq!(manager.sat_count(a2), Some(5));
+/// ```
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+pub struct Bdd {
+    index: usize
+}
+
+/// A binary connective that can be applied to two `Bdd`s.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+pub enum BddOperator {
+    And,
+    Or,
+    Implies,
+    Iff,
+    Xor,
+    Nand,
+    Nor
+}
+
+impl BddOperator {
+    fn evaluate(self, a: bool, b: bool) -> bool {
+        match self {
+            BddOperator::And => a && b,
+            BddOperator::Or => a || b,
+            BddOperator::Implies => !a || b,
+            BddOperator::Iff => a == b,
+            BddOperator::Xor => a != b,
+            BddOperator::Nand => !(a && b),
+            BddOperator::Nor => !(a || b),
+        }
+    }
+}
+
+/// A decision on the variable at the given level, leading to `high` when it
+/// is true and `low` when it is false.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+struct Node {
+    level: usize,
+    low: usize,
+    high: usize
+}
+
+/// An operation whose results are kept in the computed table.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+enum Operation {
+    Apply(BddOperator),
+    Ite
+}
+
+/// The false and true terminals, which are the first two nodes.
+const FALSE: usize = 0;
+const TRUE: usize = 1;
+
+/// Holds the nodes of a set of reduced ordered binary decision diagrams over
+/// a shared order of variables.
+///
+/// A unique table for each level makes sure that no two nodes have the same
+/// variable and children, so equal functions always get the same `Bdd`, and
+/// a computed
+/// table remembers the results of earlier operations. Variables that are not
+/// in the order yet are added after the others when they are first used.
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct BddManager {
+    variables: Vec<String>,
+    levels: HashMap<String, usize>,
+    nodes: Vec<Node>,
+    unique: Vec<HashMap<(usize, usize), usize>>,
+    computed: HashMap<(Operation, usize, usize, usize), usize>
+}
+
+impl Bdd {
+    /// Builds the diagram for the given proposition in the given manager.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("[p + q + r >= 2]").unwrap();
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let bdd = Bdd::from_proposition(&mut manager, &prop);
+    ///
+    /// assert_eq!(manager.variables(), &["p", "q", "r"]);
+    /// assert_eq!(manager.sat_count(bdd), Some(4));
+    /// ```
+    pub fn from_proposition(manager: &mut BddManager, prop: &Proposition) -> Bdd {
+        let mut binary = |operator, a: &Proposition, b: &Proposition| {
+            let a2 = Bdd::from_proposition(manager, a);
+            let b2 = Bdd::from_proposition(manager, b);
+            manager.apply(operator, a2, b2)
+        };
+
+        match *prop {
+            Proposition::Or(ref a, ref b) => binary(BddOperator::Or, a, b),
+            Proposition::And(ref a, ref b) => binary(BddOperator::And, a, b),
+            Proposition::Implies(ref a, ref b) => binary(BddOperator::Implies, a, b),
+            Proposition::Iff(ref a, ref b) => binary(BddOperator::Iff, a, b),
+            Proposition::Xor(ref a, ref b) => binary(BddOperator::Xor, a, b),
+            Proposition::Nand(ref a, ref b) => binary(BddOperator::Nand, a, b),
+            Proposition::Nor(ref a, ref b) => binary(BddOperator::Nor, a, b),
+            Proposition::Ite(ref c, ref t, ref e) => {
+                let c2 = Bdd::from_proposition(manager, c);
+                let t2 = Bdd::from_proposition(manager, t);
+                let e2 = Bdd::from_proposition(manager, e);
+                manager.ite(c2, t2, e2)
+            },
+            Proposition::Linear(ref terms, comparison, bound) => {
+                let operands: Vec<(i64, Bdd)> = terms.iter()
+                    .map(|&(c, ref p)| (c, Bdd::from_proposition(manager, p)))
+                    .collect();
+                manager.linear(&operands, comparison, bound)
+            },
+            Proposition::Not(ref a) => {
+                let a2 = Bdd::from_proposition(manager, a);
+                manager.not(a2)
+            },
+            Proposition::Term(ref a) => manager.variable(a),
+            Proposition::Top => manager.constant(true),
+            Proposition::Bottom => manager.constant(false),
+        }
+    }
+}
+
+impl BddManager {
+    /// Creates a manager whose variables come in the given order, from the
+    /// root of each diagram down. Repeated names are only used once.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let mut manager = BddManager::new(vec!("q".to_string(), "p".to_string()));
+    /// manager.variable("r");
+    ///
+    /// assert_eq!(manager.variables(), &["q", "p", "r"]);
+    /// ```
+    pub fn new(variables: Vec<String>) -> BddManager {
+        let terminal = |value| Node { level: usize::MAX, low: value, high: value };
+
+        let mut manager = BddManager {
+            variables: vec!(),
+            levels: HashMap::new(),
+            nodes: vec!(terminal(FALSE), terminal(TRUE)),
+            unique: vec!(),
+            computed: HashMap::new()
+        };
+        for v in variables {
+            manager.level(&v);
+        }
+        manager
+    }
+
+    /// Creates a manager for the given propositions, with its variables in
+    /// the order given by `variable_order`.
+    pub fn for_propositions(props: &[Proposition]) -> BddManager {
+        BddManager::new(variable_order(props))
+    }
+
+    /// Returns the variables of the manager, from the root of each diagram
+    /// down.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Returns the diagram that is always the given value.
+    pub fn constant(&self, value: bool) -> Bdd {
+        Bdd { index: if value { TRUE } else { FALSE } }
+    }
+
+    /// Returns the diagram that is true exactly when the given variable is,
+    /// adding the variable to the end of the order if it is new.
+    pub fn variable(&mut self, name: &str) -> Bdd {
+        let level = self.level(name);
+        Bdd { index: self.make(level, FALSE, TRUE) }
+    }
+
+    /// Returns the negation of the given diagram.
+    pub fn not(&mut self, f: Bdd) -> Bdd {
+        let (t, e) = (self.constant(false), self.constant(true));
+        self.ite(f, t, e)
+    }
+
+    /// Joins the two given diagrams with the given connective.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let p = manager.variable("p");
+    /// let q = manager.variable("q");
+    ///
+    /// let a = manager.apply(BddOperator::Nand, p, q);
+    /// let not_p = manager.not(p);
+    /// let not_q = manager.not(q);
+    /// let b = manager.apply(BddOperator::Or, not_p, not_q);
+    ///
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn apply(&mut self, operator: BddOperator, f: Bdd, g: Bdd) -> Bdd {
+        Bdd { index: self.apply_(operator, f.index, g.index) }
+    }
+
+    fn apply_(&mut self, operator: BddOperator, f: usize, g: usize) -> usize {
+        if f <= TRUE && g <= TRUE {
+            return if operator.evaluate(f == TRUE, g == TRUE) { TRUE } else { FALSE }
+        }
+
+        let key = (Operation::Apply(operator), f, g, 0);
+        if let Some(&result) = self.computed.get(&key) {
+            return result
+        }
+
+        let level = self.nodes[f].level.min(self.nodes[g].level);
+        let (f_low, f_high) = self.cofactors(f, level);
+        let (g_low, g_high) = self.cofactors(g, level);
+
+        let low = self.apply_(operator, f_low, g_low);
+        let high = self.apply_(operator, f_high, g_high);
+        let result = self.make(level, low, high);
+
+        self.computed.insert(key, result);
+        result
+    }
+
+    /// Returns the diagram that is `g` where `f` is true and `h` where it is
+    /// false.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let p = manager.variable("p");
+    /// let q = manager.variable("q");
+    /// let r = manager.variable("r");
+    ///
+    /// let choice = manager.ite(p, q, r);
+    /// let prop = parse_proposition("if p then q else r").unwrap();
+    ///
+    /// assert_eq!(choice, Bdd::from_proposition(&mut manager, &prop));
+    /// ```
+    pub fn ite(&mut self, f: Bdd, g: Bdd, h: Bdd) -> Bdd {
+        Bdd { index: self.ite_(f.index, g.index, h.index) }
+    }
+
+    fn ite_(&mut self, f: usize, g: usize, h: usize) -> usize {
+        if f == TRUE || g == h {
+            return g
+        }
+        if f == FALSE {
+            return h
+        }
+        if g == TRUE && h == FALSE {
+            return f
+        }
+
+        let key = (Operation::Ite, f, g, h);
+        if let Some(&result) = self.computed.get(&key) {
+            return result
+        }
+
+        let level = self.nodes[f].level
+            .min(self.nodes[g].level)
+            .min(self.nodes[h].level);
+        let (f_low, f_high) = self.cofactors(f, level);
+        let (g_low, g_high) = self.cofactors(g, level);
+        let (h_low, h_high) = self.cofactors(h, level);
+
+        let low = self.ite_(f_low, g_low, h_low);
+        let high = self.ite_(f_high, g_high, h_high);
+        let result = self.make(level, low, high);
+
+        self.computed.insert(key, result);
+        result
+    }
+
+    /// Returns the diagram that is `f` with the given variable fixed to the
+    /// given value.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(p /\\ q) \\/ (~p /\\ r)").unwrap();
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let f = Bdd::from_proposition(&mut manager, &prop);
+    ///
+    /// let when_true = manager.restrict(f, "p", true);
+    /// let q = manager.variable("q");
+    ///
+    /// assert_eq!(when_true, q);
+    /// ```
+    pub fn restrict(&mut self, f: Bdd, name: &str, value: bool) -> Bdd {
+        let level = match self.levels.get(name) {
+            Some(&l) => l,
+            None => return f
+        };
+
+        fn restrict_(manager: &mut BddManager, f: usize, level: usize, value: bool,
+                     memo: &mut HashMap<usize, usize>) -> usize {
+            let node = manager.nodes[f];
+            if node.level > level {
+                return f
+            }
+            if node.level == level {
+                return if value { node.high } else { node.low }
+            }
+            if let Some(&result) = memo.get(&f) {
+                return result
+            }
+
+            let low = restrict_(manager, node.low, level, value, memo);
+            let high = restrict_(manager, node.high, level, value, memo);
+            let result = manager.make(node.level, low, high);
+
+            memo.insert(f, result);
+            result
+        }
+
+        Bdd { index: restrict_(self, f.index, level, value, &mut HashMap::new()) }
+    }
+
+    /// Returns the diagram that is true when `f` is true for some values of
+    /// the given variables.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(p /\\ q) \\/ (~p /\\ r)").unwrap();
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let f = Bdd::from_proposition(&mut manager, &prop);
+    ///
+    /// let some = manager.exists(f, &["p".to_string()]);
+    /// let every = manager.forall(f, &["p".to_string()]);
+    ///
+    /// let q_or_r = parse_proposition("q \\/ r").unwrap();
+    /// let q_and_r = parse_proposition("q /\\ r").unwrap();
+    ///
+    /// assert_eq!(some, Bdd::from_proposition(&mut manager, &q_or_r));
+    /// assert_eq!(every, Bdd::from_proposition(&mut manager, &q_and_r));
+    /// ```
+    pub fn exists(&mut self, f: Bdd, names: &[String]) -> Bdd {
+        self.quantify(f, names, BddOperator::Or)
+    }
+
+    /// Returns the diagram that is true when `f` is true for all values of
+    /// the given variables.
+    pub fn forall(&mut self, f: Bdd, names: &[String]) -> Bdd {
+        self.quantify(f, names, BddOperator::And)
+    }
+
+    /// Removes the given variables from `f`, joining the two cofactors of
+    /// each with the given connective.
+    fn quantify(&mut self, f: Bdd, names: &[String], operator: BddOperator) -> Bdd {
+        let levels: HashSet<usize> = names.iter()
+            .filter_map(|n| self.levels.get(n).cloned())
+            .collect();
+
+        fn quantify_(manager: &mut BddManager, f: usize, levels: &HashSet<usize>,
+                     operator: BddOperator, memo: &mut HashMap<usize, usize>) -> usize {
+            if f <= TRUE {
+                return f
+            }
+            if let Some(&result) = memo.get(&f) {
+                return result
+            }
+
+            let node = manager.nodes[f];
+            let low = quantify_(manager, node.low, levels, operator, memo);
+            let high = quantify_(manager, node.high, levels, operator, memo);
+            let result = if levels.contains(&node.level) {
+                manager.apply_(operator, low, high)
+            } else {
+                manager.make(node.level, low, high)
+            };
+
+            memo.insert(f, result);
+            result
+        }
+
+        Bdd { index: quantify_(self, f.index, &levels, operator, &mut HashMap::new()) }
+    }
+
+    /// Counts the assignments to all of the variables of the manager that
+    /// make `f` true, or returns `None` if there are 2<sup>128</sup> or more
+    /// of them, which can only happen with at least 128 variables.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let mut manager = BddManager::new(vec!("p".to_string(), "q".to_string()));
+    /// let r = manager.variable("r");
+    ///
+    /// assert_eq!(manager.sat_count(r), Some(4));
+    /// ```
+    pub fn sat_count(&self, f: Bdd) -> Option<u128> {
+        fn count(manager: &BddManager, f: usize, memo: &mut HashMap<usize, Option<u128>>) -> Option<u128> {
+            if f <= TRUE {
+                return Some(f as u128)
+            }
+            if let Some(&result) = memo.get(&f) {
+                return result
+            }
+
+            let node = manager.nodes[f];
+            let mut result = Some(0u128);
+            for &child in &[node.low, node.high] {
+                let skipped = manager.level_of(child) - node.level - 1;
+                let models = count(manager, child, memo);
+                result = scaled(models, skipped).and_then(|c| result?.checked_add(c));
+            }
+
+            memo.insert(f, result);
+            result
+        }
+
+        /// Multiplies the count by two for each of the given number of
+        /// variables that are skipped over.
+        fn scaled(count: Option<u128>, skipped: usize) -> Option<u128> {
+            let count = count?;
+            if count == 0 {
+                return Some(0)
+            }
+            if skipped >= 128 || count.leading_zeros() < skipped as u32 {
+                return None
+            }
+            Some(count << skipped)
+        }
+
+        scaled(count(self, f.index, &mut HashMap::new()), self.level_of(f.index))
+    }
+
+    /// Returns the cubes for the paths from the root of `f` to the true
+    /// terminal, which never overlap and whose disjunction is `f`.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("p <+> q").unwrap();
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let f = Bdd::from_proposition(&mut manager, &prop);
+    ///
+    /// let cubes: Vec<String> = manager.cubes(f).iter().map(|c| c.to_string()).collect();
+    ///
+    /// assert_eq!(cubes, vec!("~p /\\ q", "p /\\ ~q"));
+    /// ```
+    pub fn cubes(&self, f: Bdd) -> Vec<Cube> {
+        fn paths(manager: &BddManager, f: usize, path: &mut Vec<ClausePart>, out: &mut Vec<Cube>) {
+            match f {
+                FALSE => {},
+                TRUE => out.push(Cube { parts: path.clone() }),
+                _ => {
+                    let node = manager.nodes[f];
+                    let name = manager.variables[node.level].clone();
+
+                    path.push(ClausePart::NegatedTerm(name.clone()));
+                    paths(manager, node.low, path, out);
+                    path.pop();
+
+                    path.push(ClausePart::Term(name));
+                    paths(manager, node.high, path, out);
+                    path.pop();
+                }
+            }
+        }
+
+        let mut cubes = vec!();
+        paths(self, f.index, &mut vec!(), &mut cubes);
+        cubes
+    }
+
+    /// Returns the number of decision nodes that can be reached from the
+    /// given diagrams, counting shared nodes once.
+    pub fn node_count(&self, roots: &[Bdd]) -> usize {
+        self.reachable(roots).len()
+    }
+
+    /// Converts `f` back into a proposition, with a conditional choice for
+    /// each decision node, or a simpler connective when one of its children
+    /// is a terminal.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(q /\\ p) \\/ (p /\\ ~q)").unwrap();
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let f = Bdd::from_proposition(&mut manager, &prop);
+    ///
+    /// assert_eq!(manager.to_proposition(f).to_string(), "p");
+    /// ```
+    pub fn to_proposition(&self, f: Bdd) -> Proposition {
+        fn convert(manager: &BddManager, f: usize) -> Proposition {
+            if f <= TRUE {
+                return if f == TRUE { top() } else { bottom() }
+            }
+
+            let node = manager.nodes[f];
+            let v = term(manager.variables[node.level].clone());
+            match (node.low, node.high) {
+                (FALSE, TRUE) => v,
+                (TRUE, FALSE) => not(v),
+                (low, TRUE) => or(v, convert(manager, low)),
+                (low, FALSE) => and(not(v), convert(manager, low)),
+                (TRUE, high) => or(not(v), convert(manager, high)),
+                (FALSE, high) => and(v, convert(manager, high)),
+                (low, high) => ite(v, convert(manager, high), convert(manager, low)),
+            }
+        }
+
+        convert(self, f.index)
+    }
+
+    /// Returns a DOT graph of `f`, which can be rendered with Graphviz. The
+    /// edge taken when a variable is false is dashed.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let mut manager = BddManager::new(vec!());
+    /// let p = manager.variable("p");
+    ///
+    /// let expected = "digraph bdd {
+    ///     n0 [label=\"0\", shape=box];
+    ///     n1 [label=\"1\", shape=box];
+    ///     n2 [label=\"p\", shape=circle];
+    ///     n2 -> n0 [style=dashed];
+    ///     n2 -> n1;
+    /// }
+    /// ";
+    ///
+    /// assert_eq!(manager.to_dot(p), expected);
+    /// ```
+    pub fn to_dot(&self, f: Bdd) -> String {
+        // The decision nodes are numbered from the root down, after the
+        // terminals
+        let mut nodes: Vec<usize> = self.reachable(&[f]).into_iter().collect();
+        nodes.sort_by_key(|&n| (self.nodes[n].level, n));
+        let mut numbers: HashMap<usize, usize> = nodes.iter()
+            .enumerate()
+            .map(|(i, &n)| (n, i + 2))
+            .collect();
+        numbers.insert(FALSE, FALSE);
+        numbers.insert(TRUE, TRUE);
+
+        let mut lines = vec!("digraph bdd {".to_string());
+
+        for &terminal in &[FALSE, TRUE] {
+            if f.index == terminal || nodes.iter().any(|&n| self.nodes[n].low == terminal || self.nodes[n].high == terminal) {
+                lines.push(format!("    n{} [label=\"{}\", shape=box];", terminal, terminal));
+            }
+        }
+        for &n in &nodes {
+            let label = escape_dot(&self.variables[self.nodes[n].level]);
+            lines.push(format!("    n{} [label=\"{}\", shape=circle];", numbers[&n], label));
+        }
+        for &n in &nodes {
+            let node = self.nodes[n];
+            lines.push(format!("    n{} -> n{} [style=dashed];", numbers[&n], numbers[&node.low]));
+            lines.push(format!("    n{} -> n{};", numbers[&n], numbers[&node.high]));
+        }
+
+        lines.push("}".to_string());
+        lines.push("".to_string());
+
+        lines.join("\n")
+    }
+
+    /// Reorders the variables by sifting, moving each variable in turn to
+    /// the position where the given diagrams have the fewest nodes.
+    ///
+    /// A variable is moved by swapping it with its neighbour one level at a
+    /// time, which rewrites the nodes of the two levels in place, so every
+    /// `Bdd` from the manager still stands for the same function afterwards.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(a /\\ b) \\/ (c /\\ d) \\/ (e /\\ f)").unwrap();
+    ///
+    /// let order = vec!("a", "c", "e", "b", "d", "f").into_iter()
+    ///     .map(|v| v.to_string())
+    ///     .collect();
+    /// let mut manager = BddManager::new(order);
+    /// let roots = vec!(Bdd::from_proposition(&mut manager, &prop));
+    ///
+    /// assert_eq!(manager.node_count(&roots), 14);
+    ///
+    /// manager.sift(&roots);
+    ///
+    /// assert_eq!(manager.node_count(&roots), 6);
+    /// ```
+    pub fn sift(&mut self, roots: &[Bdd]) {
+        // Variables with the most nodes are sifted first, since moving them
+        // is likely to make the most difference
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for n in self.reachable(roots) {
+            *counts.entry(self.nodes[n].level).or_insert(0) += 1;
+        }
+        let mut sifted = self.variables.clone();
+        sifted.sort_by_key(|v| ::std::cmp::Reverse(counts.get(&self.levels[v]).cloned().unwrap_or(0)));
+
+        let last = self.variables.len().saturating_sub(1);
+        for name in sifted {
+            let mut best = (self.node_count(roots), self.levels[&name]);
+            let mut step = |manager: &mut BddManager, level: usize, to: usize| {
+                manager.swap(level);
+                let count = manager.node_count(roots);
+                if count < best.0 {
+                    best = (count, to);
+                }
+            };
+
+            // The variable goes all the way down and then all the way up,
+            // before going back to the best level that it passed
+            while self.levels[&name] < last {
+                let level = self.levels[&name];
+                step(self, level, level + 1);
+            }
+            while self.levels[&name] > 0 {
+                let level = self.levels[&name] - 1;
+                step(self, level, level);
+            }
+            while self.levels[&name] < best.1 {
+                let level = self.levels[&name];
+                self.swap(level);
+            }
+        }
+    }
+
+    /// Swaps the variable at the given level with the one below it.
+    ///
+    /// The nodes of the lower variable move up unchanged, and the nodes of
+    /// the upper variable move down unless they depend on the lower one, in
+    /// which case they are rebuilt in place to decide on the lower variable
+    /// first, so that every node keeps standing for the same function.
+    fn swap(&mut self, level: usize) {
+        let below = level + 1;
+        let mut upper: Vec<usize> = mem::take(&mut self.unique[level]).into_values().collect();
+        let mut lower: Vec<usize> = mem::take(&mut self.unique[below]).into_values().collect();
+        upper.sort_unstable();
+        lower.sort_unstable();
+
+        // The children of the upper nodes are read before any levels change
+        let (moved, rebuilt): (Vec<usize>, Vec<usize>) = upper.into_iter()
+            .partition(|&f| self.nodes[self.nodes[f].low].level != below && self.nodes[self.nodes[f].high].level != below);
+        let cofactors: Vec<_> = rebuilt.into_iter()
+            .map(|f| (f, self.cofactors(self.nodes[f].low, below), self.cofactors(self.nodes[f].high, below)))
+            .collect();
+
+        for g in lower {
+            let node = self.nodes[g];
+            self.nodes[g].level = level;
+            self.unique[level].insert((node.low, node.high), g);
+        }
+        for f in moved {
+            let node = self.nodes[f];
+            self.nodes[f].level = below;
+            self.unique[below].insert((node.low, node.high), f);
+        }
+        for (f, (f00, f01), (f10, f11)) in cofactors {
+            let low = self.make(below, f00, f10);
+            let high = self.make(below, f01, f11);
+            self.nodes[f] = Node { level, low, high };
+            self.unique[level].insert((low, high), f);
+        }
+
+        self.variables.swap(level, below);
+        self.levels.insert(self.variables[level].clone(), level);
+        self.levels.insert(self.variables[below].clone(), below);
+    }
+
+    /// Returns the decision nodes that can be reached from the given
+    /// diagrams.
+    fn reachable(&self, roots: &[Bdd]) -> HashSet<usize> {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<usize> = roots.iter().map(|r| r.index).collect();
+        while let Some(f) = stack.pop() {
+            if f > TRUE && seen.insert(f) {
+                stack.push(self.nodes[f].low);
+                stack.push(self.nodes[f].high);
+            }
+        }
+        seen
+    }
+
+    /// Builds the diagram that is true when the sum of the coefficients of
+    /// the true operands meets the bound, deciding on one operand at a time
+    /// and sharing the diagrams for equal partial sums.
+    fn linear(&mut self, operands: &[(i64, Bdd)], comparison: Comparison, bound: i64) -> Bdd {
+        fn linear_(manager: &mut BddManager, operands: &[(i64, Bdd)], comparison: Comparison,
+                   bound: i128, sum: i128, memo: &mut HashMap<(usize, i128), usize>) -> usize {
+            let least: i128 = operands.iter().map(|&(c, _)| i128::from(c.min(0))).sum();
+            let most: i128 = operands.iter().map(|&(c, _)| i128::from(c.max(0))).sum();
+            let (low, high) = (sum + least, sum + most);
+
+            let holds = |total: i128| match comparison {
+                Comparison::AtMost => total <= bound,
+                Comparison::AtLeast => total >= bound,
+                Comparison::Exactly => total == bound,
+            };
+            if holds(low) && holds(high) {
+                return TRUE
+            }
+            match comparison {
+                Comparison::AtMost if low > bound => return FALSE,
+                Comparison::AtLeast if high < bound => return FALSE,
+                Comparison::Exactly if low > bound || high < bound => return FALSE,
+                _ => {}
+            }
+
+            let key = (operands.len(), sum);
+            if let Some(&result) = memo.get(&key) {
+                return result
+            }
+
+            let (c, p) = operands[0];
+            let taken = linear_(manager, &operands[1..], comparison, bound, sum + i128::from(c), memo);
+            let left = linear_(manager, &operands[1..], comparison, bound, sum, memo);
+            let result = manager.ite_(p.index, taken, left);
+
+            memo.insert(key, result);
+            result
+        }
+
+        Bdd { index: linear_(self, operands, comparison, i128::from(bound), 0, &mut HashMap::new()) }
+    }
+
+    /// Returns the level of the given variable, adding it to the end of the
+    /// order if it is new.
+    fn level(&mut self, name: &str) -> usize {
+        if let Some(&level) = self.levels.get(name) {
+            return level
+        }
+
+        let level = self.variables.len();
+        self.variables.push(name.to_string());
+        self.unique.push(HashMap::new());
+        self.levels.insert(name.to_string(), level);
+        level
+    }
+
+    /// Returns the level of the given node, where the terminals come after
+    /// every variable.
+    fn level_of(&self, f: usize) -> usize {
+        self.nodes[f].level.min(self.variables.len())
+    }
+
+    /// Returns the children of the given node for the variable at the given
+    /// level, which are the node itself if it does not decide on it.
+    fn cofactors(&self, f: usize, level: usize) -> (usize, usize) {
+        let node = self.nodes[f];
+        if node.level == level {
+            (node.low, node.high)
+        } else {
+            (f, f)
+        }
+    }
+
+    /// Returns the node with the given variable and children, leaving out
+    /// nodes whose children are the same and reusing an existing equal node.
+    fn make(&mut self, level: usize, low: usize, high: usize) -> usize {
+        if low == high {
+            return low
+        }
+
+        if let Some(&index) = self.unique[level].get(&(low, high)) {
+            return index
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(Node { level, low, high });
+        self.unique[level].insert((low, high), index);
+        index
+    }
+}
+
+/// Orders the terms of the given propositions by where they first appear in
+/// a depth-first walk, so that terms that are used together end up near
+/// each other in the order.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let prop = parse_proposition("(b /\\ a) \\/ (c /\\ b)").unwrap();
+///
+/// assert_eq!(variable_order(&[prop]), vec!("b", "a", "c"));
+/// ```
+pub fn variable_order(props: &[Proposition]) -> Vec<String> {
+    fn walk(prop: &Proposition, seen: &mut BTreeSet<String>, order: &mut Vec<String>) {
+        match *prop {
+            Proposition::Or(ref a, ref b)
+            | Proposition::And(ref a, ref b)
+            | Proposition::Implies(ref a, ref b)
+            | Proposition::Iff(ref a, ref b)
+            | Proposition::Xor(ref a, ref b)
+            | Proposition::Nand(ref a, ref b)
+            | Proposition::Nor(ref a, ref b) => {
+                walk(a, seen, order);
+                walk(b, seen, order);
+            },
+            Proposition::Ite(ref c, ref t, ref e) => {
+                walk(c, seen, order);
+                walk(t, seen, order);
+                walk(e, seen, order);
+            },
+            Proposition::Linear(ref terms, _, _) => {
+                for (_, p) in terms {
+                    walk(p, seen, order);
+                }
+            },
+            Proposition::Not(ref a) => walk(a, seen, order),
+            Proposition::Term(ref a) => {
+                if seen.insert(a.clone()) {
+                    order.push(a.clone());
+                }
+            },
+            Proposition::Top | Proposition::Bottom => {},
+        }
+    }
+
+    let mut seen = BTreeSet::new();
+    let mut order = vec!();
+    for p in props {
+        walk(p, &mut seen, &mut order);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use bdd::*;
+    use parser::*;
+    use random::*;
+    use sat::*;
+
+    /// Generates pseudorandom propositions over four terms from a fixed
+    /// seed, so that the tests are repeatable.
+    struct Generator {
+        random: Random
+    }
+
+    impl Generator {
+        fn proposition(&mut self, depth: usize) -> Proposition {
+            if depth == 0 {
+                return match self.random.next(10) {
+                    0 => top(),
+                    1 => bottom(),
+                    n => term(["p", "q", "r", "s"][(n % 4) as usize].to_string()),
+                }
+            }
+
+            let a = self.proposition(depth - 1);
+            let b = self.proposition(depth - 1);
+            match self.random.next(9) {
+                0 => or(a, b),
+                1 => and(a, b),
+                2 => implies(a, b),
+                3 => iff(a, b),
+                4 => xor(a, b),
+                5 => nand(a, b),
+                6 => nor(a, b),
+                7 => ite(a.clone(), b, not(a)),
+                _ => not(a),
+            }
+        }
+    }
+
+    fn brute_force_count(prop: &Proposition, variables: &[String]) -> u128 {
+        (0..(1u64 << variables.len()))
+            .filter(|bits| {
+                let assignment: HashMap<String, bool> = variables.iter()
+                    .enumerate()
+                    .map(|(i, v)| (v.clone(), bits & (1 << i) != 0))
+                    .collect();
+                prop.evaluate(&assignment) == Some(true)
+            })
+            .count() as u128
+    }
+
+    #[test]
+    fn bdd_canonical() {
+        let mut generator = Generator { random: Random::new(46) };
+        let variables: Vec<String> = vec!("p", "q", "r", "s").into_iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        let mut manager = BddManager::new(variables.clone());
+        for _ in 0..300 {
+            let prop = generator.proposition(3);
+
+            let f = Bdd::from_proposition(&mut manager, &prop);
+            let cnf = Bdd::from_proposition(&mut manager, &prop.to_cnf());
+            let back = manager.to_proposition(f);
+
+            assert_eq!(f, cnf, "{}", prop);
+            assert_eq!(Bdd::from_proposition(&mut manager, &back), f, "{} and {}", prop, back);
+            assert!(equivalent(&prop, &back), "{} and {}", prop, back);
+            assert_eq!(manager.sat_count(f), Some(brute_force_count(&prop, &variables)), "{}", prop);
+
+            let cubes = manager.cubes(f);
+            let sum: u128 = cubes.iter()
+                .map(|c| 1 << (variables.len() - c.parts.len()))
+                .sum();
+            assert_eq!(Some(sum), manager.sat_count(f), "{}", prop);
+        }
+    }
+
+    #[test]
+    fn bdd_linear() {
+        let props = vec!(
+            "[2 p - q + 3 r - s >= 1]",
+            "[p + q + r + s = 2]",
+            "[p - q <= -1]",
+            "[3 p + 2 q = 4]",
+            "[0 >= 0]",
+        );
+
+        for text in props {
+            let prop = parse_proposition(text).unwrap();
+            let mut manager = BddManager::new(vec!());
+            let f = Bdd::from_proposition(&mut manager, &prop);
+            let variables = manager.variables().to_vec();
+
+            assert_eq!(manager.sat_count(f), Some(brute_force_count(&prop, &variables)), "{}", prop);
+            assert!(equivalent(&prop, &manager.to_proposition(f)), "{}", prop);
+        }
+    }
+
+    #[test]
+    fn bdd_quantify_unknown_variable() {
+        let mut manager = BddManager::new(vec!());
+        let p = manager.variable("p");
+
+        assert_eq!(manager.exists(p, &["q".to_string()]), p);
+        assert_eq!(manager.restrict(p, "q", true), p);
+        assert_eq!(manager.exists(p, &["p".to_string()]), manager.constant(true));
+        assert_eq!(manager.forall(p, &["p".to_string()]), manager.constant(false));
+    }
+
+    #[test]
+    fn bdd_sift_keeps_functions() {
+        let props: Vec<Proposition> = vec!(
+            "(a /\\ b) \\/ (c /\\ d) \\/ (e /\\ f)",
+            "(a <-> d) /\\ (b <-> e) /\\ (c <-> f)",
+            "a <+> f",
+        ).into_iter().map(|p| parse_proposition(p).unwrap()).collect();
+
+        let order = vec!("a", "b", "c", "d", "e", "f").into_iter()
+            .map(|v| v.to_string())
+            .collect();
+        let mut manager = BddManager::new(order);
+        let roots: Vec<Bdd> = props.iter()
+            .map(|p| Bdd::from_proposition(&mut manager, p))
+            .collect();
+        let before = manager.node_count(&roots);
+
+        // A diagram that is not sifted is rewritten along with the others
+        let other = parse_proposition("(a -> c) /\\ [b + d + f <= 1]").unwrap();
+        let kept = Bdd::from_proposition(&mut manager, &other);
+        let models = manager.sat_count(kept);
+
+        manager.sift(&roots);
+
+        assert!(manager.node_count(&roots) < before);
+        for (p, &r) in props.iter().zip(&roots) {
+            assert_eq!(Bdd::from_proposition(&mut manager, p), r, "{}", p);
+        }
+        assert_eq!(Bdd::from_proposition(&mut manager, &other), kept);
+        assert_eq!(manager.sat_count(kept), models);
+    }
+
+    #[test]
+    fn bdd_sat_count_too_many_models() {
+        let variables: Vec<String> = (0..128).map(|i| format!("p{}", i)).collect();
+        let mut manager = BddManager::new(variables);
+        let p = manager.variable("p0");
+        let q = manager.variable("p127");
+
+        assert_eq!(manager.sat_count(p), Some(1 << 127));
+        assert_eq!(manager.sat_count(q), Some(1 << 127));
+        assert_eq!(manager.sat_count(manager.constant(false)), Some(0));
+        assert_eq!(manager.sat_count(manager.constant(true)), None);
+    }
+
+    #[test]
+    fn bdd_to_dot() {
+        let prop = parse_proposition("p /\\ ~q").unwrap();
+
+        let mut manager = BddManager::new(vec!());
+        let f = Bdd::from_proposition(&mut manager, &prop);
+
+        let expected = "digraph bdd {
+    n0 [label=\"0\", shape=box];
+    n1 [label=\"1\", shape=box];
+    n2 [label=\"p\", shape=circle];
+    n3 [label=\"q\", shape=circle];
+    n2 -> n0 [style=dashed];
+    n2 -> n3;
+    n3 -> n1 [style=dashed];
+    n3 -> n0;
+}
+";
+
+        assert_eq!(manager.to_dot(f), expected);
+        assert_eq!(manager.to_dot(manager.constant(true)), "digraph bdd {\n    n1 [label=\"1\", shape=box];\n}\n");
+    }
+}
@@ -0,0 +1,258 @@
+use clauses::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::HashMap;
+
+/// A system whose states are assignments to boolean variables, described by
+/// the states it starts in, the steps it can take, and a property that
+/// every state it reaches should have.
+///
+/// Each variable is given as its current name along with the name used for
+/// its value in the next state. The initial states and the property are
+/// written over the current names, and the transition relation over both.
+/// Any other terms are inputs, which can take a different value at each
+/// step.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// // A two bit counter that starts at zero and should never reach three
+/// let system = TransitionSystem {
+///     variables: vec!(
+///         ("a".to_string(), "next_a".to_string()),
+///         ("b".to_string(), "next_b".to_string())
+///     ),
+///     init: parse_proposition("~a /\\ ~b").unwrap(),
+///     transition: parse_proposition("(next_b <-> ~b) /\\ (next_a <-> (a <+> b))").unwrap(),
+///     property: parse_proposition("~(a /\\ b)").unwrap()
+/// };
+///
+/// assert_eq!(system.check(2), None);
+///
+/// let trace = system.check(5).unwrap();
+/// let values: Vec<(bool, bool)> = trace.states.iter()
+///     .map(|s| (s["a"], s["b"]))
+///     .collect();
+///
+/// assert_eq!(values, vec!((false, false), (false, true), (true, false), (true, true)));
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct TransitionSystem {
+    pub variables: Vec<(String, String)>,
+    pub init: Proposition,
+    pub transition: Proposition,
+    pub property: Proposition
+}
+
+/// A run of a transition system that reaches a state without the property.
+///
+/// There is one state for each step of the run, starting with an initial
+/// state and ending with the state that breaks the property, each giving the
+/// values of the variables by their current names. The inputs give the
+/// values of the inputs used in each state and in the step leaving it.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Trace {
+    pub states: Vec<HashMap<String, bool>>,
+    pub inputs: Vec<HashMap<String, bool>>
+}
+
+impl TransitionSystem {
+    /// Returns a proposition that holds exactly when the system can reach a
+    /// state without the property in the given number of steps.
+    ///
+    /// The terms are renamed for each step, with the variables in the `i`th
+    /// state and the inputs used in it written as `name@i`, where `name` is
+    /// the current name of a variable or the name of an input.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let system = TransitionSystem {
+    ///     variables: vec!(("x".to_string(), "next_x".to_string())),
+    ///     init: parse_proposition("~x").unwrap(),
+    ///     transition: parse_proposition("next_x <-> (x \\/ go)").unwrap(),
+    ///     property: parse_proposition("~x").unwrap()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     system.unroll(1).to_unicode(),
+    ///     "¬x@0 ∧ (x@1 ↔ x@0 ∨ go@0) ∧ ¬¬x@1"
+    /// );
+    /// ```
+    pub fn unroll(&self, steps: usize) -> Proposition {
+        let mut parts = vec!(self.at_step(&self.init, 0));
+        for i in 0..steps {
+            parts.push(self.at_step(&self.transition, i));
+        }
+        parts.push(not(self.at_step(&self.property, steps)));
+
+        and_all(parts)
+    }
+
+    /// Searches for the shortest run of at most the given number of steps
+    /// that reaches a state without the property, returning `None` if there
+    /// is no such run.
+    ///
+    /// The unrolling is checked for each number of steps in turn, reusing the
+    /// clauses of the initial states and the steps already taken, so the
+    /// first run found is as short as possible.
+    pub fn check(&self, bound: usize) -> Option<Trace> {
+        let mut clauses = Clause::from_proposition_defined(self.at_step(&self.init, 0));
+
+        for steps in 0..=bound {
+            if steps > 0 {
+                let transition = self.at_step(&self.transition, steps - 1);
+                clauses.append(&mut Clause::from_proposition_defined(transition));
+            }
+
+            let mut query = clauses.clone();
+            query.append(&mut Clause::from_proposition_defined(not(self.at_step(&self.property, steps))));
+
+            if let Some(model) = find_clause_model(&query) {
+                return Some(self.trace(&model, steps))
+            }
+        }
+
+        None
+    }
+
+    /// Reads the run with the given number of steps out of a model of the
+    /// unrolling.
+    fn trace(&self, model: &HashMap<String, bool>, steps: usize) -> Trace {
+        let value = |name: &str, i: usize| model.get(&step_name(name, i)).cloned().unwrap_or(false);
+
+        let states = (0..=steps)
+            .map(|i| self.variables.iter()
+                .map(|(current, _)| (current.clone(), value(current, i)))
+                .collect())
+            .collect();
+
+        let inputs = self.inputs();
+        let inputs = (0..=steps)
+            .map(|i| inputs.iter()
+                .map(|input| (input.clone(), value(input, i)))
+                .collect())
+            .collect();
+
+        Trace { states, inputs }
+    }
+
+    /// Returns the terms of the system that are not variables.
+    fn inputs(&self) -> Vec<String> {
+        let mut inputs: Vec<String> = self.init.terms().into_iter()
+            .chain(self.transition.terms())
+            .chain(self.property.terms())
+            .filter(|t| !self.variables.iter().any(|(c, n)| t == c || t == n))
+            .collect();
+
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    }
+
+    /// Renames the terms of the given proposition for the given step, so that
+    /// current names refer to that step and next names to the one after it.
+    fn at_step(&self, prop: &Proposition, step: usize) -> Proposition {
+        let names: HashMap<&str, String> = self.variables.iter()
+            .flat_map(|(current, next)| vec!(
+                (current.as_str(), step_name(current, step)),
+                (next.as_str(), step_name(current, step + 1))
+            ))
+            .collect();
+
+        prop.map_terms(&|t| term(names.get(t).cloned().unwrap_or_else(|| step_name(t, step))))
+    }
+}
+
+/// Returns the name of the given variable or input at the given step.
+fn step_name(name: &str, step: usize) -> String {
+    format!("{}@{}", name, step)
+}
+
+#[cfg(test)]
+mod tests {
+    use bmc::*;
+    use parser::*;
+
+    fn variables(names: &[&str]) -> Vec<(String, String)> {
+        names.iter()
+            .map(|n| (n.to_string(), format!("next_{}", n)))
+            .collect()
+    }
+
+    #[test]
+    fn check_three_bit_counter() {
+        let system = TransitionSystem {
+            variables: variables(&["b0", "b1", "b2"]),
+            init: parse_proposition("~b0 /\\ ~b1 /\\ ~b2").unwrap(),
+            transition: parse_proposition("\
+                (next_b0 <-> ~b0) /\\ \
+                (next_b1 <-> (b1 <+> b0)) /\\ \
+                (next_b2 <-> (b2 <+> (b1 /\\ b0)))").unwrap(),
+            property: parse_proposition("~(b0 /\\ b1 /\\ b2)").unwrap()
+        };
+
+        assert_eq!(system.check(6), None);
+
+        let trace = system.check(10).unwrap();
+        assert_eq!(trace.states.len(), 8);
+        for (i, state) in trace.states.iter().enumerate() {
+            let value = (0..3).filter(|&b| state[&format!("b{}", b)]).map(|b| 1 << b).sum::<usize>();
+            assert_eq!(value, i);
+        }
+        assert!(trace.inputs.iter().all(|i| i.is_empty()));
+    }
+
+    #[test]
+    fn check_inputs() {
+        let system = TransitionSystem {
+            variables: variables(&["x", "y"]),
+            init: parse_proposition("~x /\\ ~y").unwrap(),
+            transition: parse_proposition("(next_x <-> (x \\/ go)) /\\ (next_y <-> (x /\\ ~go))").unwrap(),
+            property: parse_proposition("~y").unwrap()
+        };
+
+        let trace = system.check(5).unwrap();
+
+        assert_eq!(trace.states.len(), 3);
+        assert!(trace.inputs[0]["go"]);
+        assert!(!trace.inputs[1]["go"]);
+        assert!(trace.states[2]["y"]);
+    }
+
+    #[test]
+    fn check_safe_system() {
+        // Two processes that take turns entering their critical sections
+        let system = TransitionSystem {
+            variables: variables(&["turn", "a", "b"]),
+            init: parse_proposition("~turn /\\ ~a /\\ ~b").unwrap(),
+            transition: parse_proposition("\
+                (next_a <-> (~turn /\\ want_a)) /\\ \
+                (next_b <-> (turn /\\ want_b)) /\\ \
+                (next_turn <-> ~turn)").unwrap(),
+            property: parse_proposition("~(a /\\ b)").unwrap()
+        };
+
+        assert_eq!(system.check(8), None);
+    }
+
+    #[test]
+    fn check_initial_violation() {
+        let system = TransitionSystem {
+            variables: variables(&["x"]),
+            init: parse_proposition("x \\/ ~x").unwrap(),
+            transition: parse_proposition("next_x <-> x").unwrap(),
+            property: parse_proposition("x").unwrap()
+        };
+
+        let trace = system.check(0).unwrap();
+
+        assert_eq!(trace.states.len(), 1);
+        assert!(!trace.states[0]["x"]);
+    }
+}
@@ -0,0 +1,955 @@
+use clauses::*;
+use maxsat::*;
+use parser::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// A combinational circuit, made up of named gates that each compute a
+/// proposition over the inputs and other gates.
+///
+/// The gates must not form a loop, but they can be in any order. Each
+/// output is a proposition over the inputs and gates, and the clauses of the
+/// circuit give each gate and output a term named after it.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let circuit = parse_blif("\
+/// .model half_adder
+/// .inputs a b
+/// .outputs sum carry
+/// .names a b sum
+/// 10 1
+/// 01 1
+/// .names a b carry
+/// 11 1
+/// .end
+/// ").unwrap();
+///
+/// let outputs = circuit.output_propositions();
+///
+/// assert_eq!(circuit.inputs, vec!("a", "b"));
+/// assert!(equivalent(&outputs[0], &parse_proposition("a <+> b").unwrap()));
+/// assert!(equivalent(&outputs[1], &parse_proposition("a /\\ b").unwrap()));
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Circuit {
+    pub inputs: Vec<String>,
+    pub gates: Vec<(String, Proposition)>,
+    pub outputs: Vec<(String, Proposition)>
+}
+
+/// An error encountered while building the miter of two circuits that do
+/// not fit together.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct MiterError {
+    pub message: String
+}
+
+impl fmt::Display for MiterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for MiterError {}
+
+impl Circuit {
+    /// Returns a proposition over the inputs for each output, with the
+    /// definition of every gate written out in place.
+    ///
+    /// A gate that feeds into several others is copied into each of them,
+    /// so the propositions can be exponentially larger than the circuit, in
+    /// which case `to_clauses` should be used instead.
+    pub fn output_propositions(&self) -> Vec<Proposition> {
+        fn expand(circuit: &Circuit, prop: &Proposition, done: &mut HashMap<String, Proposition>) -> Proposition {
+            for t in prop.terms() {
+                if done.contains_key(&t) {
+                    continue
+                }
+                if let Some((_, definition)) = circuit.gates.iter().find(|g| g.0 == t) {
+                    let expanded = expand(circuit, definition, done);
+                    done.insert(t, expanded);
+                }
+            }
+
+            prop.map_terms(&|t| done.get(t).cloned().unwrap_or_else(|| term(t.to_string())))
+        }
+
+        let mut done = HashMap::new();
+        self.outputs.iter()
+            .map(|(_, o)| expand(self, o, &mut done))
+            .collect()
+    }
+
+    /// Converts the circuit into clauses, with a term for each gate and
+    /// output that is defined to equal its proposition. The models of the
+    /// clauses are exactly the ways that the whole circuit can be evaluated.
+    ///
+    /// A gate whose operands are all literals is defined directly, and any
+    /// other gate is split up with a new term for each connective, named
+    /// after the gate, so the clauses grow linearly with the circuit.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let circuit = parse_aiger(b"aag 3 2 0 1 1\n2\n4\n6\n6 2 5\no0 gt\n").unwrap();
+    ///
+    /// let clauses = circuit.to_clauses();
+    /// let models: Vec<HashMap<String, bool>> = enumerate_models(&clauses).collect();
+    ///
+    /// assert_eq!(models.len(), 4);
+    /// for m in models {
+    ///     assert_eq!(m["gt"], m["i0"] && !m["i1"]);
+    /// }
+    /// # use std::collections::HashMap;
+    /// ```
+    pub fn to_clauses(&self) -> Vec<Clause> {
+        self.gates.iter()
+            .chain(&self.outputs)
+            .filter(|&(name, prop)| *prop != term(name.clone()))
+            .flat_map(|(name, prop)| {
+                let definition = iff(term(name.clone()), prop.clone());
+                if prop.operands().iter().all(|o| is_literal(o)) {
+                    Clause::from_proposition(definition)
+                } else {
+                    Clause::from_proposition_defined(definition)
+                }
+            })
+            .collect()
+    }
+
+    /// Builds the miter of the two circuits, which has a single output that
+    /// is true exactly when some pair of their outputs differ.
+    ///
+    /// The inputs are matched up by name, and the outputs by their position.
+    /// The gates of the two circuits are renamed to `{left name}` and
+    /// `{right name}`, so that they never clash.
+    pub fn miter(&self, other: &Circuit) -> Result<Circuit, MiterError> {
+        if self.outputs.len() != other.outputs.len() {
+            return Err(MiterError {
+                message: format!(
+                    "The circuits have different numbers of outputs, {} and {}",
+                    self.outputs.len(),
+                    other.outputs.len()
+                )
+            })
+        }
+
+        let mut inputs = self.inputs.clone();
+        for i in &other.inputs {
+            if !inputs.contains(i) {
+                inputs.push(i.clone());
+            }
+        }
+
+        let mut gates = vec!();
+        let mut sides = vec!();
+        for (circuit, side) in &[(self, "left"), (other, "right")] {
+            let internal: Vec<&String> = circuit.gates.iter().map(|(name, _)| name).collect();
+            let rename = |prop: &Proposition| prop.map_terms(&|t| if internal.iter().any(|n| *n == t) {
+                term(format!("{{{} {}}}", side, t))
+            } else {
+                term(t.to_string())
+            });
+
+            gates.extend(circuit.gates.iter()
+                .map(|(name, prop)| (format!("{{{} {}}}", side, name), rename(prop))));
+            sides.push(circuit.outputs.iter()
+                .map(|(_, prop)| rename(prop))
+                .collect::<Vec<Proposition>>());
+        }
+
+        let differences = sides[0].iter()
+            .zip(&sides[1])
+            .map(|(a, b)| xor(a.clone(), b.clone()))
+            .collect();
+
+        Ok(Circuit {
+            inputs,
+            gates,
+            outputs: vec!(("{miter}".to_string(), or_all(differences)))
+        })
+    }
+
+    /// Checks whether the two circuits compute the same outputs for every
+    /// input, returning values of the inputs for which some pair of outputs
+    /// differ if they do not.
+    ///
+    /// The check looks for a model of the clauses of the miter in which its
+    /// output is true. Inputs that only one circuit has are included, and
+    /// can make the circuits differ.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let and_gate = parse_aiger(b"aag 3 2 0 1 1\n2\n4\n6\n6 2 4\n").unwrap();
+    /// let nor_of_negations = parse_blif("\
+    /// .inputs i0 i1
+    /// .outputs o
+    /// .names i0 i1 o
+    /// 0- 0
+    /// -0 0
+    /// ").unwrap();
+    /// let or_gate = parse_aiger(b"aag 3 2 0 1 1\n2\n4\n7\n6 3 5\n").unwrap();
+    ///
+    /// assert_eq!(and_gate.find_difference(&nor_of_negations), Ok(None));
+    ///
+    /// let inputs = and_gate.find_difference(&or_gate).unwrap().unwrap();
+    /// assert!(inputs["i0"] != inputs["i1"]);
+    /// ```
+    pub fn find_difference(&self, other: &Circuit) -> Result<Option<HashMap<String, bool>>, MiterError> {
+        let miter = self.miter(other)?;
+
+        let mut clauses = miter.to_clauses();
+        clauses.push(Clause { parts: vec!(ClausePart::Term("{miter}".to_string())) });
+
+        Ok(find_clause_model(&clauses).map(|model| miter.inputs.iter()
+            .map(|i| (i.clone(), model.get(i).cloned().unwrap_or(false)))
+            .collect()))
+    }
+}
+
+/// Parses a combinational circuit in the AIGER format, in either its ASCII
+/// form, starting with `aag`, or its binary form, starting with `aig`.
+///
+/// Inputs and outputs are named by the symbol table if it names them, and
+/// are otherwise named `i0`, `i1`, ... and `o0`, `o1`, ... by their position.
+/// Each AND gate is named `{and v}` after its variable `v`. Circuits with
+/// latches, or with the bad state, constraint, justice or fairness sections
+/// of AIGER 1.9, are not combinational and are rejected.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let ascii = parse_aiger(b"aag 3 2 0 1 1\n2\n4\n6\n6 4 2\ni0 x\ni1 y\n").unwrap();
+/// let binary = parse_aiger(b"aig 3 2 0 1 1\n6\n\x02\x02i0 x\ni1 y\n").unwrap();
+///
+/// assert_eq!(ascii, binary);
+/// assert_eq!(ascii.inputs, vec!("x", "y"));
+/// assert_eq!(ascii.gates[0].1.to_string(), "y /\\ x");
+/// ```
+pub fn parse_aiger(input: &[u8]) -> Result<Circuit, ParseError> {
+    let mut reader = Reader { input, position: 0, line: 1 };
+
+    let (line, header) = reader.next_line()?;
+    let words = split_words(header);
+    let error = |column: usize, message: &str| ParseError {
+        message: message.to_string(),
+        line,
+        column
+    };
+
+    let binary = match words.first().map(|w| w.1) {
+        Some("aag") => false,
+        Some("aig") => true,
+        _ => return Err(error(1, "Expected \"aag\" or \"aig\""))
+    };
+    if words.len() < 6 {
+        return Err(error(header.len() + 1, "Expected the header to give M I L O A"))
+    }
+    let mut counts = vec!();
+    for &(column, w) in &words[1..] {
+        match w.parse::<u64>() {
+            Ok(n) => counts.push(n),
+            Err(_) => return Err(error(column, "Expected a number"))
+        }
+    }
+    let (maximum, input_count, latches, output_count, and_count) =
+        (counts[0], counts[1], counts[2], counts[3], counts[4]);
+    if latches > 0 {
+        return Err(error(words[3].0, "Latches are not supported, since the circuit must be combinational"))
+    }
+    if let Some(i) = counts[5..].iter().position(|&n| n > 0) {
+        return Err(error(words[i + 6].0, "Only inputs, outputs and AND gates are supported"))
+    }
+    if input_count + and_count > maximum {
+        return Err(error(words[1].0, "Expected M to be at least I + A"))
+    }
+
+    // Each variable is either an input or a gate, and is named by its
+    // position among them
+    let mut variables: HashMap<u64, Variable> = HashMap::new();
+
+    let mut inputs = vec!();
+    for i in 0..input_count {
+        let literal = if binary {
+            2 * (i + 1)
+        } else {
+            let (line, text) = reader.next_line()?;
+            parse_literal(text, line, maximum)?.0
+        };
+        if literal < 2 || literal % 2 == 1 || variables.contains_key(&(literal / 2)) {
+            return Err(ParseError {
+                message: format!("Expected a new variable for input {}", i),
+                line: reader.line - 1,
+                column: 1
+            })
+        }
+        variables.insert(literal / 2, Variable::Input(i as usize));
+        inputs.push(format!("i{}", i));
+    }
+
+    let mut outputs = vec!();
+    for i in 0..output_count {
+        let (line, text) = reader.next_line()?;
+        let (literal, column) = parse_literal(text, line, maximum)?;
+        outputs.push((format!("o{}", i), literal, line, column));
+    }
+
+    let mut ands = vec!();
+    for i in 0..and_count {
+        let (lhs, rhs0, rhs1, line, columns) = if binary {
+            let line = reader.line;
+            let lhs = 2 * (input_count + i + 1);
+            let delta0 = reader.next_number()?;
+            let delta1 = reader.next_number()?;
+            let rhs0 = lhs.checked_sub(delta0).filter(|&r| r < lhs);
+            let rhs1 = rhs0.and_then(|r| r.checked_sub(delta1));
+            match (rhs0, rhs1) {
+                (Some(a), Some(b)) => (lhs, a, b, line, [1, 1, 1]),
+                _ => return Err(ParseError {
+                    message: format!("Expected AND gate {} to refer to earlier literals", i),
+                    line,
+                    column: 1
+                })
+            }
+        } else {
+            let (line, text) = reader.next_line()?;
+            let words = split_words(text);
+            if words.len() != 3 {
+                return Err(ParseError {
+                    message: "Expected an AND gate to have three literals".to_string(),
+                    line,
+                    column: 1
+                })
+            }
+            let mut literals = vec!();
+            for &(column, w) in &words {
+                literals.push(parse_literal(w, line, maximum).map_err(|e| ParseError { column, ..e })?.0);
+            }
+            (literals[0], literals[1], literals[2], line, [words[0].0, words[1].0, words[2].0])
+        };
+
+        if lhs < 2 || lhs % 2 == 1 || variables.contains_key(&(lhs / 2)) {
+            return Err(ParseError {
+                message: format!("Expected a new variable for AND gate {}", i),
+                line,
+                column: columns[0]
+            })
+        }
+        variables.insert(lhs / 2, Variable::Gate);
+        ands.push((lhs, [(rhs0, columns[1]), (rhs1, columns[2])], line));
+    }
+
+    // The symbol table names inputs and outputs, and ends at a comment
+    while let Some((line, text)) = reader.next_symbol_line() {
+        if text == "c" {
+            break
+        }
+        let error = |column: usize, message: &str| ParseError {
+            message: message.to_string(),
+            line,
+            column
+        };
+
+        let (kind, rest) = text.split_at(text.chars().next().map_or(0, |c| c.len_utf8()));
+        let (index, name) = match rest.find(' ') {
+            Some(space) => (&rest[..space], &rest[space + 1..]),
+            None => return Err(error(1, "Expected a symbol"))
+        };
+        let index = match index.parse::<usize>() {
+            Ok(i) => i,
+            Err(_) => return Err(error(2, "Expected the position of the symbol"))
+        };
+        if name.is_empty() {
+            return Err(error(rest.len() + 2, "Expected the name of the symbol"))
+        }
+
+        match kind {
+            "i" if index < inputs.len() => inputs[index] = name.to_string(),
+            "o" if index < outputs.len() => outputs[index].0 = name.to_string(),
+            "i" | "o" => return Err(error(2, "Expected the position of an existing input or output")),
+            _ => return Err(error(1, "Expected a symbol for an input or output"))
+        }
+    }
+
+    let literal = |l: u64, line: usize, column: usize| -> Result<Proposition, ParseError> {
+        let positive = match (l / 2, variables.get(&(l / 2))) {
+            (0, _) => bottom(),
+            (_, Some(&Variable::Input(i))) => term(inputs[i].clone()),
+            (v, Some(&Variable::Gate)) => term(format!("{{and {}}}", v)),
+            (_, None) => return Err(ParseError {
+                message: format!("Literal {} refers to an undefined variable", l),
+                line,
+                column
+            })
+        };
+        Ok(if l % 2 == 1 { negate(positive) } else { positive })
+    };
+
+    let mut gates = vec!();
+    let mut lines = vec!();
+    for (lhs, [(rhs0, column0), (rhs1, column1)], line) in ands {
+        let a = literal(rhs0, line, column0)?;
+        let b = literal(rhs1, line, column1)?;
+        gates.push((format!("{{and {}}}", lhs / 2), and(a, b)));
+        lines.push(line);
+    }
+
+    let mut named_outputs = vec!();
+    for (name, l, line, column) in outputs {
+        named_outputs.push((name, literal(l, line, column)?));
+    }
+
+    let circuit = Circuit { inputs, gates, outputs: named_outputs };
+    if let Some(i) = find_loop(&circuit) {
+        return Err(ParseError {
+            message: "The AND gates form a loop".to_string(),
+            line: lines[i],
+            column: 1
+        })
+    }
+    Ok(circuit)
+}
+
+/// Parses a combinational circuit from a BLIF netlist.
+///
+/// The `.model`, `.inputs`, `.outputs`, `.names` and `.end` commands are
+/// supported, where each `.names` gate is given by the rows of its cover,
+/// either all with the output `1` for when the gate is true or all with the
+/// output `0` for when it is false. Lines can be continued with `\` and
+/// comments start with `#`. Each gate is named after the net it drives.
+pub fn parse_blif(input: &str) -> Result<Circuit, ParseError> {
+    let mut circuit = Circuit { inputs: vec!(), gates: vec!(), outputs: vec!() };
+    let mut lines: Vec<(usize, String)> = vec!();
+
+    // Continued lines are joined onto the line they start on
+    let mut continued = false;
+    for (i, text) in input.lines().enumerate() {
+        let text = text.split('#').next().unwrap_or("");
+        let (text, continues) = match text.trim_end().strip_suffix('\\') {
+            Some(t) => (t, true),
+            None => (text, false)
+        };
+        match (continued, lines.last_mut()) {
+            (true, Some(last)) => {
+                last.1.push(' ');
+                last.1.push_str(text);
+            },
+            _ => lines.push((i + 1, text.to_string()))
+        }
+        continued = continues;
+    }
+
+    // Each gate is its input nets followed by its output net, and the rows
+    // of its cover
+    let mut gates: Vec<(Vec<String>, Vec<Row>)> = vec!();
+    let mut output_lines = vec!();
+    let mut definitions: HashMap<String, usize> = HashMap::new();
+
+    for (line, text) in lines {
+        let words = split_words(&text);
+        let error = |column: usize, message: String| ParseError { message, line, column };
+
+        let (column, command) = match words.first() {
+            Some(&w) => w,
+            None => continue
+        };
+        let names = || words[1..].iter().map(|w| w.1.to_string());
+
+        if !command.starts_with('.') {
+            let (_, rows) = match gates.last_mut() {
+                Some(g) => g,
+                None => return Err(error(column, "Expected a command".to_string()))
+            };
+            let row = match words.len() {
+                1 => Row {
+                    line,
+                    column,
+                    pattern: String::new(),
+                    output_column: column,
+                    output: command.to_string()
+                },
+                2 => Row {
+                    line,
+                    column,
+                    pattern: command.to_string(),
+                    output_column: words[1].0,
+                    output: words[1].1.to_string()
+                },
+                _ => return Err(error(words[2].0, "Expected a row to have an input part and an output".to_string()))
+            };
+            rows.push(row);
+            continue
+        }
+
+        match command {
+            ".model" => {},
+            ".inputs" => for n in names() {
+                if definitions.insert(n.clone(), line).is_some() {
+                    return Err(error(column, format!("The net {} is defined more than once", n)))
+                }
+                circuit.inputs.push(n);
+            },
+            ".outputs" => for n in names() {
+                output_lines.push(line);
+                circuit.outputs.push((n.clone(), term(n)));
+            },
+            ".names" => {
+                let mut nets: Vec<String> = names().collect();
+                let output = match nets.pop() {
+                    Some(o) => o,
+                    None => return Err(error(column, "Expected .names to name its output".to_string()))
+                };
+                if definitions.insert(output.clone(), line).is_some() {
+                    return Err(error(words[words.len() - 1].0, format!("The net {} is defined more than once", output)))
+                }
+                nets.push(output);
+                gates.push((nets, vec!()));
+            },
+            ".end" => break,
+            c => return Err(error(column, format!("The command {} is not supported", c)))
+        }
+    }
+
+    for (mut nets, rows) in gates {
+        let output = nets.pop().unwrap_or_default();
+        let inputs: Vec<Proposition> = nets.iter().map(|n| term(n.clone())).collect();
+
+        let mut value = None;
+        let mut cubes = vec!();
+        for Row { line, column, pattern, output_column, output: row_output } in rows {
+            let error = |column: usize, message: &str| ParseError {
+                message: message.to_string(),
+                line,
+                column
+            };
+
+            if pattern.chars().count() != inputs.len() {
+                return Err(error(column, "Expected a row to have a character for each input"))
+            }
+            let row_value = match row_output.as_str() {
+                "1" => true,
+                "0" => false,
+                _ => return Err(error(output_column, "Expected the output of a row to be 0 or 1"))
+            };
+            if value.is_some_and(|v| v != row_value) {
+                return Err(error(column, "Expected every row of a cover to have the same output"))
+            }
+            value = Some(row_value);
+
+            let mut literals = vec!();
+            for (c, input) in pattern.chars().zip(&inputs) {
+                match c {
+                    '1' => literals.push(input.clone()),
+                    '0' => literals.push(not(input.clone())),
+                    '-' => {},
+                    _ => return Err(error(column, "Expected a row to only use 0, 1 and -"))
+                }
+            }
+            cubes.push(and_all(literals));
+        }
+
+        let cover = or_all(cubes);
+        circuit.gates.push((output, if value == Some(false) { not(cover) } else { cover }));
+    }
+
+    let undefined = |prop: &Proposition| prop.terms().into_iter()
+        .find(|t| !definitions.contains_key(t));
+    for (i, (_, prop)) in circuit.outputs.iter().enumerate() {
+        if let Some(t) = undefined(prop) {
+            return Err(ParseError {
+                message: format!("The net {} is never defined", t),
+                line: output_lines[i],
+                column: 1
+            })
+        }
+    }
+    for (name, prop) in &circuit.gates {
+        if let Some(t) = undefined(prop) {
+            return Err(ParseError {
+                message: format!("The net {} is never defined", t),
+                line: definitions[name],
+                column: 1
+            })
+        }
+    }
+
+    if let Some(i) = find_loop(&circuit) {
+        return Err(ParseError {
+            message: "The gates form a loop".to_string(),
+            line: definitions[&circuit.gates[i].0],
+            column: 1
+        })
+    }
+    Ok(circuit)
+}
+
+/// A row of the cover of a BLIF gate, along with where it and its output
+/// start.
+struct Row {
+    line: usize,
+    column: usize,
+    pattern: String,
+    output_column: usize,
+    output: String
+}
+
+/// What a variable of an AIGER file stands for.
+enum Variable {
+    Input(usize),
+    Gate
+}
+
+/// Reads the lines and binary numbers of an AIGER file, keeping track of
+/// the line that it is on.
+struct Reader<'a> {
+    input: &'a [u8],
+    position: usize,
+    line: usize
+}
+
+impl<'a> Reader<'a> {
+    /// Returns the next line along with its number, which must be text.
+    fn next_line(&mut self) -> Result<(usize, &'a str), ParseError> {
+        let line = self.line;
+        let error = |message: &str| ParseError { message: message.to_string(), line, column: 1 };
+
+        if self.position >= self.input.len() {
+            return Err(error("Unexpected end of the file"))
+        }
+
+        let rest = &self.input[self.position..];
+        let end = rest.iter().position(|&b| b == b'\n').unwrap_or(rest.len());
+        self.position += (end + 1).min(rest.len());
+        self.line += 1;
+
+        ::std::str::from_utf8(&rest[..end])
+            .map(|text| (line, text.trim_end_matches('\r')))
+            .map_err(|_| error("Expected a line of text"))
+    }
+
+    /// Returns the next line of the symbol table, if there is one.
+    fn next_symbol_line(&mut self) -> Option<(usize, &'a str)> {
+        if self.position >= self.input.len() {
+            return None
+        }
+        self.next_line().ok()
+    }
+
+    /// Reads a number written in the binary form of AIGER, seven bits at a
+    /// time with the high bit set on every byte but the last.
+    fn next_number(&mut self) -> Result<u64, ParseError> {
+        let mut number: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = match self.input.get(self.position) {
+                Some(&b) => b,
+                None => return Err(ParseError {
+                    message: "Unexpected end of the AND gates".to_string(),
+                    line: self.line,
+                    column: 1
+                })
+            };
+            self.position += 1;
+            if byte == b'\n' {
+                self.line += 1;
+            }
+
+            let bits = u64::from(byte & 0x7f);
+            if shift >= 64 || (shift > 0 && bits >> (64 - shift) != 0) {
+                return Err(ParseError {
+                    message: "Expected a number that fits in 64 bits".to_string(),
+                    line: self.line,
+                    column: 1
+                })
+            }
+            number |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(number)
+            }
+            shift += 7;
+        }
+    }
+}
+
+/// Parses a line holding a single literal, returning it with its column.
+fn parse_literal(text: &str, line: usize, maximum: u64) -> Result<(u64, usize), ParseError> {
+    let words = split_words(text);
+    let (column, word) = match words[..] {
+        [w] => w,
+        _ => return Err(ParseError {
+            message: "Expected a single literal".to_string(),
+            line,
+            column: 1
+        })
+    };
+
+    match word.parse::<u64>() {
+        Ok(l) if l / 2 <= maximum => Ok((l, column)),
+        _ => Err(ParseError {
+            message: "Expected a literal no larger than 2M + 1".to_string(),
+            line,
+            column
+        })
+    }
+}
+
+/// Checks whether the given proposition is a constant, a term or a negated
+/// term.
+fn is_literal(prop: &Proposition) -> bool {
+    match *prop {
+        Proposition::Not(ref a) => a.operands().is_empty(),
+        _ => prop.operands().is_empty()
+    }
+}
+
+/// Returns the negation of the given term or constant.
+fn negate(prop: Proposition) -> Proposition {
+    match prop {
+        Proposition::Bottom => top(),
+        p => not(p)
+    }
+}
+
+/// Returns the position of a gate that depends on itself through the other
+/// gates, if there is one.
+fn find_loop(circuit: &Circuit) -> Option<usize> {
+    let positions: HashMap<&str, usize> = circuit.gates.iter()
+        .enumerate()
+        .map(|(i, (name, _))| (name.as_str(), i))
+        .collect();
+
+    // Each gate is unvisited, being visited, or finished
+    let mut states = vec!(0; circuit.gates.len());
+    for start in 0..circuit.gates.len() {
+        if states[start] != 0 {
+            continue
+        }
+
+        let mut stack = vec!((start, circuit.gates[start].1.terms(), 0));
+        states[start] = 1;
+        while let Some(&mut (gate, ref operands, ref mut next)) = stack.last_mut() {
+            if *next == operands.len() {
+                states[gate] = 2;
+                stack.pop();
+                continue
+            }
+
+            let operand = positions.get(operands[*next].as_str()).cloned();
+            *next += 1;
+            match operand.map(|o| (o, states[o])) {
+                Some((o, 1)) => return Some(o),
+                Some((o, 0)) => {
+                    states[o] = 1;
+                    stack.push((o, circuit.gates[o].1.terms(), 0));
+                },
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use circuits::*;
+
+    const FULL_ADDER_BLIF: &str = "\
+# A full adder
+.model full_adder
+.inputs a b \\
+    cin
+.outputs sum cout
+.names a b t
+10 1
+01 1
+.names t cin sum
+10 1
+01 1
+.names a b cin cout
+11- 1
+1-1 1
+-11 1
+.end
+";
+
+    /// The same full adder as an AIGER file, with the inputs a, b and cin as
+    /// the variables 1, 2 and 3.
+    const FULL_ADDER_AAG: &str = "\
+aag 10 3 0 2 7
+2
+4
+6
+18
+21
+8 2 4
+10 3 5
+12 9 11
+14 12 6
+16 13 7
+18 15 17
+20 9 15
+i0 a
+i1 b
+i2 cin
+o0 sum
+o1 cout
+c
+made by hand
+";
+
+    #[test]
+    fn full_adders_equivalent() {
+        let blif = parse_blif(FULL_ADDER_BLIF).unwrap();
+        let aiger = parse_aiger(FULL_ADDER_AAG.as_bytes()).unwrap();
+
+        assert_eq!(blif.inputs, vec!("a", "b", "cin"));
+        assert_eq!(aiger.inputs, blif.inputs);
+        assert_eq!(aiger.outputs[0].0, "sum");
+
+        let expected = vec!(
+            parse_proposition("a <+> b <+> cin").unwrap(),
+            parse_proposition("[a + b + cin >= 2]").unwrap()
+        );
+        for circuit in &[&blif, &aiger] {
+            for (o, e) in circuit.output_propositions().iter().zip(&expected) {
+                assert!(equivalent(o, e), "{} and {}", o, e);
+            }
+        }
+
+        assert_eq!(blif.find_difference(&aiger), Ok(None));
+        assert_eq!(aiger.find_difference(&blif), Ok(None));
+    }
+
+    #[test]
+    fn find_difference_distinguishes() {
+        // The carry is wrongly computed as a /\ b
+        let broken = parse_blif("\
+.inputs a b cin
+.outputs sum cout
+.names a b cin sum
+100 1
+010 1
+001 1
+111 1
+.names a b cout
+11 1
+").unwrap();
+        let aiger = parse_aiger(FULL_ADDER_AAG.as_bytes()).unwrap();
+
+        let inputs = aiger.find_difference(&broken).unwrap().unwrap();
+
+        let values = |c: &Circuit| -> Vec<Option<bool>> {
+            c.output_propositions().iter().map(|o| o.evaluate(&inputs)).collect()
+        };
+        assert_ne!(values(&aiger), values(&broken));
+        assert!(inputs["cin"] && (inputs["a"] != inputs["b"]));
+    }
+
+    #[test]
+    fn find_difference_output_counts() {
+        let one = parse_aiger(b"aag 1 1 0 1 0\n2\n2\n").unwrap();
+        let two = parse_aiger(b"aag 1 1 0 2 0\n2\n2\n3\n").unwrap();
+
+        let expected = MiterError {
+            message: "The circuits have different numbers of outputs, 1 and 2".to_string()
+        };
+        assert_eq!(one.find_difference(&two), Err(expected));
+    }
+
+    #[test]
+    fn parse_aiger_constants() {
+        let circuit = parse_aiger(b"aag 1 1 0 3 0\n2\n0\n1\n3\n").unwrap();
+
+        assert_eq!(circuit.outputs[0].1, bottom());
+        assert_eq!(circuit.outputs[1].1, top());
+        assert_eq!(circuit.outputs[2].1, not(term("i0".to_string())));
+    }
+
+    #[test]
+    fn parse_aiger_binary_long_deltas() {
+        // The only gate is 202 = 2 /\ 2, so its first delta is 200, which
+        // takes two bytes
+        let mut input = b"aig 101 100 0 1 1\n202\n".to_vec();
+        input.extend(&[0xc8, 0x01, 0x00]);
+
+        let circuit = parse_aiger(&input).unwrap();
+
+        assert_eq!(circuit.inputs.len(), 100);
+        assert_eq!(circuit.gates, vec!(("{and 101}".to_string(), and(term("i0".to_string()), term("i0".to_string())))));
+    }
+
+    #[test]
+    fn parse_aiger_errors() {
+        let cases: Vec<(&[u8], usize, usize, &str)> = vec!(
+            (b"aiger 1 1 0 0 0\n2\n", 1, 1, "Expected \"aag\" or \"aig\""),
+            (b"aag 1 1 0 0\n2\n", 1, 12, "Expected the header to give M I L O A"),
+            (b"aag 1 0 1 0 0\n2 3\n", 1, 9, "Latches are not supported, since the circuit must be combinational"),
+            (b"aag 1 1 0 0 0 1\n2\n", 1, 15, "Only inputs, outputs and AND gates are supported"),
+            (b"aag 2 1 0 1 0\n2\n4\n", 3, 1, "Literal 4 refers to an undefined variable"),
+            (b"aag 1 1 0 1 0\n2\n9\n", 3, 1, "Expected a literal no larger than 2M + 1"),
+            (b"aag 3 1 0 1 2\n2\n4\n4 6 2\n6 4 2\n", 4, 1, "The AND gates form a loop"),
+            (b"aag 2 1 0 1 1\n2\n4\n4 2\n", 4, 1, "Expected an AND gate to have three literals"),
+            (b"aag 1 1 0 0 0\n2\no0 x\n", 3, 2, "Expected the position of an existing input or output"),
+            (b"aig 2 1 0 1 1\n4\n\x80", 3, 1, "Unexpected end of the AND gates"),
+            (b"aag 1 1 0 1 0\n2\n", 3, 1, "Unexpected end of the file"),
+        );
+
+        for (input, line, column, message) in cases {
+            let expected = ParseError {
+                message: message.to_string(),
+                line,
+                column
+            };
+
+            assert_eq!(parse_aiger(input), Err(expected), "{:?}", String::from_utf8_lossy(input));
+        }
+    }
+
+    #[test]
+    fn parse_blif_errors() {
+        let cases = vec!(
+            (".inputs a\n.outputs b\n", 2, 1, "The net b is never defined"),
+            (".inputs a\n.outputs b\n.names a c b\n11 1\n", 3, 1, "The net c is never defined"),
+            (".inputs a\n.outputs b\n.names a b\n2 1\n", 4, 1, "Expected a row to only use 0, 1 and -"),
+            (".inputs a\n.outputs b\n.names a b\n1 1\n0 0\n", 5, 1, "Expected every row of a cover to have the same output"),
+            (".inputs a\n.outputs b\n.names a b\n11 1\n", 4, 1, "Expected a row to have a character for each input"),
+            (".inputs a\n.outputs b\n.names a b\n1 x\n", 4, 3, "Expected the output of a row to be 0 or 1"),
+            (".inputs a\n.outputs b\n.names a b\n.names b a\n", 4, 10, "The net a is defined more than once"),
+            (".inputs a\n.outputs b\n.latch a b\n", 3, 1, "The command .latch is not supported"),
+            ("11 1\n", 1, 1, "Expected a command"),
+            (".outputs b\n.names c b\n1 1\n.names b c\n1 1\n", 2, 1, "The gates form a loop"),
+        );
+
+        for (input, line, column, message) in cases {
+            let expected = ParseError {
+                message: message.to_string(),
+                line,
+                column
+            };
+
+            assert_eq!(parse_blif(input), Err(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_blif_constants() {
+        let circuit = parse_blif(".outputs one zero\n.names one\n1\n.names zero\n").unwrap();
+
+        assert_eq!(circuit.gates, vec!(
+            ("one".to_string(), top()),
+            ("zero".to_string(), bottom())
+        ));
+    }
+}
@@ -1,5 +1,12 @@
 use propositions::*;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+
 /// A proposition made up of the disjunction of possibly negated terms.
 ///
 /// For example the proposition `p \/ ~q \/ r` would be represetnted as the
@@ -19,14 +26,49 @@ use propositions::*;
 #[derive(Eq)]
 #[derive(Hash)]
 #[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Clause {
     pub parts: Vec<ClausePart>
 }
 
+/// The way that linear constraints, such as `[p + q + r <= 2]`, are broken
+/// into clauses.
+///
+/// Each encoding builds a circuit whose outputs say that at least a given
+/// number of the operands are true, with a new term defined for each gate.
+/// The sequential counter adds the operands one at a time, the totalizer
+/// adds up the two halves of the operands, and the sorting network sorts the
+/// operands so that the true ones come first. Constraints with coefficients
+/// other than one are instead weighed by a decision diagram, which uses each
+/// operand once however large its coefficient.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Eq)]
+#[derive(PartialEq)]
+pub enum CardinalityEncoding {
+    #[default]
+    SequentialCounter,
+    Totalizer,
+    SortingNetwork
+}
+
 impl Clause {
     /// Converts the given proposition into the corresponding clauses. Note
     /// that one proposition could break down into one or more clauses.
     ///
+    /// The clauses are only equisatisfiable with the proposition, not
+    /// equivalent to it. Operands that would otherwise blow up the number of
+    /// clauses, such as those of exclusive disjunctions and linear
+    /// constraints, are replaced by new defined terms whose names are
+    /// surrounded in braces, like `{p ∧ q}`, and which `is_defined_term`
+    /// tells apart from the terms of the proposition.
+    /// `Circuit::to_clauses` and bounded model checking rely on this, so
+    /// that their gates and states can be defined without distributing.
+    ///
     /// ```
     /// let prop1 = resolution_prover::term("hello".to_string());
     ///
@@ -42,7 +84,29 @@ impl Clause {
     /// );
     /// ```
     pub fn from_proposition(prop: Proposition) -> Vec<Clause> {
-        let all_parts = Clause::break_into_clauses(prop);
+        Clause::from_proposition_with(prop, CardinalityEncoding::default())
+    }
+
+    /// Converts the given proposition into the corresponding clauses, using
+    /// the given encoding for any linear constraints.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("[a + b + c + d <= 1]").unwrap();
+    ///
+    /// for encoding in &[
+    ///     CardinalityEncoding::SequentialCounter,
+    ///     CardinalityEncoding::Totalizer,
+    ///     CardinalityEncoding::SortingNetwork
+    /// ] {
+    ///     let clauses = Clause::from_proposition_with(prop.clone(), *encoding);
+    ///
+    ///     assert_eq!(count_models(&clauses), Some(5));
+    /// }
+    /// ```
+    pub fn from_proposition_with(prop: Proposition, encoding: CardinalityEncoding) -> Vec<Clause> {
+        let all_parts = Clause::break_into_clauses(prop, encoding);
 
         all_parts.iter()
             .map(|parts| Clause { parts: parts.to_vec() })
@@ -55,10 +119,59 @@ impl Clause {
     /// In the returned value, the first level of `Vec` represents the
     /// different clauses, and the second level of `Vec` represents the parts
     /// of that specific clause.
-    fn break_into_clauses(prop: Proposition) -> Vec<Vec<ClausePart>> {
+    fn break_into_clauses(prop: Proposition, encoding: CardinalityEncoding) -> Vec<Vec<ClausePart>> {
+        let mut definitions = vec!();
+        let encoded = Clause::encode_connectives(prop, encoding, &mut definitions);
+
+        Clause::break_with_definitions(encoded, definitions)
+    }
+
+    /// Converts the given proposition into clauses that can be satisfied
+    /// exactly when it can, replacing every operand that is not already a
+    /// possibly negated term with a new term, as `define` does for the
+    /// operands of exclusive disjunctions.
+    ///
+    /// Unlike `from_proposition`, this never distributes conjunctions over
+    /// disjunctions, so negating a large product of sums does not make the
+    /// number of clauses grow exponentially.
+    pub(crate) fn from_proposition_defined(prop: Proposition) -> Vec<Clause> {
+        let encoding = CardinalityEncoding::default();
+        let mut definitions = vec!();
+        let root = Clause::define_operands(prop, encoding, &mut definitions);
+        let encoded = Clause::encode_connectives(root, encoding, &mut definitions);
+
+        Clause::break_with_definitions(encoded, definitions).into_iter()
+            .map(|parts| Clause { parts })
+            .collect()
+    }
+
+    /// Breaks down the given proposition, along with the definitions of the
+    /// new terms that it uses, into the parts of the equivalent clauses.
+    fn break_with_definitions(encoded: Proposition, definitions: Vec<(String, Proposition)>) -> Vec<Vec<ClausePart>> {
+        let mut all_parts = Clause::break_into_clauses_(encoded);
+        for (_, d) in definitions {
+            // Distributing a definition can give clauses that contain both a
+            // term and its negation, which always hold and so are left out.
+            // The clauses of the proposition itself are all kept, so that
+            // none of its terms go missing
+            all_parts.extend(Clause::break_into_clauses_(d).into_iter()
+                .filter(|parts| !parts.iter().any(|p| parts.contains(&p.negate()))));
+        }
+        all_parts
+    }
+
+    #[allow(clippy::redundant_closure)]
+    fn break_into_clauses_(prop: Proposition) -> Vec<Vec<ClausePart>> {
         let no_implication = Clause::eliminate_implication(prop);
         let red_negations = Clause::reduce_negation(no_implication);
-        let bubbled = Clause::bubble_up_ands(red_negations);
+
+        // A true proposition needs no clauses, while a false one is the empty
+        // clause
+        let bubbled = match Clause::eliminate_constants(red_negations) {
+            Proposition::Top => return vec!(),
+            Proposition::Bottom => return vec!(vec!()),
+            p => Clause::bubble_up_ands(p),
+        };
         let or_not_props = Clause::split_on_ands(bubbled);
 
         or_not_props.iter()
@@ -66,14 +179,410 @@ impl Clause {
             .collect()
     }
 
+    /// Rewrites exclusive disjunctions, negated conjunctions and
+    /// disjunctions, conditional choices and linear constraints in terms of
+    /// the other connectives.
+    ///
+    /// Distributing an exclusive disjunction or a conditional choice over its
+    /// operands would copy them, so any operand that is copied and is not
+    /// already a possibly negated term is replaced by a new term. The new
+    /// term is named after the operand, surrounded in braces, and a
+    /// definition stating that the two are equivalent is added to the given
+    /// list. Since the name is determined by the operand, the same operand
+    /// always gets the same term, even across different propositions.
+    fn encode_connectives(prop: Proposition, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        match prop {
+            Proposition::Xor(a, b) =>
+                Clause::encode_xor(*a, *b, false, encoding, definitions),
+            Proposition::Ite(c, t, e) =>
+                Clause::encode_ite(*c, *t, *e, encoding, definitions),
+            Proposition::Linear(terms, comparison, bound) =>
+                Clause::encode_linear(terms, comparison, bound, encoding, definitions),
+            Proposition::Nand(a, b) =>
+                Clause::encode_connectives(not(and(*a, *b)), encoding, definitions),
+            Proposition::Nor(a, b) =>
+                Clause::encode_connectives(not(or(*a, *b)), encoding, definitions),
+            Proposition::Not(a) => match *a {
+                // Negations are pushed inside here so that the encodings do
+                // not need to be negated afterwards
+                Proposition::Xor(b, c) =>
+                    Clause::encode_xor(*b, *c, true, encoding, definitions),
+                Proposition::Ite(c, t, e) =>
+                    Clause::encode_ite(*c, not(*t), not(*e), encoding, definitions),
+                Proposition::Nand(b, c) =>
+                    Clause::encode_connectives(and(*b, *c), encoding, definitions),
+                Proposition::Nor(b, c) =>
+                    Clause::encode_connectives(or(*b, *c), encoding, definitions),
+                b => not(Clause::encode_connectives(b, encoding, definitions)),
+            },
+            Proposition::Or(a, b) => or(
+                Clause::encode_connectives(*a, encoding, definitions),
+                Clause::encode_connectives(*b, encoding, definitions)
+            ),
+            Proposition::And(a, b) => and(
+                Clause::encode_connectives(*a, encoding, definitions),
+                Clause::encode_connectives(*b, encoding, definitions)
+            ),
+            Proposition::Implies(a, b) => implies(
+                Clause::encode_connectives(*a, encoding, definitions),
+                Clause::encode_connectives(*b, encoding, definitions)
+            ),
+            Proposition::Iff(a, b) => iff(
+                Clause::encode_connectives(*a, encoding, definitions),
+                Clause::encode_connectives(*b, encoding, definitions)
+            ),
+            p => p
+        }
+    }
+
+    /// Encodes the exclusive disjunction of the given propositions, or its
+    /// negation, as the conjunction of two disjunctions.
+    fn encode_xor(a: Proposition, b: Proposition, negated: bool, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        let a2 = Clause::define(a, encoding, definitions);
+        let b2 = Clause::define(b, encoding, definitions);
+        let b3 = if negated { not(b2) } else { b2 };
+
+        and(
+            or(a2.clone(), b3.clone()),
+            or(not(a2), not(b3))
+        )
+    }
+
+    /// Encodes the conditional choice between the given branches as the
+    /// conjunction of an implication for each branch.
+    fn encode_ite(c: Proposition, t: Proposition, e: Proposition, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        let c2 = Clause::define(c, encoding, definitions);
+
+        and(
+            or(not(c2.clone()), Clause::encode_connectives(t, encoding, definitions)),
+            or(c2, Clause::encode_connectives(e, encoding, definitions))
+        )
+    }
+
+    /// Returns a possibly negated term that is equivalent to the given
+    /// proposition, adding a definition for a new term if the proposition is
+    /// not already a possibly negated term or a constant.
+    fn define(prop: Proposition, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        match prop {
+            Proposition::Not(a) => match *a {
+                Proposition::Term(b) => not(term(b)),
+                b => not(Clause::define(b, encoding, definitions)),
+            },
+            p @ Proposition::Term(_)
+            | p @ Proposition::Top
+            | p @ Proposition::Bottom => p,
+            p => {
+                let name = format!("{{{}}}", p.to_unicode());
+                Clause::define_as(name, p, encoding, definitions)
+            }
+        }
+    }
+
+    /// Returns a term with the given name, adding a definition stating that
+    /// it is equivalent to the given proposition if there is not one already.
+    fn define_as(name: String, prop: Proposition, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        if !definitions.iter().any(|(n, _)| *n == name) {
+            // Reserve the name before encoding so that the definitions of the
+            // operands come after this one
+            let index = definitions.len();
+            definitions.push((name.clone(), Proposition::Top));
+
+            let encoded = Clause::encode_connectives(prop, encoding, definitions);
+            definitions[index].1 = iff(term(name.clone()), encoded);
+        }
+
+        term(name)
+    }
+
+    /// Returns the given proposition with each of its operands replaced by a
+    /// possibly negated term, defining new terms for the operands and for
+    /// their own operands in turn, so that every definition only joins
+    /// possibly negated terms.
+    fn define_operands(prop: Proposition, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        let mut operand = |p: Box<Proposition>| match *p {
+            p @ Proposition::Term(_)
+            | p @ Proposition::Top
+            | p @ Proposition::Bottom => p,
+            Proposition::Not(a) => not(Clause::define_operands(*a, encoding, definitions)),
+            p => {
+                let name = format!("{{{}}}", p.to_unicode());
+                let gate = Clause::define_operands(p, encoding, definitions);
+                Clause::define_as(name, gate, encoding, definitions)
+            }
+        };
+
+        match prop {
+            Proposition::Or(a, b) => or(operand(a), operand(b)),
+            Proposition::And(a, b) => and(operand(a), operand(b)),
+            Proposition::Implies(a, b) => implies(operand(a), operand(b)),
+            Proposition::Iff(a, b) => iff(operand(a), operand(b)),
+            Proposition::Xor(a, b) => xor(operand(a), operand(b)),
+            Proposition::Nand(a, b) => nand(operand(a), operand(b)),
+            Proposition::Nor(a, b) => nor(operand(a), operand(b)),
+            Proposition::Ite(c, t, e) => ite(operand(c), operand(t), operand(e)),
+            Proposition::Linear(terms, comparison, bound) => Proposition::Linear(
+                terms.into_iter().map(|(c, p)| (c, operand(Box::new(p)))).collect(),
+                comparison,
+                bound
+            ),
+            Proposition::Not(a) => not(Clause::define_operands(*a, encoding, definitions)),
+            p => p
+        }
+    }
+
+    /// Encodes a linear constraint in terms of the outputs of a circuit that
+    /// counts its true operands, built with the given encoding.
+    ///
+    /// Operands with negative coefficients are replaced by their negations,
+    /// which moves the coefficient over to the bound, so that every operand
+    /// has a positive coefficient. When any coefficient is more than one,
+    /// the operands are weighed by a decision diagram instead of counted.
+    fn encode_linear(terms: Vec<(i64, Proposition)>, comparison: Comparison, bound: i64, encoding: CardinalityEncoding, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        let mut weighted = vec!();
+        let mut bound = i128::from(bound);
+        for (c, p) in terms {
+            let literal = Clause::define(p, encoding, definitions);
+            if c < 0 {
+                bound -= i128::from(c);
+                weighted.push((negate_literal(literal), i128::from(c).abs()));
+            } else if c > 0 {
+                weighted.push((literal, i128::from(c)));
+            }
+        }
+
+        if weighted.iter().any(|&(_, weight)| weight != 1) {
+            // Deciding on the heaviest operands first keeps the diagram small
+            weighted.sort_by_key(|&(_, weight)| Reverse(weight));
+            let mut nodes = HashMap::new();
+            let mut at_least = |n: i128| Clause::decision_diagram(&weighted, n, &mut nodes, definitions);
+
+            return match comparison {
+                Comparison::AtMost => not(at_least(bound + 1)),
+                Comparison::AtLeast => at_least(bound),
+                Comparison::Exactly => and(at_least(bound), not(at_least(bound + 1))),
+            }
+        }
+
+        let inputs: Vec<Proposition> = weighted.into_iter().map(|(literal, _)| literal).collect();
+        let needed = match comparison {
+            Comparison::AtLeast => bound,
+            Comparison::AtMost | Comparison::Exactly => bound + 1,
+        };
+
+        // More outputs than inputs are never true, so they are not built
+        let needed = needed.clamp(0, inputs.len() as i128) as usize;
+        let outputs = if needed == 0 {
+            vec!()
+        } else {
+            match encoding {
+                CardinalityEncoding::SequentialCounter =>
+                    Clause::sequential_counter(&inputs, needed, definitions),
+                CardinalityEncoding::Totalizer =>
+                    Clause::totalizer(&inputs, needed, definitions),
+                CardinalityEncoding::SortingNetwork =>
+                    Clause::sorting_network(&inputs, needed, definitions),
+            }
+        };
+
+        // Holds when at least the given number of the inputs are true
+        let at_least = |n: i128| if n <= 0 {
+            top()
+        } else if n > inputs.len() as i128 {
+            bottom()
+        } else {
+            outputs[n as usize - 1].clone()
+        };
+
+        match comparison {
+            Comparison::AtMost => not(at_least(bound + 1)),
+            Comparison::AtLeast => at_least(bound),
+            Comparison::Exactly => and(at_least(bound), not(at_least(bound + 1))),
+        }
+    }
+
+    /// Returns whether the true inputs weigh at least the given amount,
+    /// deciding on the first input and then weighing the rest.
+    ///
+    /// Each node is defined once for each number of inputs left and amount
+    /// still needed, so every input appears once whatever its weight.
+    fn decision_diagram(inputs: &[(Proposition, i128)], needed: i128, nodes: &mut HashMap<(usize, i128), Proposition>, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        if needed <= 0 {
+            return top()
+        }
+        if needed > inputs.iter().map(|&(_, weight)| weight).sum::<i128>() {
+            return bottom()
+        }
+        if let Some(node) = nodes.get(&(inputs.len(), needed)) {
+            return node.clone()
+        }
+
+        let (ref literal, weight) = inputs[0];
+        let without = Clause::decision_diagram(&inputs[1..], needed, nodes, definitions);
+        let with = Clause::decision_diagram(&inputs[1..], needed - weight, nodes, definitions);
+
+        let name = format!("{{[{}] >= {}}}", weighted_sum_name(inputs), needed);
+        let node = Clause::define_gate(name, either(without, both(literal.clone(), with)), definitions);
+        nodes.insert((inputs.len(), needed), node.clone());
+        node
+    }
+
+    /// Returns whether at least each number of the inputs, from one up to
+    /// the given number, are true, adding the inputs one at a time.
+    fn sequential_counter(inputs: &[Proposition], needed: usize, definitions: &mut Vec<(String, Proposition)>) -> Vec<Proposition> {
+        let mut counts: Vec<Proposition> = vec!();
+
+        for i in 0..inputs.len() {
+            let mut next = vec!();
+            for j in 1..(i + 2).min(needed + 1) {
+                let without = counts.get(j - 1).cloned().unwrap_or_else(bottom);
+                let with = if j == 1 {
+                    inputs[i].clone()
+                } else {
+                    both(inputs[i].clone(), counts[j - 2].clone())
+                };
+
+                let name = format!("{{[{}] >= {}}}", sum_name(&inputs[..i + 1]), j);
+                next.push(Clause::define_gate(name, either(without, with), definitions));
+            }
+            counts = next;
+        }
+
+        counts
+    }
+
+    /// Returns whether at least each number of the inputs, from one up to
+    /// the given number, are true, adding up the counts of the two halves of
+    /// the inputs.
+    fn totalizer(inputs: &[Proposition], needed: usize, definitions: &mut Vec<(String, Proposition)>) -> Vec<Proposition> {
+        if inputs.len() == 1 {
+            return inputs.to_vec()
+        }
+
+        let middle = inputs.len() / 2;
+        let left = Clause::totalizer(&inputs[..middle], needed, definitions);
+        let right = Clause::totalizer(&inputs[middle..], needed, definitions);
+
+        let count = |counts: &[Proposition], n: usize| if n == 0 {
+            top()
+        } else {
+            counts.get(n - 1).cloned().unwrap_or_else(bottom)
+        };
+
+        (1..inputs.len().min(needed) + 1)
+            .map(|j| {
+                let prop = (0..j + 1)
+                    .map(|i| both(count(&left, i), count(&right, j - i)))
+                    .fold(bottom(), either);
+
+                let name = format!("{{[{}] >= {}}}", sum_name(inputs), j);
+                Clause::define_gate(name, prop, definitions)
+            })
+            .collect()
+    }
+
+    /// Returns whether at least each number of the inputs, from one up to
+    /// the given number, are true, sorting the inputs with an odd-even merge
+    /// sort so that the true ones come first.
+    fn sorting_network(inputs: &[Proposition], needed: usize, definitions: &mut Vec<(String, Proposition)>) -> Vec<Proposition> {
+        let size = inputs.len().next_power_of_two();
+        let mut wires = inputs.to_vec();
+        wires.resize(size, bottom());
+
+        let prefix = sum_name(inputs);
+        let mut comparator = 0;
+
+        let mut p = 1;
+        while p < size {
+            let mut k = p;
+            while k >= 1 {
+                let mut j = k % p;
+                while j + k < size {
+                    for i in 0..k.min(size - j - k) {
+                        if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                            let (a, b) = (wires[i + j].clone(), wires[i + j + k].clone());
+
+                            let high = format!("{{[{}] max {}}}", prefix, comparator);
+                            let low = format!("{{[{}] min {}}}", prefix, comparator);
+                            wires[i + j] = Clause::define_gate(high, either(a.clone(), b.clone()), definitions);
+                            wires[i + j + k] = Clause::define_gate(low, both(a, b), definitions);
+                            comparator += 1;
+                        }
+                    }
+                    j += 2 * k;
+                }
+                k /= 2;
+            }
+            p *= 2;
+        }
+
+        wires.truncate(needed);
+        wires
+    }
+
+    /// Returns a possibly negated term or constant equivalent to the given
+    /// gate, adding a definition for a term with the given name if the gate
+    /// is not already one.
+    fn define_gate(name: String, gate: Proposition, definitions: &mut Vec<(String, Proposition)>) -> Proposition {
+        match gate {
+            p @ Proposition::Term(_)
+            | p @ Proposition::Not(_)
+            | p @ Proposition::Top
+            | p @ Proposition::Bottom => p,
+            p => {
+                if !definitions.iter().any(|(n, _)| *n == name) {
+                    definitions.push((name.clone(), iff(term(name.clone()), p)));
+                }
+                term(name)
+            }
+        }
+    }
+
+    /// Expands a linear constraint into the choices of which operands are
+    /// true, making a disjunction over each operand being true or false
+    /// whenever the remaining operands could both meet and miss the bound.
+    ///
+    /// The expansion can be exponentially larger than the constraint, so it
+    /// is only used where new terms cannot be introduced.
+    pub(crate) fn expand_linear(terms: &[(i64, Proposition)], comparison: Comparison, bound: i64) -> Proposition {
+        let bound = i128::from(bound);
+        let (low, high) = match comparison {
+            Comparison::AtMost => (i128::MIN, bound),
+            Comparison::AtLeast => (bound, i128::MAX),
+            Comparison::Exactly => (bound, bound),
+        };
+
+        fn expand(terms: &[(i64, Proposition)], low: i128, high: i128) -> Proposition {
+            let least: i128 = terms.iter().map(|&(c, _)| i128::from(c.min(0))).sum();
+            let most: i128 = terms.iter().map(|&(c, _)| i128::from(c.max(0))).sum();
+
+            if least >= low && most <= high {
+                return top()
+            }
+            if most < low || least > high {
+                return bottom()
+            }
+
+            let (c, ref p) = terms[0];
+            let c = i128::from(c);
+            either(
+                both(p.clone(), expand(&terms[1..], low.saturating_sub(c), high.saturating_sub(c))),
+                both(not(p.clone()), expand(&terms[1..], low, high))
+            )
+        }
+
+        expand(terms, low, high)
+    }
+
     /// Converts the given proposition to an equivalent proposition that does
-    /// not use any instances of implication or biconditional.
+    /// not use any instances of implication or biconditional, or any of the
+    /// other connectives besides conjunction, disjunction and negation.
     ///
     /// The conversions are done by breaking biconditionals into the and of
     /// the implication in each direction, and then converting the implications
     /// into the disjunction of the negation of the antecedent and the
-    /// consequent.
-    fn eliminate_implication(prop: Proposition) -> Proposition {
+    /// consequent. The other connectives are expanded directly, which copies
+    /// their operands.
+    pub(crate) fn eliminate_implication(prop: Proposition) -> Proposition {
         match prop {
             Proposition::Implies(a, b) => {
                 let a_simpl = Clause::eliminate_implication(*a);
@@ -85,7 +594,37 @@ impl Clause {
                     Clause::eliminate_implication(implies(*a.clone(), *b.clone())),
                     Clause::eliminate_implication(implies(*b, *a))
                 )
-            }
+            },
+            Proposition::Or(a, b) => or(
+                Clause::eliminate_implication(*a),
+                Clause::eliminate_implication(*b)
+            ),
+            Proposition::And(a, b) => and(
+                Clause::eliminate_implication(*a),
+                Clause::eliminate_implication(*b)
+            ),
+            Proposition::Xor(a, b) => {
+                let a_simpl = Clause::eliminate_implication(*a);
+                let b_simpl = Clause::eliminate_implication(*b);
+                and(
+                    or(a_simpl.clone(), b_simpl.clone()),
+                    or(not(a_simpl), not(b_simpl))
+                )
+            },
+            Proposition::Nand(a, b) =>
+                Clause::eliminate_implication(not(and(*a, *b))),
+            Proposition::Nor(a, b) =>
+                Clause::eliminate_implication(not(or(*a, *b))),
+            Proposition::Ite(c, t, e) => {
+                let c_simpl = Clause::eliminate_implication(*c);
+                and(
+                    or(not(c_simpl.clone()), Clause::eliminate_implication(*t)),
+                    or(c_simpl, Clause::eliminate_implication(*e))
+                )
+            },
+            Proposition::Linear(terms, comparison, bound) =>
+                Clause::eliminate_implication(Clause::expand_linear(&terms, comparison, bound)),
+            Proposition::Not(a) => not(Clause::eliminate_implication(*a)),
             p => p
         }
     }
@@ -101,7 +640,7 @@ impl Clause {
     /// This function assumes that all implications and biconditionals have
     /// already been removed from the proposition, and will panic upon finding
     /// any.
-    fn reduce_negation(prop: Proposition) -> Proposition {
+    pub(crate) fn reduce_negation(prop: Proposition) -> Proposition {
         match prop {
             Proposition::Not(a) => {
                 let a2 = *a; // Pull the value out to allow multiple matching
@@ -116,6 +655,8 @@ impl Clause {
                         Clause::reduce_negation(not(*c))
                     ),
                     Proposition::Term(b) => not(term(b)),
+                    Proposition::Top => Proposition::Bottom,
+                    Proposition::Bottom => Proposition::Top,
                     p => panic!("Unexpected implies or iff: {}", not(p))
                 }
             },
@@ -128,64 +669,68 @@ impl Clause {
                 Clause::reduce_negation(*b)
             ),
             Proposition::Term(a) => term(a),
+            p @ Proposition::Top | p @ Proposition::Bottom => p,
             p => panic!("Unexpected implies or iff: {}", p)
         }
     }
 
+    /// Removes the constants from the given proposition, so that it is
+    /// either `Top`, `Bottom`, or a proposition without any constants.
+    ///
+    /// Assumes that negations have already been reduced, so that they only
+    /// apply to terms.
+    pub(crate) fn eliminate_constants(prop: Proposition) -> Proposition {
+        match prop {
+            Proposition::Or(a, b) => {
+                match (Clause::eliminate_constants(*a), Clause::eliminate_constants(*b)) {
+                    (Proposition::Top, _) | (_, Proposition::Top) => Proposition::Top,
+                    (Proposition::Bottom, p) | (p, Proposition::Bottom) => p,
+                    (p1, p2) => or(p1, p2)
+                }
+            },
+            Proposition::And(a, b) => {
+                match (Clause::eliminate_constants(*a), Clause::eliminate_constants(*b)) {
+                    (Proposition::Bottom, _) | (_, Proposition::Bottom) => Proposition::Bottom,
+                    (Proposition::Top, p) | (p, Proposition::Top) => p,
+                    (p1, p2) => and(p1, p2)
+                }
+            },
+            p => p
+        }
+    }
+
     /// Bubbles up the conjunctions in the given proposition so that the
     /// proposition becomes the conjunctions of terms and disjunctions.
     ///
     /// This conversion is done by using the distributed property of
     /// conujunctions and disjunctions.
-    fn bubble_up_ands(prop: Proposition) -> Proposition {
-        match Clause::bubble_up_ands_(prop, false) {
-            (p, _) => p
+    pub(crate) fn bubble_up_ands(prop: Proposition) -> Proposition {
+        match prop {
+            Proposition::Or(a, b) => Clause::distribute(
+                Clause::bubble_up_ands(*a),
+                Clause::bubble_up_ands(*b)
+            ),
+            Proposition::And(a, b) => and(
+                Clause::bubble_up_ands(*a),
+                Clause::bubble_up_ands(*b)
+            ),
+            p => p
         }
     }
 
-    fn bubble_up_ands_(prop: Proposition, cleared: bool) -> (Proposition, bool) {
-        let handled = match (prop, cleared) {
-            (p, true) => p,
-            (Proposition::Term(a), _) => term(a),
-            (Proposition::Not(a), _) =>
-                not(Clause::bubble_up_ands_(*a, false).0),
-            (Proposition::Or(a, b), _) => {
-                let a_bubbled = Clause::bubble_up_ands_(*a, false).0;
-                let b_bubbled = Clause::bubble_up_ands_(*b, false).0;
-
-                or(a_bubbled, b_bubbled)
-            },
-            (Proposition::And(a, b), _) => {
-                let a_bubbled = Clause::bubble_up_ands_(*a, false).0;
-                let b_bubbled = Clause::bubble_up_ands_(*b, false).0;
-
-                and(a_bubbled, b_bubbled)
-            },
-            (p, _) => Clause::bubble_up_ands_(p, false).0,
-        };
-
-        match handled {
-            Proposition::Or(a, b) => {
-                let a2 = *a;
-                let b2 = *b;
-                match (a2, b2) {
-                    (Proposition::And(c, d), e) =>
-                        (and(
-                            or(*c, e.clone()),
-                            or(*d, e)
-                        ), true),
-                    (c, Proposition::And(d, e)) =>
-                        (and(
-                            or(c.clone(), *d),
-                            or(c, *e)
-                        ), true),
-                    (p1, p2) => (or(p1, p2), true)
-                }
-            },
-            Proposition::And(a, b) => (and(*a, *b), true),
-            Proposition::Not(a) => (not(*a), true),
-            Proposition::Term(a) => (term(a), true),
-            p => panic!("Unexpected implies or iff: {}", p)
+    /// Distributes the disjunction of the two given propositions over their
+    /// conjunctions, assuming that their conjunctions are already bubbled up.
+    fn distribute(a: Proposition, b: Proposition) -> Proposition {
+        match (a, b) {
+            (Proposition::And(c, d), e) => and(
+                Clause::distribute(*c, e.clone()),
+                Clause::distribute(*d, e)
+            ),
+            (c, Proposition::And(d, e)) => and(
+                Clause::distribute(c.clone(), *d),
+                Clause::distribute(c, *e)
+            ),
+            (c, d) => or(c, d)
         }
     }
 
@@ -193,7 +738,7 @@ impl Clause {
     ///
     /// Assumes that the proposition has already had its conjunctions bubbled
     /// up.
-    fn split_on_ands(prop: Proposition) -> Vec<Proposition> {
+    pub(crate) fn split_on_ands(prop: Proposition) -> Vec<Proposition> {
         match prop {
             Proposition::And(a, b) => {
                 let mut a_parts = Clause::split_on_ands(*a);
@@ -212,7 +757,8 @@ impl Clause {
     ///
     /// Assumes that the proposition has been simplified to contain only
     /// disjunctions, negations, and raw terms.
-    fn from_or_not_prop(prop: &Proposition) -> Vec<ClausePart> {
+    #[allow(clippy::borrow_deref_ref)]
+    pub(crate) fn from_or_not_prop(prop: &Proposition) -> Vec<ClausePart> {
         match *prop {
             Proposition::Or(ref a, ref b) => {
                 let mut a_parts = Clause::from_or_not_prop(&*a);
@@ -235,6 +781,83 @@ impl Clause {
     }
 }
 
+/// Returns the negation of the given possibly negated term or constant.
+fn negate_literal(literal: Proposition) -> Proposition {
+    match literal {
+        Proposition::Not(a) => *a,
+        Proposition::Top => bottom(),
+        Proposition::Bottom => top(),
+        p => not(p)
+    }
+}
+
+/// Returns the disjunction of the given propositions, leaving out constants.
+pub(crate) fn either(a: Proposition, b: Proposition) -> Proposition {
+    match (a, b) {
+        (Proposition::Top, _) | (_, Proposition::Top) => top(),
+        (Proposition::Bottom, p) | (p, Proposition::Bottom) => p,
+        (p, q) => or(p, q)
+    }
+}
+
+/// Returns the conjunction of the given propositions, leaving out constants.
+pub(crate) fn both(a: Proposition, b: Proposition) -> Proposition {
+    match (a, b) {
+        (Proposition::Bottom, _) | (_, Proposition::Bottom) => bottom(),
+        (Proposition::Top, p) | (p, Proposition::Top) => p,
+        (p, q) => and(p, q)
+    }
+}
+
+/// Returns the sum of the given inputs, which is used to name the terms
+/// defined while counting them.
+///
+/// The names put the sum in brackets on its own, like `{[p + q] >= 2}`, so
+/// that they never clash with the name of a defined operand, like
+/// `{[p + q >= 2]}`.
+fn sum_name(inputs: &[Proposition]) -> String {
+    inputs.iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<String>>()
+        .join(" + ")
+}
+
+/// Returns the weighted sum of the given inputs, which is used to name the
+/// nodes of a decision diagram.
+///
+/// Every weight is written out, like `{[2 p + 1 q] >= 2}`, so that the names
+/// never clash with those of a circuit counting the inputs.
+fn weighted_sum_name(inputs: &[(Proposition, i128)]) -> String {
+    inputs.iter()
+        .map(|&(ref p, weight)| format!("{} {}", weight, p))
+        .collect::<Vec<String>>()
+        .join(" + ")
+}
+
+/// Checks whether the term with the given name was introduced while
+/// breaking a proposition into clauses, rather than appearing in the
+/// proposition itself.
+///
+/// Such terms stand for a subformula or for part of a linear constraint, and
+/// their names are surrounded in braces so that they can never be parsed as
+/// terms.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses = Clause::from_proposition(parse_proposition("(p /\\ q) <+> r").unwrap());
+///
+/// let defined = clauses.iter()
+///     .flat_map(|c| c.parts.iter())
+///     .any(|p| is_defined_term(p.name()));
+///
+/// assert!(defined);
+/// assert!(!is_defined_term("p"));
+/// ```
+pub fn is_defined_term(name: &str) -> bool {
+    name.starts_with('{')
+}
+
 /// A term or negated term that is part of a clause.
 ///
 /// For example, `p` and `~q` can be represented as the following.
@@ -277,12 +900,77 @@ impl ClausePart {
             ClausePart::NegatedTerm(a) => ClausePart::Term(a.clone())
         }
     }
+
+    /// Returns the name of the term of the clause part, whether it is
+    /// negated or not.
+    ///
+    /// ```
+    /// let not_q = resolution_prover::ClausePart::NegatedTerm("q".to_string());
+    ///
+    /// assert_eq!(not_q.name(), "q");
+    /// ```
+    pub fn name(&self) -> &str {
+        match *self {
+            ClausePart::Term(ref a) | ClausePart::NegatedTerm(ref a) => a,
+        }
+    }
+}
+
+impl fmt::Display for Clause {
+    /// Displays the clause as the disjunction of its parts, using `[]` to
+    /// represent the empty clause.
+    ///
+    /// ```
+    /// let clause = resolution_prover::Clause {
+    ///     parts: vec!(
+    ///         resolution_prover::ClausePart::NegatedTerm("p".to_string()),
+    ///         resolution_prover::ClausePart::Term("q".to_string())
+    ///     )
+    /// };
+    ///
+    /// assert_eq!(clause.to_string(), "~p \\/ q");
+    ///
+    /// let empty = resolution_prover::Clause { parts: vec!() };
+    ///
+    /// assert_eq!(empty.to_string(), "[]");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.parts.is_empty() {
+            return write!(f, "[]")
+        }
+
+        let parts: Vec<String> = self.parts.iter()
+            .map(|p| p.to_string())
+            .collect();
+
+        write!(f, "{}", parts.join(" \\/ "))
+    }
+}
+
+impl fmt::Display for ClausePart {
+    /// Displays the clause part as its term, prefixed with `~` if it is
+    /// negated.
+    ///
+    /// ```
+    /// let not_q = resolution_prover::ClausePart::NegatedTerm("q".to_string());
+    ///
+    /// assert_eq!(not_q.to_string(), "~q");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ClausePart::Term(ref a) => write!(f, "{}", a),
+            ClausePart::NegatedTerm(ref a) => write!(f, "~{}", a),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use propositions::*;
     use clauses::*;
+    use models::*;
+    use parser::*;
+    use sat::*;
 
     #[test]
     fn eliminate_implication_implies() {
@@ -493,6 +1181,49 @@ mod tests {
         assert_eq!(Clause::reduce_negation(prop), expected);
     }
 
+    #[test]
+    fn reduce_negation_not_top() {
+        let prop = not(or(
+            term("a".to_string()),
+            top()
+        ));
+
+        let expected = and(
+            not(term("a".to_string())),
+            bottom()
+        );
+
+        assert_eq!(Clause::reduce_negation(prop), expected);
+    }
+
+    #[test]
+    fn eliminate_constants_nested() {
+        let prop = and(
+            or(term("a".to_string()), bottom()),
+            or(term("b".to_string()), top())
+        );
+
+        let expected = term("a".to_string());
+
+        assert_eq!(Clause::eliminate_constants(prop), expected);
+    }
+
+    #[test]
+    fn from_proposition_top() {
+        let prop = and_all(vec!());
+
+        assert_eq!(Clause::from_proposition(prop), vec!());
+    }
+
+    #[test]
+    fn from_proposition_bottom() {
+        let prop = and(term("a".to_string()), or_all(vec!()));
+
+        let expected = vec!(Clause { parts: vec!() });
+
+        assert_eq!(Clause::from_proposition(prop), expected);
+    }
+
     #[test]
     fn bubble_up_ands_term() {
         let prop = term("a".to_string());
@@ -573,4 +1304,230 @@ mod tests {
 
         assert_eq!(Clause::bubble_up_ands(prop), expected);
     }
+
+    #[test]
+    fn from_proposition_xor() {
+        let prop = xor(
+            term("a".to_string()),
+            term("b".to_string())
+        );
+
+        let expected = vec!(
+            Clause { parts: vec!(
+                ClausePart::Term("a".to_string()),
+                ClausePart::Term("b".to_string())
+            ) },
+            Clause { parts: vec!(
+                ClausePart::NegatedTerm("a".to_string()),
+                ClausePart::NegatedTerm("b".to_string())
+            ) }
+        );
+
+        assert_eq!(Clause::from_proposition(prop), expected);
+    }
+
+    #[test]
+    fn from_proposition_keeps_tautology() {
+        let prop = or(term("q".to_string()), not(term("q".to_string())));
+
+        let expected = vec!(
+            Clause { parts: vec!(
+                ClausePart::Term("q".to_string()),
+                ClausePart::NegatedTerm("q".to_string())
+            ) }
+        );
+
+        assert_eq!(Clause::from_proposition(prop), expected);
+    }
+
+    #[test]
+    fn from_proposition_xor_chain_linear() {
+        let prop = (1..20)
+            .map(|i| term(format!("x{}", i)))
+            .fold(term("x0".to_string()), xor);
+
+        // Each of the 18 inner exclusive disjunctions is defined by 4
+        // clauses, while the outermost one needs only 2
+        assert_eq!(Clause::from_proposition(not(prop)).len(), 2 + 18 * 4);
+    }
+
+    #[test]
+    fn from_proposition_shared_definition() {
+        let inner = xor(term("a".to_string()), term("b".to_string()));
+
+        let first = Clause::from_proposition(xor(inner.clone(), term("c".to_string())));
+        let second = Clause::from_proposition(xor(inner, term("d".to_string())));
+
+        let name = ClausePart::Term("{a ⊕ b}".to_string());
+
+        assert!(first.iter().any(|c| c.parts.contains(&name)));
+        assert!(second.iter().any(|c| c.parts.contains(&name)));
+    }
+
+    #[test]
+    fn from_proposition_distinct_groupings() {
+        // Both operands display as `a /\ b \/ c`, so they must not be named
+        // after their displayed form
+        let left = or(and(term("a".to_string()), term("b".to_string())), term("c".to_string()));
+        let right = and(term("a".to_string()), or(term("b".to_string()), term("c".to_string())));
+
+        let clauses = Clause::from_proposition(and(
+            xor(left, term("d".to_string())),
+            xor(right, term("e".to_string()))
+        ));
+
+        // With a false and c true, the left operand holds but the right
+        // does not, so d and e cannot both be false
+        let mut assumptions = clauses;
+        for p in &["~a", "c", "~d", "~e"] {
+            assumptions.append(&mut Clause::from_proposition(parse_proposition(p).unwrap()));
+        }
+
+        assert_eq!(find_clause_model(&assumptions), None);
+    }
+
+    #[test]
+    fn from_proposition_not_ite() {
+        let prop = not(ite(
+            term("c".to_string()),
+            term("a".to_string()),
+            term("b".to_string())
+        ));
+
+        let expected = vec!(
+            Clause { parts: vec!(
+                ClausePart::NegatedTerm("c".to_string()),
+                ClausePart::NegatedTerm("a".to_string())
+            ) },
+            Clause { parts: vec!(
+                ClausePart::Term("c".to_string()),
+                ClausePart::NegatedTerm("b".to_string())
+            ) }
+        );
+
+        assert_eq!(Clause::from_proposition(prop), expected);
+    }
+
+    #[test]
+    fn from_proposition_nested_implication() {
+        let prop = and(
+            implies(term("a".to_string()), term("b".to_string())),
+            term("c".to_string())
+        );
+
+        let expected = vec!(
+            Clause { parts: vec!(
+                ClausePart::NegatedTerm("a".to_string()),
+                ClausePart::Term("b".to_string())
+            ) },
+            Clause { parts: vec!(
+                ClausePart::Term("c".to_string())
+            ) }
+        );
+
+        assert_eq!(Clause::from_proposition(prop), expected);
+    }
+
+    #[test]
+    fn bubble_up_ands_nested_distribution() {
+        let prop = or(
+            or(
+                and(term("a".to_string()), term("b".to_string())),
+                term("c".to_string())
+            ),
+            and(term("d".to_string()), term("e".to_string()))
+        );
+
+        let clauses = Clause::split_on_ands(Clause::bubble_up_ands(prop));
+
+        assert_eq!(clauses.len(), 4);
+        assert!(clauses.iter().all(|c| matches!(*c, Proposition::Or(..))));
+    }
+
+    #[test]
+    fn from_proposition_with_linear_encodings() {
+        let constraints = vec!(
+            "[a + b + c + d <= 2]",
+            "[a + b + c + d >= 3]",
+            "[a + b + c + d = 2]",
+            "[3 a - 2 b + c + (c /\\ d) <= 2]",
+            "[2 a + 2 b - c >= -1]",
+            "~([a + b + c = 1]) \\/ d",
+            "[a + ~(b) + [c + d >= 1] = 2]",
+            "[a + b <= -1]",
+            "[a + b >= 0]",
+            "[300 a + 200 b + 100 c <= 350]",
+            "[2 a + 3 b - 4 c + d = 1]",
+            "[a + b + c + d = 4]",
+            "[a + b - c <= 2]",
+        );
+        let encodings = vec!(
+            CardinalityEncoding::SequentialCounter,
+            CardinalityEncoding::Totalizer,
+            CardinalityEncoding::SortingNetwork,
+        );
+        let names: Vec<String> = "abcd".chars().map(|c| c.to_string()).collect();
+
+        for input in constraints {
+            let prop = parse_proposition(input).unwrap();
+
+            let expected = (0..16u32)
+                .filter(|bits| {
+                    let assignment = names.iter().cloned()
+                        .enumerate()
+                        .map(|(i, n)| (n, bits & (1 << i) != 0))
+                        .collect();
+                    prop.evaluate(&assignment) == Some(true)
+                })
+                .count() as u128;
+
+            for &encoding in &encodings {
+                let clauses = Clause::from_proposition_with(prop.clone(), encoding);
+                let defined: Vec<String> = clauses.iter()
+                    .flat_map(|c| c.parts.iter())
+                    .map(|p| p.name().to_string())
+                    .filter(|n| !names.contains(n))
+                    .collect();
+                let missing = names.iter()
+                    .filter(|n| !clauses.iter().any(|c| c.parts.iter().any(|p| p.name() == *n)))
+                    .count() as u32;
+
+                // The new terms are defined by the operands, so they do not
+                // change the number of models
+                assert!(defined.iter().all(|n| is_defined_term(n)), "{}", input);
+                assert_eq!(
+                    count_models(&clauses).map(|n| n << missing),
+                    Some(expected),
+                    "{} with {:?}",
+                    input,
+                    encoding
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn from_proposition_large_coefficients() {
+        let prop = parse_proposition("[300 a + 200 b + 100 c <= 350]").unwrap();
+
+        for &encoding in &[CardinalityEncoding::SequentialCounter, CardinalityEncoding::Totalizer, CardinalityEncoding::SortingNetwork] {
+            let clauses = Clause::from_proposition_with(prop.clone(), encoding);
+
+            // Each operand is decided on once rather than counted once for
+            // each unit of its coefficient
+            assert!(clauses.len() < 20, "{} clauses with {:?}", clauses.len(), encoding);
+            assert_eq!(count_models(&clauses), Some(5));
+        }
+    }
+
+    #[test]
+    fn eliminate_implication_linear() {
+        let prop = parse_proposition("[a + b + c >= 2]").unwrap();
+
+        let expanded = Clause::eliminate_implication(prop);
+
+        let expected = parse_proposition("(a /\\ (b \\/ (~b /\\ c))) \\/ (~a /\\ (b /\\ c))").unwrap();
+
+        assert_eq!(expanded, expected);
+    }
 }
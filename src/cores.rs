@@ -0,0 +1,153 @@
+use clauses::*;
+use propositions::*;
+use sat::*;
+
+/// Finds a minimal set of the given assumptions from which the goal
+/// follows, returning their indices in increasing order, or None if the
+/// goal does not follow from all of them.
+///
+/// The set is found by deletion: each assumption is dropped in turn, and
+/// stays dropped if the goal still follows from those that remain. No
+/// assumption of the result can be removed without the goal no longer
+/// following, though a smaller set may exist. When the assumptions are
+/// contradictory, the result may not need the goal at all.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let assumptions = vec!(
+///     parse_proposition("p").unwrap(),
+///     parse_proposition("p -> q").unwrap(),
+///     parse_proposition("r -> q").unwrap(),
+///     parse_proposition("q -> s").unwrap(),
+///     parse_proposition("r").unwrap(),
+/// );
+///
+/// let goal = parse_proposition("s").unwrap();
+///
+/// assert_eq!(minimal_core(&assumptions, &goal), Some(vec!(0, 1, 3)));
+/// assert_eq!(minimal_core(&assumptions, &term("t".to_string())), None);
+/// ```
+pub fn minimal_core(assumptions: &[Proposition], goal: &Proposition) -> Option<Vec<usize>> {
+    let negated_goal = Clause::from_proposition(not(goal.clone()));
+    let clauses: Vec<Vec<Clause>> = assumptions.iter()
+        .map(|a| Clause::from_proposition(a.clone()))
+        .collect();
+
+    let follows = |kept: &[usize]| {
+        let mut all = negated_goal.clone();
+        for &i in kept {
+            all.extend(clauses[i].iter().cloned());
+        }
+        find_clause_model(&all).is_none()
+    };
+
+    let mut kept: Vec<usize> = (0..assumptions.len()).collect();
+    if !follows(&kept) {
+        return None
+    }
+
+    // Dropping assumptions from the end first keeps the earlier ones when
+    // there is a choice
+    for i in (0..assumptions.len()).rev() {
+        let without: Vec<usize> = kept.iter().cloned().filter(|&k| k != i).collect();
+        if follows(&without) {
+            kept = without;
+        }
+    }
+
+    Some(kept)
+}
+
+/// Finds a minimal unsatisfiable subset of the given propositions, returning
+/// their indices in increasing order, or None if the propositions are
+/// satisfiable.
+///
+/// Every proposition of the subset is needed for it to be unsatisfiable, so
+/// that removing any one of them leaves propositions that can all be true at
+/// once.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let rules = vec!(
+///     parse_proposition("a -> b").unwrap(),
+///     parse_proposition("c").unwrap(),
+///     parse_proposition("b -> ~c").unwrap(),
+///     parse_proposition("d \\/ a").unwrap(),
+///     parse_proposition("a").unwrap(),
+/// );
+///
+/// assert_eq!(minimal_unsatisfiable_subset(&rules), Some(vec!(0, 1, 2, 4)));
+/// assert_eq!(minimal_unsatisfiable_subset(&rules[..4]), None);
+/// ```
+pub fn minimal_unsatisfiable_subset(props: &[Proposition]) -> Option<Vec<usize>> {
+    minimal_core(props, &bottom())
+}
+
+#[cfg(test)]
+mod tests {
+    use cores::*;
+    use parser::*;
+    use resolution::*;
+
+    #[test]
+    fn minimal_core_is_minimal() {
+        let assumptions: Vec<Proposition> = vec!(
+            "a -> b", "b -> c", "a", "c -> d", "a -> d", "e", "d -> f", "e -> f"
+        ).into_iter()
+            .map(|a| parse_proposition(a).unwrap())
+            .collect();
+        let goal = parse_proposition("f /\\ b").unwrap();
+
+        let core = minimal_core(&assumptions, &goal).unwrap();
+        let kept: Vec<Proposition> = core.iter().map(|&i| assumptions[i].clone()).collect();
+
+        assert_eq!(core, vec!(0, 1, 2, 3, 6));
+        assert!(resolve(kept.clone(), goal.clone()));
+
+        // Without any one of them, there is a model where the goal is false
+        for i in 0..kept.len() {
+            let mut fewer = kept.clone();
+            fewer[i] = not(goal.clone());
+            assert!(find_model(&fewer).is_some());
+        }
+    }
+
+    #[test]
+    fn minimal_core_contradictory_assumptions() {
+        let assumptions = vec!(
+            parse_proposition("p").unwrap(),
+            parse_proposition("q").unwrap(),
+            parse_proposition("~p").unwrap(),
+        );
+
+        let goal = parse_proposition("r").unwrap();
+
+        assert_eq!(minimal_core(&assumptions, &goal), Some(vec!(0, 2)));
+    }
+
+    #[test]
+    fn minimal_core_tautology() {
+        let goal = parse_proposition("p \\/ ~p").unwrap();
+
+        assert_eq!(minimal_core(&[term("q".to_string())], &goal), Some(vec!()));
+    }
+
+    #[test]
+    fn used_assumptions_is_core() {
+        let assumptions = vec!(
+            parse_proposition("p").unwrap(),
+            parse_proposition("s").unwrap(),
+            parse_proposition("p -> q").unwrap(),
+            parse_proposition("q -> r").unwrap(),
+        );
+
+        let goal = parse_proposition("r").unwrap();
+
+        let proof = prove(assumptions.clone(), goal.clone()).unwrap();
+
+        assert_eq!(proof.used_assumptions(), vec!(0, 2, 3));
+        assert_eq!(minimal_core(&assumptions, &goal), Some(vec!(0, 2, 3)));
+    }
+}
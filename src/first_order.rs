@@ -0,0 +1,1262 @@
+//! First-order logic with function and predicate symbols, quantifiers, and
+//! resolution with unification.
+//!
+//! The connectives and constructors here share their names with the
+//! propositional ones, so this module is not re-exported at the top level of
+//! the crate.
+//!
+//! ```
+//! use resolution_prover::first_order::*;
+//!
+//! let x = || variable("x".to_string());
+//! let socrates = || constant("socrates".to_string());
+//!
+//! let assumptions = vec!(
+//!     forall("x".to_string(), implies(
+//!         predicate("human".to_string(), vec!(x())),
+//!         predicate("mortal".to_string(), vec!(x()))
+//!     )),
+//!     predicate("human".to_string(), vec!(socrates()))
+//! );
+//!
+//! let goal = predicate("mortal".to_string(), vec!(socrates()));
+//!
+//! assert_eq!(resolve(assumptions, goal, 100), Outcome::Proved);
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// A term, which is either a variable or a function symbol applied to
+/// arguments. Constants are functions with no arguments.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(Ord)]
+#[derive(PartialEq)]
+#[derive(PartialOrd)]
+pub enum Term {
+    Variable(String),
+    Function(String, Vec<Term>)
+}
+
+/// A statement in first-order logic.
+///
+/// For example, `forall x. human(x) -> mortal(x)` would be represented in
+/// the following way.
+///
+/// ```
+/// use resolution_prover::first_order::*;
+///
+/// let x = || variable("x".to_string());
+///
+/// let all_mortal = forall("x".to_string(), implies(
+///     predicate("human".to_string(), vec!(x())),
+///     predicate("mortal".to_string(), vec!(x()))
+/// ));
+///
+/// assert_eq!(all_mortal.to_string(), "forall x. (human(x) -> mortal(x))");
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Formula {
+    Predicate(String, Vec<Term>),
+    Not(Box<Formula>),
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+    Implies(Box<Formula>, Box<Formula>),
+    Iff(Box<Formula>, Box<Formula>),
+    ForAll(String, Box<Formula>),
+    Exists(String, Box<Formula>)
+}
+
+/// Creates a variable with the given name.
+pub fn variable(name: String) -> Term {
+    Term::Variable(name)
+}
+
+/// Creates a constant with the given name.
+///
+/// ```
+/// let a = resolution_prover::first_order::constant("a".to_string());
+///
+/// assert_eq!(a.to_string(), "a");
+/// ```
+pub fn constant(name: String) -> Term {
+    Term::Function(name, vec!())
+}
+
+/// Creates a term that applies the given function symbol to the given
+/// arguments.
+///
+/// ```
+/// use resolution_prover::first_order::*;
+///
+/// let f = function(
+///     "f".to_string(),
+///     vec!(variable("x".to_string()), constant("a".to_string()))
+/// );
+///
+/// assert_eq!(f.to_string(), "f(x, a)");
+/// ```
+pub fn function(name: String, arguments: Vec<Term>) -> Term {
+    Term::Function(name, arguments)
+}
+
+/// Creates a formula that applies the given predicate symbol to the given
+/// arguments.
+pub fn predicate(name: String, arguments: Vec<Term>) -> Formula {
+    Formula::Predicate(name, arguments)
+}
+
+/// Creates a formula that is the negation of the given formula.
+pub fn not(formula: Formula) -> Formula {
+    Formula::Not(Box::new(formula))
+}
+
+/// Creates a formula that is the conjunction of the two given formulas.
+pub fn and(a: Formula, b: Formula) -> Formula {
+    Formula::And(Box::new(a), Box::new(b))
+}
+
+/// Creates a formula that is the disjunction of the two given formulas.
+pub fn or(a: Formula, b: Formula) -> Formula {
+    Formula::Or(Box::new(a), Box::new(b))
+}
+
+/// Creates a formula that is the implication with the two given formulas as
+/// the antecedent and consequent respectively.
+pub fn implies(a: Formula, b: Formula) -> Formula {
+    Formula::Implies(Box::new(a), Box::new(b))
+}
+
+/// Creates a formula that is the biconditional of the two given formulas.
+pub fn iff(a: Formula, b: Formula) -> Formula {
+    Formula::Iff(Box::new(a), Box::new(b))
+}
+
+/// Creates a formula that universally quantifies the given variable over the
+/// given formula.
+pub fn forall(variable: String, formula: Formula) -> Formula {
+    Formula::ForAll(variable, Box::new(formula))
+}
+
+/// Creates a formula that existentially quantifies the given variable over
+/// the given formula.
+///
+/// ```
+/// use resolution_prover::first_order::*;
+///
+/// let someone_loves_a = exists("x".to_string(), predicate(
+///     "loves".to_string(),
+///     vec!(variable("x".to_string()), constant("a".to_string()))
+/// ));
+///
+/// assert_eq!(someone_loves_a.to_string(), "exists x. loves(x, a)");
+/// ```
+pub fn exists(variable: String, formula: Formula) -> Formula {
+    Formula::Exists(variable, Box::new(formula))
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Term::Variable(ref a) => write!(f, "{}", a),
+            Term::Function(ref a, ref args) if args.is_empty() => write!(f, "{}", a),
+            Term::Function(ref a, ref args) => write!(f, "{}({})", a, join(args)),
+        }
+    }
+}
+
+impl fmt::Display for Formula {
+    /// Displays the formula using the same ascii operators as propositions,
+    /// with `forall x.` and `exists x.` for the quantifiers.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operand = |p: &Formula| match *p {
+            Formula::Predicate(..) | Formula::Not(_) => p.to_string(),
+            _ => format!("({})", p),
+        };
+
+        match *self {
+            Formula::Predicate(ref a, ref args) if args.is_empty() => write!(f, "{}", a),
+            Formula::Predicate(ref a, ref args) => write!(f, "{}({})", a, join(args)),
+            Formula::Not(ref a) => write!(f, "~({})", a),
+            Formula::And(ref a, ref b) => write!(f, "{} /\\ {}", operand(a), operand(b)),
+            Formula::Or(ref a, ref b) => write!(f, "{} \\/ {}", operand(a), operand(b)),
+            Formula::Implies(ref a, ref b) => write!(f, "{} -> {}", operand(a), operand(b)),
+            Formula::Iff(ref a, ref b) => write!(f, "{} <-> {}", operand(a), operand(b)),
+            Formula::ForAll(ref x, ref a) => write!(f, "forall {}. {}", x, operand(a)),
+            Formula::Exists(ref x, ref a) => write!(f, "exists {}. {}", x, operand(a)),
+        }
+    }
+}
+
+/// Joins the given terms with commas.
+fn join(terms: &[Term]) -> String {
+    terms.iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// A mapping from variable names to the terms that they are replaced with.
+pub type Substitution = BTreeMap<String, Term>;
+
+impl Term {
+    /// Applies the given substitution to the term, following any chains of
+    /// variable bindings in the substitution.
+    ///
+    /// ```
+    /// use resolution_prover::first_order::*;
+    ///
+    /// let mut substitution = Substitution::new();
+    /// substitution.insert("x".to_string(), constant("a".to_string()));
+    ///
+    /// let term = function("f".to_string(), vec!(variable("x".to_string())));
+    ///
+    /// assert_eq!(term.apply(&substitution).to_string(), "f(a)");
+    /// ```
+    pub fn apply(&self, substitution: &Substitution) -> Term {
+        match *self {
+            Term::Variable(ref x) => match substitution.get(x) {
+                Some(t) => t.apply(substitution),
+                None => self.clone()
+            },
+            Term::Function(ref f, ref args) => Term::Function(
+                f.clone(),
+                args.iter().map(|a| a.apply(substitution)).collect()
+            ),
+        }
+    }
+
+    /// Checks whether the given variable occurs within the term once the
+    /// given substitution is applied.
+    fn occurs(&self, variable: &str, substitution: &Substitution) -> bool {
+        match *self {
+            Term::Variable(ref x) if x == variable => true,
+            Term::Variable(ref x) => match substitution.get(x) {
+                Some(t) => t.occurs(variable, substitution),
+                None => false
+            },
+            Term::Function(_, ref args) =>
+                args.iter().any(|a| a.occurs(variable, substitution)),
+        }
+    }
+
+    /// Adds the names of the variables and function symbols of the term to
+    /// the given set.
+    fn collect_names(&self, names: &mut HashSet<String>) {
+        match *self {
+            Term::Variable(ref x) => {
+                names.insert(x.clone());
+            },
+            Term::Function(ref f, ref args) => {
+                names.insert(f.clone());
+                for a in args {
+                    a.collect_names(names);
+                }
+            },
+        }
+    }
+
+    /// Adds the variables of the term that are not in `bound` to `free`, in
+    /// order of their first appearance.
+    fn free_variables(&self, bound: &[String], free: &mut Vec<String>) {
+        match *self {
+            Term::Variable(ref x) => {
+                if !bound.contains(x) && !free.contains(x) {
+                    free.push(x.clone());
+                }
+            },
+            Term::Function(_, ref args) => {
+                for a in args {
+                    a.free_variables(bound, free);
+                }
+            },
+        }
+    }
+
+    /// Counts the number of symbols in the term.
+    fn weight(&self) -> usize {
+        match *self {
+            Term::Variable(_) => 1,
+            Term::Function(_, ref args) =>
+                1 + args.iter().map(|a| a.weight()).sum::<usize>(),
+        }
+    }
+
+    /// Renames the variables of the term using the given renaming, adding a
+    /// new name from `fresh` for any variable seen for the first time.
+    fn rename(&self, renaming: &mut BTreeMap<String, String>, fresh: &dyn Fn(usize) -> String) -> Term {
+        match *self {
+            Term::Variable(ref x) => {
+                let next = renaming.len();
+                let name = renaming.entry(x.clone())
+                    .or_insert_with(|| fresh(next));
+                Term::Variable(name.clone())
+            },
+            Term::Function(ref f, ref args) => Term::Function(
+                f.clone(),
+                args.iter().map(|a| a.rename(renaming, fresh)).collect()
+            ),
+        }
+    }
+}
+
+/// Finds the most general unifier of the two given terms, if they can be
+/// unified.
+///
+/// A variable is never bound to a term that contains it, so `x` and `f(x)`
+/// do not unify.
+///
+/// ```
+/// use resolution_prover::first_order::*;
+///
+/// let x = || variable("x".to_string());
+/// let y = || variable("y".to_string());
+/// let a = || constant("a".to_string());
+///
+/// let s = function("f".to_string(), vec!(x(), a()));
+/// let t = function("f".to_string(), vec!(y(), y()));
+///
+/// let unifier = unify(&s, &t).unwrap();
+///
+/// assert_eq!(s.apply(&unifier), t.apply(&unifier));
+/// assert_eq!(s.apply(&unifier).to_string(), "f(a, a)");
+///
+/// let fx = function("f".to_string(), vec!(x()));
+///
+/// assert_eq!(unify(&x(), &fx), None);
+/// ```
+pub fn unify(a: &Term, b: &Term) -> Option<Substitution> {
+    let mut substitution = Substitution::new();
+
+    if unify_with(a, b, &mut substitution) {
+        Some(resolve_bindings(substitution))
+    } else {
+        None
+    }
+}
+
+/// Extends the given substitution so that it unifies the two terms,
+/// returning whether that was possible.
+fn unify_with(a: &Term, b: &Term, substitution: &mut Substitution) -> bool {
+    let a = walk(a, substitution);
+    let b = walk(b, substitution);
+
+    match (a, b) {
+        (Term::Variable(ref x), Term::Variable(ref y)) if x == y => true,
+        (Term::Variable(x), t) | (t, Term::Variable(x)) => {
+            if t.occurs(&x, substitution) {
+                false
+            } else {
+                substitution.insert(x, t);
+                true
+            }
+        },
+        (Term::Function(f, f_args), Term::Function(g, g_args)) => {
+            f == g
+                && f_args.len() == g_args.len()
+                && f_args.iter()
+                    .zip(g_args.iter())
+                    .all(|(s, t)| unify_with(s, t, substitution))
+        },
+    }
+}
+
+/// Follows the bindings of the given term while it is a bound variable.
+fn walk(term: &Term, substitution: &Substitution) -> Term {
+    match *term {
+        Term::Variable(ref x) => match substitution.get(x) {
+            Some(t) => walk(t, substitution),
+            None => term.clone()
+        },
+        _ => term.clone()
+    }
+}
+
+/// Applies the substitution to each of its own bindings, so that no bound
+/// variable appears in the terms that variables are bound to.
+fn resolve_bindings(substitution: Substitution) -> Substitution {
+    substitution.iter()
+        .map(|(x, t)| (x.clone(), t.apply(&substitution)))
+        .collect()
+}
+
+/// An atomic formula, made up of a predicate symbol applied to terms.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+pub struct Atom {
+    pub predicate: String,
+    pub arguments: Vec<Term>
+}
+
+impl Atom {
+    fn apply(&self, substitution: &Substitution) -> Atom {
+        Atom {
+            predicate: self.predicate.clone(),
+            arguments: self.arguments.iter()
+                .map(|a| a.apply(substitution))
+                .collect()
+        }
+    }
+
+    /// Extends the given substitution so that it unifies the two atoms,
+    /// returning whether that was possible.
+    fn unify_with(&self, other: &Atom, substitution: &mut Substitution) -> bool {
+        self.predicate == other.predicate
+            && self.arguments.len() == other.arguments.len()
+            && self.arguments.iter()
+                .zip(other.arguments.iter())
+                .all(|(s, t)| unify_with(s, t, substitution))
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.arguments.is_empty() {
+            write!(f, "{}", self.predicate)
+        } else {
+            write!(f, "{}({})", self.predicate, join(&self.arguments))
+        }
+    }
+}
+
+/// An atom or negated atom that is part of a clause.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+pub enum Literal {
+    Positive(Atom),
+    Negative(Atom)
+}
+
+impl Literal {
+    /// Returns the negated version of the literal.
+    pub fn negate(&self) -> Literal {
+        match *self {
+            Literal::Positive(ref a) => Literal::Negative(a.clone()),
+            Literal::Negative(ref a) => Literal::Positive(a.clone()),
+        }
+    }
+
+    fn atom(&self) -> &Atom {
+        match *self {
+            Literal::Positive(ref a) | Literal::Negative(ref a) => a,
+        }
+    }
+
+    fn is_positive(&self) -> bool {
+        match *self {
+            Literal::Positive(_) => true,
+            Literal::Negative(_) => false,
+        }
+    }
+
+    fn apply(&self, substitution: &Substitution) -> Literal {
+        match *self {
+            Literal::Positive(ref a) => Literal::Positive(a.apply(substitution)),
+            Literal::Negative(ref a) => Literal::Negative(a.apply(substitution)),
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Literal::Positive(ref a) => write!(f, "{}", a),
+            Literal::Negative(ref a) => write!(f, "~{}", a),
+        }
+    }
+}
+
+/// A disjunction of literals, where every variable is implicitly universally
+/// quantified.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+pub struct Clause {
+    pub literals: Vec<Literal>
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.literals.is_empty() {
+            return write!(f, "[]")
+        }
+
+        let literals: Vec<String> = self.literals.iter()
+            .map(|l| l.to_string())
+            .collect();
+
+        write!(f, "{}", literals.join(" \\/ "))
+    }
+}
+
+/// A quantifier pulled out to the front of a formula in prenex normal form.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum Quantifier {
+    ForAll(String),
+    Exists(String)
+}
+
+impl Clause {
+    /// Converts the given formula into the corresponding clauses, replacing
+    /// existentially quantified variables with Skolem functions.
+    ///
+    /// Free variables are treated as universally quantified over the whole
+    /// formula, so they become arguments of the Skolem functions. The
+    /// renamed variables and the Skolem functions are given names that do
+    /// not already appear in the formula.
+    ///
+    /// ```
+    /// use resolution_prover::first_order::*;
+    ///
+    /// let x = || variable("x".to_string());
+    /// let y = || variable("y".to_string());
+    ///
+    /// let everyone_has_a_mother = forall("x".to_string(), exists(
+    ///     "y".to_string(),
+    ///     predicate("mother".to_string(), vec!(y(), x()))
+    /// ));
+    ///
+    /// let clauses = Clause::from_formula(everyone_has_a_mother);
+    ///
+    /// assert_eq!(clauses.len(), 1);
+    /// assert_eq!(clauses[0].to_string(), "mother(sk1(x_1), x_1)");
+    /// ```
+    pub fn from_formula(formula: Formula) -> Vec<Clause> {
+        ClauseConverter::new().convert(formula)
+    }
+
+    /// Applies the given substitution to the clause, merging any literals
+    /// that become identical.
+    fn apply(&self, substitution: &Substitution) -> Clause {
+        let mut literals: Vec<Literal> = vec!();
+        for l in &self.literals {
+            let applied = l.apply(substitution);
+            if !literals.contains(&applied) {
+                literals.push(applied);
+            }
+        }
+
+        Clause { literals }
+    }
+
+    /// Renames the variables of the clause in order of their first
+    /// appearance, using the given function to name them.
+    fn rename(&self, fresh: &dyn Fn(usize) -> String) -> Clause {
+        let mut renaming = BTreeMap::new();
+
+        Clause {
+            literals: self.literals.iter()
+                .map(|l| {
+                    let atom = l.atom();
+                    let renamed = Atom {
+                        predicate: atom.predicate.clone(),
+                        arguments: atom.arguments.iter()
+                            .map(|a| a.rename(&mut renaming, fresh))
+                            .collect()
+                    };
+
+                    if l.is_positive() {
+                        Literal::Positive(renamed)
+                    } else {
+                        Literal::Negative(renamed)
+                    }
+                })
+                .collect()
+        }
+    }
+
+    /// Renames the variables of the clause to a standard form, so that
+    /// clauses that are the same up to variable names become equal.
+    fn normalize(&self) -> Clause {
+        self.rename(&|i| format!("X{}", i))
+    }
+
+    fn is_tautology(&self) -> bool {
+        self.literals.iter()
+            .any(|l| self.literals.contains(&l.negate()))
+    }
+
+    /// Counts the number of symbols in the clause.
+    fn weight(&self) -> usize {
+        self.literals.iter()
+            .map(|l| 1 + l.atom().arguments.iter().map(|a| a.weight()).sum::<usize>())
+            .sum()
+    }
+}
+
+/// Converts formulas into clauses, keeping track of the names used for
+/// renamed variables and Skolem functions so that they stay unique.
+struct ClauseConverter {
+    next_variable: usize,
+    next_skolem: usize,
+    used: HashSet<String>
+}
+
+impl ClauseConverter {
+    fn new() -> ClauseConverter {
+        ClauseConverter {
+            next_variable: 0,
+            next_skolem: 0,
+            used: HashSet::new()
+        }
+    }
+
+    /// Records the names of the variables and function symbols of the given
+    /// formula, so that no new name clashes with them.
+    fn avoid(&mut self, formula: &Formula) {
+        match *formula {
+            Formula::Predicate(_, ref args) => {
+                for a in args {
+                    a.collect_names(&mut self.used);
+                }
+            },
+            Formula::Not(ref a) => self.avoid(a),
+            Formula::And(ref a, ref b)
+            | Formula::Or(ref a, ref b)
+            | Formula::Implies(ref a, ref b)
+            | Formula::Iff(ref a, ref b) => {
+                self.avoid(a);
+                self.avoid(b);
+            },
+            Formula::ForAll(ref x, ref a) | Formula::Exists(ref x, ref a) => {
+                self.used.insert(x.clone());
+                self.avoid(a);
+            },
+        }
+    }
+
+    fn convert(&mut self, formula: Formula) -> Vec<Clause> {
+        self.avoid(&formula);
+        let closed = close(formula);
+        let no_implication = eliminate_implication(closed);
+        let red_negations = reduce_negation(no_implication);
+        let standardized = self.standardize_apart(red_negations, &BTreeMap::new());
+        let (prefix, matrix) = to_prenex(standardized);
+        let skolemized = self.skolemize(prefix, matrix);
+        let bubbled = bubble_up_ands(skolemized);
+        let or_not_formulas = split_on_ands(bubbled);
+
+        or_not_formulas.iter()
+            .map(|f| Clause { literals: from_or_not_formula(f) })
+            .collect()
+    }
+
+    /// Renames every quantified variable in the given formula so that no two
+    /// quantifiers use the same variable.
+    fn standardize_apart(&mut self, formula: Formula, renaming: &BTreeMap<String, String>) -> Formula {
+        match formula {
+            Formula::ForAll(x, a) => {
+                let (name, inner) = self.rename_bound(&x, renaming);
+                forall(name, self.standardize_apart(*a, &inner))
+            },
+            Formula::Exists(x, a) => {
+                let (name, inner) = self.rename_bound(&x, renaming);
+                exists(name, self.standardize_apart(*a, &inner))
+            },
+            Formula::And(a, b) => and(
+                self.standardize_apart(*a, renaming),
+                self.standardize_apart(*b, renaming)
+            ),
+            Formula::Or(a, b) => or(
+                self.standardize_apart(*a, renaming),
+                self.standardize_apart(*b, renaming)
+            ),
+            Formula::Not(a) => not(self.standardize_apart(*a, renaming)),
+            Formula::Predicate(p, args) => {
+                let substitution: Substitution = renaming.iter()
+                    .map(|(x, y)| (x.clone(), variable(y.clone())))
+                    .collect();
+
+                predicate(p, args.iter().map(|a| a.apply(&substitution)).collect())
+            },
+            f => panic!("Unexpected implies or iff: {}", f)
+        }
+    }
+
+    fn rename_bound(&mut self, x: &str, renaming: &BTreeMap<String, String>) -> (String, BTreeMap<String, String>) {
+        let name = fresh(&mut self.next_variable, &mut self.used, &|n| format!("{}_{}", x, n));
+
+        let mut inner = renaming.clone();
+        inner.insert(x.to_string(), name.clone());
+
+        (name, inner)
+    }
+
+    /// Removes the quantifiers from the front of the formula, replacing each
+    /// existentially quantified variable with a new function of the
+    /// universally quantified variables before it.
+    fn skolemize(&mut self, prefix: Vec<Quantifier>, matrix: Formula) -> Formula {
+        let mut universals = vec!();
+        let mut substitution = Substitution::new();
+
+        for q in prefix {
+            match q {
+                Quantifier::ForAll(x) => universals.push(variable(x)),
+                Quantifier::Exists(x) => {
+                    let name = fresh(&mut self.next_skolem, &mut self.used, &|n| format!("sk{}", n));
+                    substitution.insert(x, function(name, universals.clone()));
+                },
+            }
+        }
+
+        substitute(matrix, &substitution)
+    }
+}
+
+/// Returns the first name given by `name` for the numbers after the given
+/// counter that has not been used yet, marking it as used.
+fn fresh(counter: &mut usize, used: &mut HashSet<String>, name: &dyn Fn(usize) -> String) -> String {
+    loop {
+        *counter += 1;
+        let candidate = name(*counter);
+        if used.insert(candidate.clone()) {
+            return candidate
+        }
+    }
+}
+
+/// Universally quantifies the given formula over its free variables, in order
+/// of their first appearance.
+fn close(formula: Formula) -> Formula {
+    fn free_variables(formula: &Formula, bound: &mut Vec<String>, free: &mut Vec<String>) {
+        match *formula {
+            Formula::Predicate(_, ref args) => {
+                for a in args {
+                    a.free_variables(bound, free);
+                }
+            },
+            Formula::Not(ref a) => free_variables(a, bound, free),
+            Formula::And(ref a, ref b)
+            | Formula::Or(ref a, ref b)
+            | Formula::Implies(ref a, ref b)
+            | Formula::Iff(ref a, ref b) => {
+                free_variables(a, bound, free);
+                free_variables(b, bound, free);
+            },
+            Formula::ForAll(ref x, ref a) | Formula::Exists(ref x, ref a) => {
+                bound.push(x.clone());
+                free_variables(a, bound, free);
+                bound.pop();
+            },
+        }
+    }
+
+    let mut free = vec!();
+    free_variables(&formula, &mut vec!(), &mut free);
+
+    free.into_iter()
+        .rev()
+        .fold(formula, |f, x| forall(x, f))
+}
+
+/// Converts the given formula to an equivalent formula that does not use any
+/// implications or biconditionals.
+fn eliminate_implication(formula: Formula) -> Formula {
+    match formula {
+        Formula::Implies(a, b) => or(
+            not(eliminate_implication(*a)),
+            eliminate_implication(*b)
+        ),
+        Formula::Iff(a, b) => and(
+            eliminate_implication(implies(*a.clone(), *b.clone())),
+            eliminate_implication(implies(*b, *a))
+        ),
+        Formula::And(a, b) => and(eliminate_implication(*a), eliminate_implication(*b)),
+        Formula::Or(a, b) => or(eliminate_implication(*a), eliminate_implication(*b)),
+        Formula::Not(a) => not(eliminate_implication(*a)),
+        Formula::ForAll(x, a) => forall(x, eliminate_implication(*a)),
+        Formula::Exists(x, a) => exists(x, eliminate_implication(*a)),
+        f => f
+    }
+}
+
+/// Moves the negations in the given formula inwards as far as possible,
+/// using deMorgan's law and the duality of the quantifiers.
+///
+/// # Panics
+///
+/// This function assumes that all implications and biconditionals have
+/// already been removed from the formula, and will panic upon finding any.
+fn reduce_negation(formula: Formula) -> Formula {
+    match formula {
+        Formula::Not(a) => match *a {
+            Formula::Not(b) => reduce_negation(*b),
+            Formula::And(b, c) => or(
+                reduce_negation(not(*b)),
+                reduce_negation(not(*c))
+            ),
+            Formula::Or(b, c) => and(
+                reduce_negation(not(*b)),
+                reduce_negation(not(*c))
+            ),
+            Formula::ForAll(x, b) => exists(x, reduce_negation(not(*b))),
+            Formula::Exists(x, b) => forall(x, reduce_negation(not(*b))),
+            p @ Formula::Predicate(..) => not(p),
+            p => panic!("Unexpected implies or iff: {}", not(p))
+        },
+        Formula::And(a, b) => and(reduce_negation(*a), reduce_negation(*b)),
+        Formula::Or(a, b) => or(reduce_negation(*a), reduce_negation(*b)),
+        Formula::ForAll(x, a) => forall(x, reduce_negation(*a)),
+        Formula::Exists(x, a) => exists(x, reduce_negation(*a)),
+        p @ Formula::Predicate(..) => p,
+        p => panic!("Unexpected implies or iff: {}", p)
+    }
+}
+
+/// Pulls all of the quantifiers of the given formula out to its front,
+/// returning them in order along with the quantifier-free remainder.
+///
+/// Assumes that the formula is in negation normal form and has had its
+/// variables standardized apart.
+fn to_prenex(formula: Formula) -> (Vec<Quantifier>, Formula) {
+    match formula {
+        Formula::ForAll(x, a) => {
+            let (mut prefix, matrix) = to_prenex(*a);
+            prefix.insert(0, Quantifier::ForAll(x));
+            (prefix, matrix)
+        },
+        Formula::Exists(x, a) => {
+            let (mut prefix, matrix) = to_prenex(*a);
+            prefix.insert(0, Quantifier::Exists(x));
+            (prefix, matrix)
+        },
+        Formula::And(a, b) => {
+            let (mut prefix, a_matrix) = to_prenex(*a);
+            let (mut b_prefix, b_matrix) = to_prenex(*b);
+            prefix.append(&mut b_prefix);
+            (prefix, and(a_matrix, b_matrix))
+        },
+        Formula::Or(a, b) => {
+            let (mut prefix, a_matrix) = to_prenex(*a);
+            let (mut b_prefix, b_matrix) = to_prenex(*b);
+            prefix.append(&mut b_prefix);
+            (prefix, or(a_matrix, b_matrix))
+        },
+        f => (vec!(), f)
+    }
+}
+
+/// Applies the given substitution to the terms of a quantifier-free formula.
+fn substitute(formula: Formula, substitution: &Substitution) -> Formula {
+    match formula {
+        Formula::Predicate(p, args) =>
+            predicate(p, args.iter().map(|a| a.apply(substitution)).collect()),
+        Formula::Not(a) => not(substitute(*a, substitution)),
+        Formula::And(a, b) => and(substitute(*a, substitution), substitute(*b, substitution)),
+        Formula::Or(a, b) => or(substitute(*a, substitution), substitute(*b, substitution)),
+        f => panic!("Unexpected quantifier, implies, or iff: {}", f)
+    }
+}
+
+/// Bubbles up the conjunctions in the given quantifier-free formula so that
+/// it becomes the conjunction of disjunctions of literals.
+fn bubble_up_ands(formula: Formula) -> Formula {
+    match formula {
+        Formula::And(a, b) => and(bubble_up_ands(*a), bubble_up_ands(*b)),
+        Formula::Or(a, b) => distribute(bubble_up_ands(*a), bubble_up_ands(*b)),
+        f => f
+    }
+}
+
+/// Distributes the disjunction of the two given formulas over any
+/// conjunctions at their tops.
+fn distribute(a: Formula, b: Formula) -> Formula {
+    match (a, b) {
+        (Formula::And(c, d), e) =>
+            and(distribute(*c, e.clone()), distribute(*d, e)),
+        (c, Formula::And(d, e)) =>
+            and(distribute(c.clone(), *d), distribute(c, *e)),
+        (c, d) => or(c, d)
+    }
+}
+
+/// Splits the given formula on its conjunctions.
+fn split_on_ands(formula: Formula) -> Vec<Formula> {
+    match formula {
+        Formula::And(a, b) => {
+            let mut a_parts = split_on_ands(*a);
+            let mut b_parts = split_on_ands(*b);
+
+            a_parts.append(&mut b_parts);
+
+            a_parts
+        },
+        f => vec!(f)
+    }
+}
+
+/// Converts the given disjunction of literals into the literals of a clause.
+fn from_or_not_formula(formula: &Formula) -> Vec<Literal> {
+    match *formula {
+        Formula::Or(ref a, ref b) => {
+            let mut a_parts = from_or_not_formula(a);
+            let mut b_parts = from_or_not_formula(b);
+
+            a_parts.append(&mut b_parts);
+
+            a_parts
+        },
+        Formula::Not(ref inner) => match **inner {
+            Formula::Predicate(ref p, ref args) => vec!(Literal::Negative(Atom {
+                predicate: p.clone(),
+                arguments: args.clone()
+            })),
+            _ => panic!("Formula contained non-(or, not) term: {}", formula)
+        },
+        Formula::Predicate(ref p, ref args) => vec!(Literal::Positive(Atom {
+            predicate: p.clone(),
+            arguments: args.clone()
+        })),
+        _ => panic!("Formula contained non-(or, not) term: {}", formula)
+    }
+}
+
+/// The result of searching for a first-order resolution refutation.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Outcome {
+    /// The empty clause was derived, so the goal follows from the
+    /// assumptions.
+    Proved,
+    /// Every possible resolvent was derived without reaching the empty
+    /// clause, so the goal does not follow from the assumptions.
+    NotProvable,
+    /// The step limit was reached before the search finished.
+    LimitReached
+}
+
+/// Checks if the given goal formula follows from the given assumptions, using
+/// resolution with unification and factoring.
+///
+/// First-order provability is only semi-decidable, so the search gives up
+/// after processing `max_steps` clauses. Free variables are treated as
+/// universally quantified, in the assumptions and in the goal alike, and
+/// equality is not given any special treatment.
+///
+/// ```
+/// use resolution_prover::first_order::*;
+///
+/// let x = || variable("x".to_string());
+/// let a = || constant("a".to_string());
+///
+/// let assumptions = vec!(
+///     forall("x".to_string(), implies(
+///         predicate("p".to_string(), vec!(x())),
+///         predicate("p".to_string(), vec!(function("f".to_string(), vec!(x()))))
+///     )),
+///     predicate("p".to_string(), vec!(a()))
+/// );
+///
+/// let goal = predicate("q".to_string(), vec!(a()));
+///
+/// assert_eq!(resolve(assumptions, goal, 50), Outcome::LimitReached);
+/// ```
+pub fn resolve(assumptions: Vec<Formula>, goal: Formula, max_steps: usize) -> Outcome {
+    // The new names must not clash with any of the formulas, not just the
+    // ones converted before them
+    let mut converter = ClauseConverter::new();
+    for a in &assumptions {
+        converter.avoid(a);
+    }
+    converter.avoid(&goal);
+
+    let mut inputs: Vec<Clause> = assumptions.into_iter()
+        .flat_map(|a| converter.convert(a))
+        .collect();
+    inputs.append(&mut converter.convert(not(close(goal))));
+
+    let mut seen: HashSet<Clause> = HashSet::new();
+    let mut unprocessed: Vec<Clause> = vec!();
+    for c in inputs {
+        let normalized = c.apply(&Substitution::new()).normalize();
+        if normalized.literals.is_empty() {
+            return Outcome::Proved
+        }
+        if !normalized.is_tautology() && seen.insert(normalized.clone()) {
+            unprocessed.push(normalized);
+        }
+    }
+
+    let mut processed: Vec<Clause> = vec!();
+    let mut steps = 0;
+
+    while !unprocessed.is_empty() {
+        steps += 1;
+        if steps > max_steps {
+            return Outcome::LimitReached
+        }
+
+        // Process the lightest clause next, preferring older clauses on ties
+        let lightest = (0..unprocessed.len())
+            .min_by_key(|i| (unprocessed[*i].weight(), *i))
+            .unwrap();
+        let given = unprocessed.remove(lightest);
+        processed.push(given.clone());
+
+        let mut derived = factors(&given);
+        for other in &processed {
+            derived.append(&mut resolvents(&given, other));
+        }
+
+        for c in derived {
+            let normalized = c.normalize();
+            if normalized.literals.is_empty() {
+                return Outcome::Proved
+            }
+            if !normalized.is_tautology() && seen.insert(normalized.clone()) {
+                unprocessed.push(normalized);
+            }
+        }
+    }
+
+    Outcome::NotProvable
+}
+
+/// Returns every binary resolvent of the two given clauses, after renaming
+/// the variables of the second clause apart from those of the first.
+fn resolvents(a: &Clause, b: &Clause) -> Vec<Clause> {
+    let b = b.rename(&|i| format!("Y{}", i));
+    let mut results = vec!();
+
+    for (i, l) in a.literals.iter().enumerate() {
+        for (j, m) in b.literals.iter().enumerate() {
+            if l.is_positive() == m.is_positive() {
+                continue
+            }
+
+            let mut substitution = Substitution::new();
+            if !l.atom().unify_with(m.atom(), &mut substitution) {
+                continue
+            }
+
+            let rest = Clause {
+                literals: a.literals.iter().enumerate()
+                    .filter(|&(k, _)| k != i)
+                    .map(|(_, l)| l.clone())
+                    .chain(b.literals.iter().enumerate()
+                        .filter(|&(k, _)| k != j)
+                        .map(|(_, m)| m.clone()))
+                    .collect()
+            };
+
+            results.push(rest.apply(&substitution));
+        }
+    }
+
+    results
+}
+
+/// Returns every factor of the given clause that comes from unifying two of
+/// its literals with the same sign.
+fn factors(clause: &Clause) -> Vec<Clause> {
+    let mut results = vec!();
+
+    for (i, l) in clause.literals.iter().enumerate() {
+        for m in clause.literals.iter().skip(i + 1) {
+            if l.is_positive() != m.is_positive() {
+                continue
+            }
+
+            let mut substitution = Substitution::new();
+            if l.atom().unify_with(m.atom(), &mut substitution) {
+                results.push(clause.apply(&substitution));
+            }
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use first_order::*;
+
+    fn x() -> Term {
+        variable("x".to_string())
+    }
+
+    fn y() -> Term {
+        variable("y".to_string())
+    }
+
+    fn a() -> Term {
+        constant("a".to_string())
+    }
+
+    fn f(t: Term) -> Term {
+        function("f".to_string(), vec!(t))
+    }
+
+    fn p(t: Term) -> Formula {
+        predicate("p".to_string(), vec!(t))
+    }
+
+    fn q(t: Term) -> Formula {
+        predicate("q".to_string(), vec!(t))
+    }
+
+    #[test]
+    fn unify_nested() {
+        let s = function("g".to_string(), vec!(x(), f(y())));
+        let t = function("g".to_string(), vec!(f(a()), x()));
+
+        let unifier = unify(&s, &t).unwrap();
+
+        assert_eq!(s.apply(&unifier), t.apply(&unifier));
+        assert_eq!(s.apply(&unifier).to_string(), "g(f(a), f(a))");
+    }
+
+    #[test]
+    fn unify_occurs_check_through_binding() {
+        let s = function("g".to_string(), vec!(x(), x()));
+        let t = function("g".to_string(), vec!(y(), f(y())));
+
+        assert_eq!(unify(&s, &t), None);
+    }
+
+    #[test]
+    fn unify_clashing_functions() {
+        let s = f(x());
+        let t = function("h".to_string(), vec!(a()));
+
+        assert_eq!(unify(&s, &t), None);
+    }
+
+    #[test]
+    fn reduce_negation_quantifiers() {
+        let formula = not(forall("x".to_string(), exists("y".to_string(), p(x()))));
+
+        let expected = exists("x".to_string(), forall("y".to_string(), not(p(x()))));
+
+        assert_eq!(reduce_negation(formula), expected);
+    }
+
+    #[test]
+    fn to_prenex_standardized() {
+        let formula = and(
+            forall("x".to_string(), p(x())),
+            exists("x".to_string(), q(x()))
+        );
+
+        let mut converter = ClauseConverter::new();
+        let standardized = converter.standardize_apart(formula, &BTreeMap::new());
+        let (prefix, matrix) = to_prenex(standardized);
+
+        assert_eq!(prefix, vec!(
+            Quantifier::ForAll("x_1".to_string()),
+            Quantifier::Exists("x_2".to_string())
+        ));
+        assert_eq!(matrix.to_string(), "p(x_1) /\\ q(x_2)");
+    }
+
+    #[test]
+    fn from_formula_skolem_constant_and_distribution() {
+        let formula = exists("x".to_string(), or(
+            p(x()),
+            and(q(x()), q(a()))
+        ));
+
+        let clauses: Vec<String> = Clause::from_formula(formula).iter()
+            .map(|c| c.to_string())
+            .collect();
+
+        assert_eq!(clauses, vec!("p(sk1) \\/ q(sk1)", "p(sk1) \\/ q(a)"));
+    }
+
+    #[test]
+    fn from_formula_fresh_names() {
+        // The input already uses the names that the renamed variable and the
+        // Skolem function would get
+        let formula = forall("x".to_string(), exists(
+            "y".to_string(),
+            predicate("r".to_string(), vec!(
+                y(),
+                x(),
+                variable("x_1".to_string()),
+                constant("sk1".to_string())
+            ))
+        ));
+
+        let clauses: Vec<String> = Clause::from_formula(formula).iter()
+            .map(|c| c.to_string())
+            .collect();
+
+        assert_eq!(clauses, vec!("r(sk2(x_1_1, x_2), x_2, x_1_1, sk1)"));
+    }
+
+    #[test]
+    fn resolve_free_variables() {
+        let loves = |a: Term, b: Term| predicate("loves".to_string(), vec!(a, b));
+
+        // Everyone loves someone, with x left free or bound, does not mean
+        // that someone is loved by everyone
+        let goal = exists("y".to_string(), forall("x".to_string(), loves(x(), y())));
+        let free = exists("y".to_string(), loves(x(), y()));
+
+        assert_eq!(resolve(vec!(free.clone()), goal.clone(), 100), Outcome::NotProvable);
+        assert_eq!(
+            resolve(vec!(forall("x".to_string(), free.clone())), goal, 100),
+            Outcome::NotProvable
+        );
+
+        // A free variable in the goal is universal too
+        let everyone = forall("x".to_string(), forall("y".to_string(), loves(x(), y())));
+        assert_eq!(resolve(vec!(everyone), loves(x(), a()), 100), Outcome::Proved);
+        assert_eq!(resolve(vec!(loves(a(), a())), loves(x(), a()), 100), Outcome::NotProvable);
+    }
+
+    #[test]
+    fn resolve_needs_factoring() {
+        // (p(x) \/ p(y)) and ~p(x) \/ ~p(y) are only contradictory once
+        // each clause is factored
+        let assumptions = vec!(
+            forall("x".to_string(), forall("y".to_string(), or(p(x()), p(y())))),
+            forall("x".to_string(), forall("y".to_string(), or(not(p(x())), not(p(y())))))
+        );
+
+        let goal = q(a());
+
+        assert_eq!(resolve(assumptions, goal, 100), Outcome::Proved);
+    }
+
+    #[test]
+    fn resolve_existential_goal() {
+        let assumptions = vec!(
+            forall("x".to_string(), implies(p(x()), q(f(x())))),
+            p(a())
+        );
+
+        let goal = exists("y".to_string(), q(y()));
+
+        assert_eq!(resolve(assumptions, goal, 100), Outcome::Proved);
+    }
+
+    #[test]
+    fn resolve_not_provable() {
+        let assumptions = vec!(
+            forall("x".to_string(), implies(p(x()), q(x()))),
+            p(a())
+        );
+
+        let goal = q(constant("b".to_string()));
+
+        assert_eq!(resolve(assumptions, goal, 100), Outcome::NotProvable);
+    }
+}
@@ -0,0 +1,373 @@
+use clauses::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Checks whether every one of the given clauses is a Horn clause, meaning
+/// that it contains at most one term that is not negated.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let rule = Clause::from_proposition(implies(
+///     and(term("p".to_string()), term("q".to_string())),
+///     term("r".to_string())
+/// ));
+///
+/// assert!(is_horn(&rule));
+///
+/// let choice = Clause::from_proposition(or(
+///     term("p".to_string()),
+///     term("q".to_string())
+/// ));
+///
+/// assert!(!is_horn(&choice));
+/// ```
+pub fn is_horn(clauses: &[Clause]) -> bool {
+    clauses.iter().all(|c| positive_term(c).is_ok())
+}
+
+/// Returns the term of the given clause that is not negated, if there is
+/// one, or an error if there is more than one.
+fn positive_term(clause: &Clause) -> Result<Option<&String>, ()> {
+    let mut positives = clause.parts.iter().filter_map(|p| match *p {
+        ClausePart::Term(ref a) => Some(a),
+        ClausePart::NegatedTerm(_) => None,
+    });
+
+    let first = positives.next();
+    if positives.any(|p| Some(p) != first) {
+        Err(())
+    } else {
+        Ok(first)
+    }
+}
+
+/// The result of forward chaining over a set of Horn clauses.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ForwardChaining {
+    /// The terms that are derivable from the clauses, in the order that they
+    /// were derived.
+    pub derived: Vec<String>,
+    /// The index of a clause with no term that is not negated, all of whose
+    /// terms were derived, showing that the clauses are contradictory.
+    pub contradiction: Option<usize>
+}
+
+/// Derives every term that follows from the given Horn clauses, in time
+/// linear in the total size of the clauses.
+///
+/// Each clause is treated as a rule whose negated terms are its premises and
+/// whose term that is not negated is its conclusion. Clauses without such a
+/// term are constraints, and if all of the premises of a constraint are
+/// derived then the clauses are contradictory.
+///
+/// # Panics
+///
+/// Panics if any of the clauses are not Horn clauses.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses: Vec<Clause> = vec!(
+///     term("p".to_string()),
+///     implies(
+///         and(term("p".to_string()), term("q".to_string())),
+///         term("r".to_string())
+///     ),
+///     implies(term("p".to_string()), term("q".to_string()))
+/// ).into_iter().flat_map(Clause::from_proposition).collect();
+///
+/// let result = forward_chain(&clauses);
+///
+/// assert_eq!(result.derived, vec!("p", "q", "r"));
+/// assert_eq!(result.contradiction, None);
+/// ```
+pub fn forward_chain(clauses: &[Clause]) -> ForwardChaining {
+    let mut remaining: Vec<usize> = vec!();
+    let mut watchers: HashMap<&String, Vec<usize>> = HashMap::new();
+    let mut queue: Vec<usize> = vec!();
+
+    for (i, c) in clauses.iter().enumerate() {
+        let premises: HashSet<&String> = c.parts.iter()
+            .filter_map(|p| match *p {
+                ClausePart::NegatedTerm(ref a) => Some(a),
+                ClausePart::Term(_) => None,
+            })
+            .collect();
+
+        for p in &premises {
+            watchers.entry(p).or_default().push(i);
+        }
+
+        remaining.push(premises.len());
+        if premises.is_empty() {
+            queue.push(i);
+        }
+    }
+
+    let mut derived: Vec<String> = vec!();
+    let mut known: HashSet<&String> = HashSet::new();
+
+    // Clauses are processed in the order that they become satisfied, so the
+    // queue is used first in, first out
+    let mut next = 0;
+    while next < queue.len() {
+        let i = queue[next];
+        next += 1;
+
+        let conclusion = positive_term(&clauses[i])
+            .expect("Expected only Horn clauses");
+
+        let atom = match conclusion {
+            Some(a) => a,
+            None => return ForwardChaining {
+                derived,
+                contradiction: Some(i)
+            }
+        };
+
+        if !known.insert(atom) {
+            continue
+        }
+        derived.push(atom.clone());
+
+        if let Some(ws) = watchers.get(atom) {
+            for w in ws {
+                remaining[*w] -= 1;
+                if remaining[*w] == 0 {
+                    queue.push(*w);
+                }
+            }
+        }
+    }
+
+    ForwardChaining {
+        derived,
+        contradiction: None
+    }
+}
+
+/// A derivation of a term by backward chaining, made up of the clause that
+/// concluded the term and the derivations of each of that clause's
+/// premises.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct HornTrace {
+    pub term: String,
+    pub clause: Clause,
+    pub premises: Vec<HornTrace>
+}
+
+impl fmt::Display for HornTrace {
+    /// Displays the derivation as an indented tree, with each term followed
+    /// by the clause used to derive it.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let clauses: Vec<Clause> = vec!(
+    ///     term("p".to_string()),
+    ///     implies(term("p".to_string()), term("q".to_string()))
+    /// ).into_iter().flat_map(Clause::from_proposition).collect();
+    ///
+    /// let trace = backward_chain(&clauses, "q").unwrap();
+    ///
+    /// assert_eq!(trace.to_string(), "q  (~p \\/ q)\n  p  (p)\n");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+impl HornTrace {
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        writeln!(f, "{}{}  ({})", "  ".repeat(depth), self.term, self.clause)?;
+
+        for p in &self.premises {
+            p.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Attempts to derive the given term from the given Horn clauses by working
+/// backwards from it, returning a trace of the derivation if one is found.
+///
+/// The clauses that conclude a term are tried in order, and a term is never
+/// used to derive itself.
+///
+/// # Panics
+///
+/// Panics if any of the clauses are not Horn clauses.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses: Vec<Clause> = vec!(
+///     term("p".to_string()),
+///     implies(term("q".to_string()), term("r".to_string()))
+/// ).into_iter().flat_map(Clause::from_proposition).collect();
+///
+/// assert!(backward_chain(&clauses, "p").is_some());
+/// assert!(backward_chain(&clauses, "r").is_none());
+/// ```
+pub fn backward_chain(clauses: &[Clause], goal: &str) -> Option<HornTrace> {
+    let mut rules: HashMap<&str, Vec<&Clause>> = HashMap::new();
+    for c in clauses {
+        if let Some(a) = positive_term(c).expect("Expected only Horn clauses") {
+            rules.entry(a.as_str()).or_default().push(c);
+        }
+    }
+
+    let mut proven = HashMap::new();
+    backward_chain_(&rules, goal, &mut vec!(), &mut proven)
+}
+
+fn backward_chain_<'a>(
+    rules: &HashMap<&str, Vec<&'a Clause>>,
+    goal: &'a str,
+    ancestors: &mut Vec<&'a str>,
+    proven: &mut HashMap<&'a str, HornTrace>
+) -> Option<HornTrace> {
+    if let Some(t) = proven.get(goal) {
+        return Some(t.clone())
+    }
+    if ancestors.contains(&goal) {
+        return None
+    }
+
+    ancestors.push(goal);
+
+    let mut result = None;
+    for clause in rules.get(goal).map(|r| r.as_slice()).unwrap_or(&[]) {
+        let mut premises = vec!();
+        let all_proven = clause.parts.iter().all(|p| match *p {
+            ClausePart::NegatedTerm(ref a) => {
+                match backward_chain_(rules, a, ancestors, proven) {
+                    Some(t) => {
+                        premises.push(t);
+                        true
+                    },
+                    None => false
+                }
+            },
+            ClausePart::Term(_) => true,
+        });
+
+        if all_proven {
+            result = Some(HornTrace {
+                term: goal.to_string(),
+                clause: (*clause).clone(),
+                premises
+            });
+            break
+        }
+    }
+
+    ancestors.pop();
+
+    if let Some(ref t) = result {
+        proven.insert(goal, t.clone());
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use horn::*;
+    use propositions::*;
+
+    fn to_clauses(props: Vec<Proposition>) -> Vec<Clause> {
+        props.into_iter()
+            .flat_map(Clause::from_proposition)
+            .collect()
+    }
+
+    #[test]
+    fn is_horn_repeated_positive() {
+        let clauses = vec!(Clause { parts: vec!(
+            ClausePart::Term("p".to_string()),
+            ClausePart::Term("p".to_string()),
+            ClausePart::NegatedTerm("q".to_string())
+        ) });
+
+        assert!(is_horn(&clauses));
+    }
+
+    #[test]
+    fn forward_chain_contradiction() {
+        let clauses = to_clauses(vec!(
+            term("p".to_string()),
+            implies(term("p".to_string()), term("q".to_string())),
+            not(and(term("p".to_string()), term("q".to_string())))
+        ));
+
+        let result = forward_chain(&clauses);
+
+        assert_eq!(result.derived, vec!("p", "q"));
+        assert_eq!(result.contradiction, Some(2));
+    }
+
+    #[test]
+    fn forward_chain_repeated_premise() {
+        let clauses = vec!(
+            Clause { parts: vec!(ClausePart::Term("p".to_string())) },
+            Clause { parts: vec!(
+                ClausePart::NegatedTerm("p".to_string()),
+                ClausePart::NegatedTerm("p".to_string()),
+                ClausePart::Term("q".to_string())
+            ) }
+        );
+
+        assert_eq!(forward_chain(&clauses).derived, vec!("p", "q"));
+    }
+
+    #[test]
+    fn backward_chain_readme_example() {
+        let clauses = to_clauses(vec!(
+            term("p".to_string()),
+            implies(
+                and(term("p".to_string()), term("q".to_string())),
+                term("r".to_string())
+            ),
+            implies(
+                or(term("s".to_string()), term("t".to_string())),
+                term("q".to_string())
+            ),
+            term("t".to_string())
+        ));
+
+        let trace = backward_chain(&clauses, "r").unwrap();
+
+        let expected = "\
+r  (~p \\/ ~q \\/ r)
+  p  (p)
+  q  (~t \\/ q)
+    t  (t)
+";
+
+        assert_eq!(trace.to_string(), expected);
+    }
+
+    #[test]
+    fn backward_chain_cycle() {
+        let clauses = to_clauses(vec!(
+            implies(term("p".to_string()), term("q".to_string())),
+            implies(term("q".to_string()), term("p".to_string()))
+        ));
+
+        assert_eq!(backward_chain(&clauses, "p"), None);
+    }
+}
@@ -0,0 +1,248 @@
+use clauses::*;
+use normal_forms::*;
+use proofs::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::{HashMap, HashSet};
+
+/// The rules used to build an interpolant from a resolution proof.
+///
+/// Both systems give each step of the proof a partial interpolant, built up
+/// from the input clauses. McMillan's system turns a clause from the first
+/// part into the disjunction of its shared literals, and gives the
+/// strongest interpolant of the two. Pudlák's system treats the two parts
+/// the same way, so swapping them gives the negation of the interpolant.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Eq)]
+#[derive(PartialEq)]
+pub enum InterpolationSystem {
+    #[default]
+    McMillan,
+    Pudlak
+}
+
+impl Proof {
+    /// Returns a Craig interpolant for the proof, where the assumptions at
+    /// the given indices make up the first part and the other assumptions,
+    /// along with the negated goal, make up the second.
+    ///
+    /// The interpolant follows from the first part, contradicts the second,
+    /// and only uses terms that appear in the clauses of both parts. Terms
+    /// defined to stand for subformulas of the assumptions or the goal are
+    /// replaced by those subformulas.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let assumptions = vec!(
+    ///     parse_proposition("p /\\ r").unwrap(),
+    ///     parse_proposition("p -> q").unwrap()
+    /// );
+    /// let goal = parse_proposition("q").unwrap();
+    ///
+    /// let proof = prove(assumptions, goal).unwrap();
+    /// let interpolant = proof.interpolant(&[0], InterpolationSystem::McMillan);
+    ///
+    /// assert_eq!(interpolant.to_string(), "p");
+    /// ```
+    pub fn interpolant(&self, a: &[usize], system: InterpolationSystem) -> Proposition {
+        let in_a = |step: &ProofStep| match step.justification {
+            Justification::Assumption(i) => a.contains(&i),
+            _ => false
+        };
+
+        let mut a_terms = HashSet::new();
+        let mut b_terms = HashSet::new();
+        for step in &self.steps {
+            if let Justification::Resolution { .. } = step.justification {
+                continue
+            }
+            let terms = if in_a(step) { &mut a_terms } else { &mut b_terms };
+            terms.extend(step.clause.parts.iter().map(|p| p.name().to_string()));
+        }
+
+        let mut partial: Vec<Proposition> = vec!();
+        for step in &self.steps {
+            let interpolant = match step.justification {
+                Justification::Resolution { left, right, ref pivot } => {
+                    let (l, r) = (partial[left].clone(), partial[right].clone());
+                    let term = pivot.name();
+                    match (a_terms.contains(term), b_terms.contains(term), system) {
+                        (true, false, _) => either(l, r),
+                        (false, true, _) | (_, _, InterpolationSystem::McMillan) => both(l, r),
+                        (_, _, InterpolationSystem::Pudlak) => both(
+                            either(literal(pivot), l),
+                            either(literal(&pivot.negate()), r)
+                        ),
+                    }
+                },
+                _ if !in_a(step) => top(),
+                _ => match system {
+                    InterpolationSystem::McMillan => step.clause.parts.iter()
+                        .filter(|p| b_terms.contains(p.name()))
+                        .fold(bottom(), |i, p| either(i, literal(p))),
+                    InterpolationSystem::Pudlak => bottom(),
+                },
+            };
+            partial.push(interpolant);
+        }
+
+        // Terms defined to stand for subformulas can be shared by both parts,
+        // so they are replaced by the subformulas that they stand for
+        let mut subformulas = HashMap::new();
+        let mut pending: Vec<&Proposition> = self.assumptions.iter().chain(Some(&self.goal)).collect();
+        while let Some(p) = pending.pop() {
+            subformulas.insert(format!("{{{}}}", p.to_unicode()), p);
+            pending.extend(p.operands());
+        }
+
+        partial.pop()
+            .unwrap_or_else(top)
+            .map_terms(&|t| subformulas.get(t).map_or_else(|| term(t.to_string()), |&p| p.clone()))
+    }
+}
+
+/// Returns a Craig interpolant for the two given parts, whose conjunction
+/// must be contradictory, or `None` if it is not.
+///
+/// The interpolant follows from the first part, contradicts the second, and
+/// only uses terms that appear in both parts. It is extracted from the
+/// proof found by `refute`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let a = vec!(parse_proposition("p /\\ (p -> q)").unwrap());
+/// let b = vec!(parse_proposition("~q /\\ r").unwrap());
+///
+/// let interpolant = interpolate(&a, &b, InterpolationSystem::Pudlak).unwrap();
+///
+/// assert_eq!(interpolant.terms(), vec!("q"));
+/// assert!(equivalent(&interpolant, &term("q".to_string())));
+///
+/// assert_eq!(interpolate(&a, &[], InterpolationSystem::Pudlak), None);
+/// ```
+pub fn interpolate(a: &[Proposition], b: &[Proposition], system: InterpolationSystem) -> Option<Proposition> {
+    let props: Vec<Proposition> = a.iter().chain(b).cloned().collect();
+    let proof = refute(&props)?;
+
+    let first: Vec<usize> = (0..a.len()).collect();
+    Some(proof.interpolant(&first, system))
+}
+
+#[cfg(test)]
+mod tests {
+    use interpolation::*;
+    use random::*;
+    use resolution::*;
+
+    /// Generates pseudorandom propositions over the given terms from a fixed
+    /// seed, so that the tests are repeatable.
+    struct Generator {
+        random: Random
+    }
+
+    impl Generator {
+        fn proposition(&mut self, terms: &[&str], depth: usize) -> Proposition {
+            if depth == 0 {
+                return term(terms[self.random.next(terms.len() as u64) as usize].to_string())
+            }
+
+            let a = self.proposition(terms, depth - 1);
+            let b = self.proposition(terms, depth - 1);
+            match self.random.next(6) {
+                0 => or(a, b),
+                1 => and(a, b),
+                2 => implies(a, b),
+                3 => iff(a, b),
+                4 => xor(a, b),
+                _ => not(a),
+            }
+        }
+    }
+
+    #[test]
+    fn interpolate_random() {
+        let mut generator = Generator { random: Random::new(47) };
+
+        for _ in 0..100 {
+            // Both parts mention f, which they disagree on, so they always
+            // contradict each other
+            let f = generator.proposition(&["q", "r"], 2);
+            let a = vec!(f.clone(), generator.proposition(&["p", "q", "r"], 3));
+            let b = vec!(generator.proposition(&["q", "r", "s"], 3), not(f));
+
+            let strongest = interpolate(&a, &b, InterpolationSystem::McMillan).unwrap();
+            let symmetric = interpolate(&a, &b, InterpolationSystem::Pudlak).unwrap();
+
+            for i in &[&strongest, &symmetric] {
+                assert!(i.terms().iter().all(|t| t == "q" || t == "r"), "{}", i);
+
+                let mut with_a = a.clone();
+                with_a.push(not((*i).clone()));
+                assert_eq!(find_model(&with_a), None, "{}", i);
+
+                let mut with_b = b.clone();
+                with_b.push((*i).clone());
+                assert_eq!(find_model(&with_b), None, "{}", i);
+            }
+
+            assert_eq!(find_model(&[strongest.clone(), not(symmetric.clone())]), None);
+        }
+    }
+
+    #[test]
+    fn interpolate_contradictory_part() {
+        let p = term("p".to_string());
+        let q = term("q".to_string());
+        let contradiction = and(p.clone(), not(p.clone()));
+
+        let a = vec!(contradiction.clone());
+        let b = vec!(q.clone());
+        assert_eq!(interpolate(&a, &b, InterpolationSystem::McMillan), Some(bottom()));
+        assert_eq!(interpolate(&a, &b, InterpolationSystem::Pudlak), Some(bottom()));
+
+        let a = vec!(q);
+        let b = vec!(contradiction);
+        assert_eq!(interpolate(&a, &b, InterpolationSystem::McMillan), Some(top()));
+        assert_eq!(interpolate(&a, &b, InterpolationSystem::Pudlak), Some(top()));
+    }
+
+    #[test]
+    fn interpolate_shared_subformula() {
+        // Both parts give p /\ q the same defined term, which the interpolant
+        // must not mention
+        let pq = and(term("p".to_string()), term("q".to_string()));
+
+        let a = vec!(xor(pq.clone(), term("r".to_string())), not(term("r".to_string())));
+        let b = vec!(xor(pq.clone(), term("s".to_string())), term("s".to_string()));
+
+        for &system in &[InterpolationSystem::McMillan, InterpolationSystem::Pudlak] {
+            let interpolant = interpolate(&a, &b, system).unwrap();
+
+            assert_eq!(interpolant.terms(), vec!("p", "q"));
+            assert!(equivalent(&interpolant, &pq), "{}", interpolant);
+        }
+    }
+
+    #[test]
+    fn interpolant_with_negated_goal() {
+        let assumptions = vec!(
+            implies(term("p".to_string()), term("q".to_string())),
+            implies(term("q".to_string()), term("r".to_string())),
+            term("p".to_string())
+        );
+        let goal = term("r".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        for &system in &[InterpolationSystem::McMillan, InterpolationSystem::Pudlak] {
+            let interpolant = proof.interpolant(&[0, 2], system);
+            assert!(equivalent(&interpolant, &term("q".to_string())), "{}", interpolant);
+        }
+    }
+}
@@ -1,7 +1,60 @@
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[macro_use]
+mod macros;
+
+mod bdd;
+mod bmc;
+mod circuits;
 mod clauses;
+mod cores;
+pub mod first_order;
+mod horn;
+mod interpolation;
+mod maxsat;
+mod minimize;
+mod models;
+mod narration;
+mod natural_deduction;
+mod normal_forms;
+mod notation;
+mod parser;
+mod proofs;
 mod propositions;
+mod puzzles;
+#[cfg(test)]
+mod random;
 mod resolution;
+mod sat;
+#[cfg(feature = "serde")]
+mod serialization;
+mod simplify;
+mod smtlib;
+mod tptp;
 
+pub use bdd::*;
+pub use bmc::*;
+pub use circuits::*;
 pub use clauses::*;
+pub use cores::*;
+pub use horn::*;
+pub use interpolation::*;
+pub use maxsat::*;
+pub use minimize::*;
+pub use models::*;
+pub use narration::*;
+pub use natural_deduction::*;
+pub use normal_forms::*;
+pub use notation::*;
+pub use parser::*;
+pub use proofs::*;
 pub use propositions::*;
+pub use puzzles::*;
 pub use resolution::*;
+pub use sat::*;
+#[cfg(feature = "serde")]
+pub use serialization::*;
+pub use simplify::*;
+pub use smtlib::*;
+pub use tptp::*;
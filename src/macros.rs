@@ -0,0 +1,257 @@
+/// Creates a proposition from formula syntax, checked at compile time.
+///
+/// Since Rust does not allow `\` in macro input, conjunction is written as
+/// `&` or `&&` and disjunction as `|` or `||`. Otherwise the syntax and
+/// precedence are the same as for `parse_proposition`, with `~` or `!` for
+/// negation, `->`, `<->`, `<+>` for exclusive disjunction, `~&` for negated
+/// conjunction, `~|` for negated disjunction, `true`, `false`, and
+/// `if c then p else q`. A conditional choice used as the condition or first
+/// branch of another one must be surrounded by parentheses.
+///
+/// A Rust expression surrounded by braces is converted into a proposition
+/// with `Proposition::from`, so that existing propositions and names can be
+/// included.
+///
+/// The macro reads the formula one token at a time, once for each level of
+/// precedence, so a long formula can reach the compiler's recursion limit.
+/// Conjunctions are read the most times, and with the default limit of 128 a
+/// conjunction of at most 13 terms can be written. Longer formulas can be
+/// put together from smaller ones with braces, or the crate that uses the
+/// macro can raise the limit, such as with `#![recursion_limit = "256"]`,
+/// which allows twice as many terms.
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate resolution_prover;
+///
+/// fn main() {
+///     prop!(a & b & c & d & e & f & g & h & i & j & k & l & m & n);
+/// }
+/// ```
+///
+/// ```
+/// #[macro_use]
+/// extern crate resolution_prover;
+///
+/// use resolution_prover::*;
+///
+/// fn main() {
+///     let prop = prop!((p & q) -> r);
+///
+///     let expected = implies(
+///         and(term("p".to_string()), term("q".to_string())),
+///         term("r".to_string())
+///     );
+///
+///     assert_eq!(prop, expected);
+///
+///     let goal = prop!(~{prop} | {"s"});
+///
+///     assert_eq!(goal.to_string(), "~(p /\\ q -> r) \\/ s");
+/// }
+/// ```
+#[macro_export]
+macro_rules! prop {
+    // Biconditionals and exclusive disjunctions, which group to the left
+    (@iff [$($acc:tt)*] [$($cur:tt)*] if $($rest:tt)+) => {
+        prop!(@fold [$($acc)*] prop!(@imp [] $($cur)* if $($rest)+))
+    };
+    (@iff [$($acc:tt)*] [$($cur:tt)+] <-> $($rest:tt)+) => {
+        prop!(@iff [iff, prop!(@fold [$($acc)*] prop!(@imp [] $($cur)+))] [] $($rest)+)
+    };
+    (@iff [$($acc:tt)*] [$($cur:tt)+] < + > $($rest:tt)+) => {
+        prop!(@iff [xor, prop!(@fold [$($acc)*] prop!(@imp [] $($cur)+))] [] $($rest)+)
+    };
+    (@iff [$($acc:tt)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        prop!(@iff [$($acc)*] [$($cur)* $next] $($rest)*)
+    };
+    (@iff [$($acc:tt)*] [$($cur:tt)+]) => {
+        prop!(@fold [$($acc)*] prop!(@imp [] $($cur)+))
+    };
+
+    // Implications, which group to the right
+    (@imp [$($cur:tt)*] if $($rest:tt)+) => {
+        prop!(@or [] [] $($cur)* if $($rest)+)
+    };
+    (@imp [$($cur:tt)+] -> $($rest:tt)+) => {
+        $crate::implies(prop!(@or [] [] $($cur)+), prop!(@imp [] $($rest)+))
+    };
+    (@imp [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        prop!(@imp [$($cur)* $next] $($rest)*)
+    };
+    (@imp [$($cur:tt)+]) => {
+        prop!(@or [] [] $($cur)+)
+    };
+
+    // Disjunctions and negated disjunctions, which group to the left
+    (@or [$($acc:tt)*] [$($cur:tt)*] if $($rest:tt)+) => {
+        prop!(@fold [$($acc)*] prop!(@and [] [] $($cur)* if $($rest)+))
+    };
+    (@or [$($acc:tt)*] [$($cur:tt)+] ~ | $($rest:tt)+) => {
+        prop!(@or [nor, prop!(@fold [$($acc)*] prop!(@and [] [] $($cur)+))] [] $($rest)+)
+    };
+    (@or [$($acc:tt)*] [$($cur:tt)+] | $($rest:tt)+) => {
+        prop!(@or [or, prop!(@fold [$($acc)*] prop!(@and [] [] $($cur)+))] [] $($rest)+)
+    };
+    (@or [$($acc:tt)*] [$($cur:tt)+] || $($rest:tt)+) => {
+        prop!(@or [or, prop!(@fold [$($acc)*] prop!(@and [] [] $($cur)+))] [] $($rest)+)
+    };
+    (@or [$($acc:tt)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        prop!(@or [$($acc)*] [$($cur)* $next] $($rest)*)
+    };
+    (@or [$($acc:tt)*] [$($cur:tt)+]) => {
+        prop!(@fold [$($acc)*] prop!(@and [] [] $($cur)+))
+    };
+
+    // Conjunctions and negated conjunctions, which group to the left
+    (@and [$($acc:tt)*] [$($cur:tt)*] if $($rest:tt)+) => {
+        prop!(@fold [$($acc)*] prop!(@unary $($cur)* if $($rest)+))
+    };
+    (@and [$($acc:tt)*] [$($cur:tt)+] ~ & $($rest:tt)+) => {
+        prop!(@and [nand, prop!(@fold [$($acc)*] prop!(@unary $($cur)+))] [] $($rest)+)
+    };
+    (@and [$($acc:tt)*] [$($cur:tt)+] & $($rest:tt)+) => {
+        prop!(@and [and, prop!(@fold [$($acc)*] prop!(@unary $($cur)+))] [] $($rest)+)
+    };
+    (@and [$($acc:tt)*] [$($cur:tt)+] && $($rest:tt)+) => {
+        prop!(@and [and, prop!(@fold [$($acc)*] prop!(@unary $($cur)+))] [] $($rest)+)
+    };
+    (@and [$($acc:tt)*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        prop!(@and [$($acc)*] [$($cur)* $next] $($rest)*)
+    };
+    (@and [$($acc:tt)*] [$($cur:tt)+]) => {
+        prop!(@fold [$($acc)*] prop!(@unary $($cur)+))
+    };
+
+    // Negations, parentheses, conditional choices, constants and terms
+    (@unary ~ $($rest:tt)+) => {
+        $crate::not(prop!(@unary $($rest)+))
+    };
+    (@unary ! $($rest:tt)+) => {
+        $crate::not(prop!(@unary $($rest)+))
+    };
+    (@unary if $($rest:tt)+) => {
+        prop!(@if [] $($rest)+)
+    };
+    (@unary ($($inner:tt)+)) => {
+        prop!($($inner)+)
+    };
+    (@unary {$value:expr}) => {
+        $crate::Proposition::from($value)
+    };
+    (@unary true) => {
+        $crate::top()
+    };
+    (@unary false) => {
+        $crate::bottom()
+    };
+    (@unary $name:ident) => {
+        $crate::term(stringify!($name).to_string())
+    };
+
+    (@if [$($c:tt)+] then $($rest:tt)+) => {
+        prop!(@then [$($c)+] [] $($rest)+)
+    };
+    (@if [$($c:tt)*] $next:tt $($rest:tt)*) => {
+        prop!(@if [$($c)* $next] $($rest)*)
+    };
+    (@then [$($c:tt)+] [$($t:tt)+] else $($rest:tt)+) => {
+        $crate::ite(prop!($($c)+), prop!($($t)+), prop!($($rest)+))
+    };
+    (@then [$($c:tt)+] [$($t:tt)*] $next:tt $($rest:tt)*) => {
+        prop!(@then [$($c)+] [$($t)* $next] $($rest)*)
+    };
+
+    // Combines the pending left operand, if there is one, with the operand
+    // that follows it
+    (@fold [] $e:expr) => {
+        $e
+    };
+    (@fold [$op:ident, $acc:expr] $e:expr) => {
+        $crate::$op($acc, $e)
+    };
+
+    ($($tokens:tt)+) => {
+        prop!(@iff [] [] $($tokens)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::*;
+    use propositions::*;
+
+    #[test]
+    fn prop_macro_precedence() {
+        let prop = prop!(~p | q && r <-> s -> t -> u);
+
+        let expected = iff(
+            or(
+                not(term("p".to_string())),
+                and(term("q".to_string()), term("r".to_string()))
+            ),
+            implies(
+                term("s".to_string()),
+                implies(term("t".to_string()), term("u".to_string()))
+            )
+        );
+
+        assert_eq!(prop, expected);
+    }
+
+    #[test]
+    fn prop_macro_left_grouping() {
+        let prop = prop!(a & b & c | d ~| e <+> f <-> g);
+
+        let expected = iff(
+            xor(
+                nor(
+                    or(
+                        and(
+                            and(term("a".to_string()), term("b".to_string())),
+                            term("c".to_string())
+                        ),
+                        term("d".to_string())
+                    ),
+                    term("e".to_string())
+                ),
+                term("f".to_string())
+            ),
+            term("g".to_string())
+        );
+
+        assert_eq!(prop, expected);
+    }
+
+    #[test]
+    fn prop_macro_matches_parser() {
+        let props = vec!(
+            (prop!(a ~& !b -> true), "a ~/\\ ~b -> true"),
+            (prop!(x & if c then (p | q) else y | false), "x /\\ if c then p \\/ q else y \\/ false"),
+            (prop!(if a <-> b then c -> d else ~(e)), "if a <-> b then c -> d else ~(e)"),
+        );
+
+        for (prop, text) in props {
+            assert_eq!(Ok(prop), parse_proposition(text));
+        }
+    }
+
+    #[test]
+    fn prop_macro_recursion_limit() {
+        let prop = prop!(a & b & c & d & e & f & g & h & i & j & k & l & m);
+
+        let text = "a /\\ b /\\ c /\\ d /\\ e /\\ f /\\ g /\\ h /\\ i /\\ j /\\ k /\\ l /\\ m";
+
+        assert_eq!(Ok(prop), parse_proposition(text));
+    }
+
+    #[test]
+    fn prop_macro_interpolation() {
+        let name = "r";
+        let inner = prop!(p | q);
+
+        let prop = prop!({inner} -> {name});
+
+        assert_eq!(prop, parse_proposition("(p \\/ q) -> r").unwrap());
+    }
+}
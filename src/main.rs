@@ -1,3 +1,315 @@
+extern crate resolution_prover;
+
+use resolution_prover::*;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str = "Usage: resolution-prover [--dot FILE] [--narrate FORMAT] [--natural-deduction]
+                         [--trace] [--check-consistency]
+                         [--tptp] [--tstp] [--timeout SECONDS] [--smtlib]
+                         [--wcnf] PROBLEM
+
+Attempts to prove the goal of the given problem file from its assumptions.
+
+Options:
+    --dot FILE          Write the found proof to FILE as a Graphviz DOT graph
+    --narrate FORMAT    Describe the found proof step by step, where FORMAT
+                        is either \"text\" or \"markdown\"
+    --natural-deduction Print the found proof as a natural deduction proof
+    --trace             Print how the goal is derived by backward chaining,
+                        for Horn clause assumptions and a goal that is a
+                        single term
+    --check-consistency Check that the assumptions do not contradict each
+                        other before proving the goal
+    --tptp              Read the problem in the TPTP format and report the
+                        result as an SZS status
+    --tstp              Like --tptp, but also print the found proof as a
+                        TSTP derivation
+    --timeout SECONDS   With --tptp, give up after SECONDS and report the
+                        Timeout status
+    --smtlib            Run the problem as an SMT-LIB 2 script over boolean
+                        constants and print its responses
+    --wcnf              Read the problem as weighted MaxSAT clauses in the
+                        WCNF format and print an optimal assignment";
+
+/// The options given on the command line.
+struct Options {
+    problem: String,
+    dot: Option<String>,
+    narrate: Option<NarrationFormat>,
+    natural_deduction: bool,
+    trace: bool,
+    check_consistency: bool,
+    tptp: bool,
+    tstp: bool,
+    timeout: Option<Duration>,
+    smtlib: bool,
+    wcnf: bool
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut problem = None;
+    let mut dot = None;
+    let mut narrate = None;
+    let mut natural_deduction = false;
+    let mut trace = false;
+    let mut check_consistency = false;
+    let mut tptp = false;
+    let mut tstp = false;
+    let mut timeout = None;
+    let mut smtlib = false;
+    let mut wcnf = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dot" => match args.next() {
+                Some(file) => dot = Some(file),
+                None => return Err("Missing file for --dot".to_string())
+            },
+            "--narrate" => match args.next().as_deref() {
+                Some("text") => narrate = Some(NarrationFormat::PlainText),
+                Some("markdown") => narrate = Some(NarrationFormat::Markdown),
+                Some(f) => return Err(format!("Unknown narration format: {}", f)),
+                None => return Err("Missing format for --narrate".to_string())
+            },
+            "--natural-deduction" => natural_deduction = true,
+            "--trace" => trace = true,
+            "--check-consistency" => check_consistency = true,
+            "--tptp" => tptp = true,
+            "--tstp" => {
+                tptp = true;
+                tstp = true;
+            },
+            "--timeout" => match args.next().map(|s| s.parse::<f64>()) {
+                Some(Ok(s)) if s >= 0.0 && s.is_finite() =>
+                    timeout = Some(Duration::from_secs_f64(s)),
+                Some(_) => return Err("Expected a number of seconds for --timeout".to_string()),
+                None => return Err("Missing seconds for --timeout".to_string())
+            },
+            "--smtlib" => smtlib = true,
+            "--wcnf" => wcnf = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            a if a.starts_with('-') =>
+                return Err(format!("Unknown option: {}", a)),
+            _ if problem.is_some() =>
+                return Err("Expected only one problem file".to_string()),
+            _ => problem = Some(arg)
+        }
+    }
+
+    match problem {
+        Some(problem) => Ok(Options {
+            problem,
+            dot,
+            narrate,
+            natural_deduction,
+            trace,
+            check_consistency,
+            tptp,
+            tstp,
+            timeout,
+            smtlib,
+            wcnf
+        }),
+        None => Err(USAGE.to_string())
+    }
+}
+
+fn run(options: Options) -> Result<bool, String> {
+    let contents = fs::read_to_string(&options.problem)
+        .map_err(|e| format!("{}: {}", options.problem, e))?;
+
+    if options.tptp {
+        return run_tptp(&options, &contents)
+    }
+
+    if options.smtlib {
+        print!("{}", SmtInterpreter::new().run(&contents));
+        return Ok(true)
+    }
+
+    if options.wcnf {
+        return run_wcnf(&options, &contents)
+    }
+
+    let problem = parse_problem(&contents)
+        .map_err(|e| format!("{}:{}", options.problem, e))?;
+
+    if options.check_consistency {
+        if let Some(conflict) = minimal_unsatisfiable_subset(&problem.assumptions) {
+            println!("Inconsistent assumptions");
+            for i in conflict {
+                println!("{}) {}", i + 1, problem.assumptions[i]);
+            }
+            return Ok(false)
+        }
+    }
+
+    let trace = if options.trace {
+        Some(backward_chain_goal(&problem.assumptions, &problem.goal)?)
+    } else {
+        None
+    };
+
+    // When a proof is asked for, finding it is what decides the answer, so
+    // the answer always comes with the proof that is shown
+    let proof = if options.narrate.is_some() || options.natural_deduction || options.dot.is_some() {
+        match prove(problem.assumptions.clone(), problem.goal.clone()) {
+            Some(proof) => Some(proof),
+            None => {
+                println!("Not provable");
+                return Ok(false)
+            }
+        }
+    } else if resolve(problem.assumptions.clone(), problem.goal.clone()) {
+        None
+    } else {
+        println!("Not provable");
+        return Ok(false)
+    };
+    println!("Provable");
+
+    match trace {
+        Some(Some(t)) => print!("\n{}", t),
+        Some(None) => println!("\nThe goal only follows because the assumptions contradict each other"),
+        None => {}
+    }
+
+    if let Some(proof) = proof {
+        write_proof(&options, &proof)?;
+    }
+    Ok(true)
+}
+
+/// Works backwards from the goal through the clauses of the assumptions,
+/// which must be Horn clauses, to find how the goal is derived.
+fn backward_chain_goal(assumptions: &[Proposition], goal: &Proposition) -> Result<Option<HornTrace>, String> {
+    let clauses: Vec<Clause> = assumptions.iter()
+        .flat_map(|a| Clause::from_proposition(a.clone()))
+        .collect();
+
+    if !is_horn(&clauses) {
+        return Err("--trace needs assumptions that break down into Horn clauses".to_string())
+    }
+
+    match *goal {
+        Proposition::Term(ref a) => Ok(backward_chain(&clauses, a)),
+        _ => Err("--trace needs a goal that is a single term".to_string())
+    }
+}
+
+/// Attempts a TPTP problem, printing its SZS status and, if asked for, the
+/// found derivation.
+fn run_tptp(options: &Options, contents: &str) -> Result<bool, String> {
+    let tptp = parse_tptp(contents)
+        .map_err(|e| format!("{}:{}", options.problem, e))?;
+
+    let name = Path::new(&options.problem)
+        .file_stem()
+        .map_or(options.problem.clone(), |s| s.to_string_lossy().into_owned());
+
+    // The attempt runs on its own thread so that it can be abandoned once
+    // the time limit has passed
+    let (sender, receiver) = mpsc::channel();
+    let worker = tptp.clone();
+    thread::spawn(move || sender.send(worker.attempt()));
+
+    // A worker that stops without sending anything has given up rather than
+    // run out of time
+    let (status, proof) = match options.timeout {
+        Some(t) => match receiver.recv_timeout(t) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => (SzsStatus::Timeout, None),
+            Err(mpsc::RecvTimeoutError::Disconnected) => (SzsStatus::GaveUp, None)
+        },
+        None => receiver.recv().unwrap_or((SzsStatus::GaveUp, None))
+    };
+
+    println!("% SZS status {} for {}", status, name);
+
+    match proof {
+        Some(proof) => {
+            if options.tstp {
+                println!("% SZS output start CNFRefutation for {}", name);
+                print!("{}", proof.to_tstp(&tptp));
+                println!("% SZS output end CNFRefutation for {}", name);
+            }
+            write_proof(options, &proof)?;
+            Ok(true)
+        },
+        None => Ok(false)
+    }
+}
+
+/// Solves a weighted MaxSAT problem, printing the result in the format used
+/// by the MaxSAT Evaluations.
+fn run_wcnf(options: &Options, contents: &str) -> Result<bool, String> {
+    let wcnf = parse_wcnf(contents)
+        .map_err(|e| format!("{}:{}", options.problem, e))?;
+
+    match wcnf.solve().map_err(|e| e.to_string())? {
+        Some(solution) => {
+            // Terms are named by their numbers, so the assignment is listed
+            // from the first number to the last
+            let last = solution.model.keys()
+                .filter_map(|t| t.parse::<usize>().ok())
+                .max()
+                .unwrap_or(0);
+            let values: String = (1..last + 1)
+                .map(|v| if solution.model.get(&v.to_string()) == Some(&true) { '1' } else { '0' })
+                .collect();
+
+            println!("o {}", solution.cost);
+            println!("s OPTIMUM FOUND");
+            println!("v {}", values);
+            Ok(true)
+        },
+        None => {
+            println!("s UNSATISFIABLE");
+            Ok(false)
+        }
+    }
+}
+
+/// Writes the outputs that were asked for on the command line for the given
+/// proof.
+fn write_proof(options: &Options, proof: &Proof) -> Result<(), String> {
+    if let Some(format) = options.narrate {
+        print!("\n{}", proof.narrate(format));
+    }
+
+    if options.natural_deduction {
+        let deduction = proof.to_natural_deduction()
+            .map_err(|e| e.to_string())?;
+        print!("\n{}", deduction);
+    }
+
+    if let Some(ref file) = options.dot {
+        fs::write(file, proof.to_dot())
+            .map_err(|e| format!("{}: {}", file, e))?;
+    }
+
+    Ok(())
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args = env::args().skip(1).collect();
+
+    let result = parse_args(args).and_then(run);
+
+    match result {
+        Ok(true) => (),
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    }
 }
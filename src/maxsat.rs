@@ -0,0 +1,661 @@
+use clauses::*;
+use parser::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+/// A clause that should hold if possible, along with the weight lost by
+/// breaking it.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct WeightedClause {
+    pub clause: Clause,
+    pub weight: u64
+}
+
+/// An assignment that satisfies every hard clause or assumption and breaks
+/// soft ones of the least possible total weight.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct MaxSatSolution {
+    /// The values of the terms of the clauses or assumptions, leaving out
+    /// any terms introduced while breaking them into clauses.
+    pub model: HashMap<String, bool>,
+    /// The total weight of the broken soft clauses or assumptions.
+    pub cost: u64,
+    /// The indices of the broken soft clauses or assumptions, in order.
+    pub falsified: Vec<usize>
+}
+
+/// An error for a MaxSAT problem whose least total weight of broken soft
+/// clauses or assumptions is too large to be represented.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct MaxSatError {
+    pub message: String
+}
+
+impl fmt::Display for MaxSatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for MaxSatError {}
+
+/// Finds an assignment that satisfies all of the hard clauses and maximizes
+/// the total weight of the satisfied soft clauses, returning None if the
+/// hard clauses are unsatisfiable.
+///
+/// Returns an error if the soft clauses that every assignment breaks weigh
+/// more than `u64::MAX` in total.
+///
+/// Each soft clause is relaxed with a new term that may be set to satisfy
+/// it, and the search is repeated with the total weight of the relaxing
+/// terms bounded below the cost of the best assignment found so far, until
+/// no cheaper assignment exists. The bound is encoded as clauses following a
+/// decision diagram over the relaxing terms, heaviest first.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clause = |s: &str| Clause::from_proposition(parse_proposition(s).unwrap()).remove(0);
+///
+/// let hard = vec!(clause("~a \\/ ~b"));
+/// let soft = vec!(
+///     WeightedClause { clause: clause("a"), weight: 2 },
+///     WeightedClause { clause: clause("b"), weight: 3 },
+/// );
+///
+/// let solution = solve_max_sat(&hard, &soft).unwrap().unwrap();
+///
+/// assert_eq!(solution.cost, 2);
+/// assert_eq!(solution.falsified, vec!(0));
+/// assert_eq!(solution.model["b"], true);
+/// ```
+pub fn solve_max_sat(hard: &[Clause], soft: &[WeightedClause]) -> Result<Option<MaxSatSolution>, MaxSatError> {
+    let mut solver = Solver::new(hard);
+
+    let relaxing: Vec<String> = (0..soft.len()).map(|i| format!("{{relax {}}}", i)).collect();
+    for (s, r) in soft.iter().zip(&relaxing) {
+        let mut relaxed = s.clause.clone();
+        relaxed.parts.push(ClausePart::Term(r.clone()));
+        solver.add_clause(&relaxed);
+    }
+
+    let terms: BTreeSet<&str> = hard.iter()
+        .chain(soft.iter().map(|s| &s.clause))
+        .flat_map(|c| c.parts.iter().map(|p| p.name()))
+        .filter(|t| !is_defined_term(t))
+        .collect();
+
+    let mut best = match solver.find_model() {
+        Some(model) => model,
+        None => return Ok(None)
+    };
+    // A cost of None is more than can be represented
+    let mut cost = falsified_weight(soft, &best);
+
+    // Ordering the heaviest first keeps the decision diagram small, since
+    // the bound is used up by the fewest terms
+    let mut order: Vec<usize> = (0..soft.len()).collect();
+    order.sort_by(|&a, &b| soft[b].weight.cmp(&soft[a].weight));
+    let weighted: Vec<(&str, u64)> = order.iter()
+        .map(|&i| (relaxing[i].as_str(), soft[i].weight))
+        .collect();
+
+    let mut round = 0;
+    while cost != Some(0) {
+        let mut bound = Bound {
+            terms: &weighted,
+            remaining: suffix_sums(&weighted),
+            round,
+            nodes: HashMap::new(),
+            clauses: vec!()
+        };
+
+        match bound.node(0, cost.map_or(u64::MAX, |c| c - 1)) {
+            Node::True => (),
+            Node::False => break,
+            Node::Term(root) => {
+                solver.add_clause(&Clause { parts: vec!(ClausePart::Term(root)) });
+            }
+        }
+        for c in &bound.clauses {
+            solver.add_clause(c);
+        }
+
+        match solver.find_model() {
+            Some(model) => {
+                cost = falsified_weight(soft, &model);
+                best = model;
+            },
+            None => break
+        }
+        round += 1;
+    }
+
+    let cost = cost.ok_or_else(too_heavy)?;
+    let falsified = (0..soft.len())
+        .filter(|&i| !clause_satisfied(&soft[i].clause, &best))
+        .collect();
+    let model = terms.into_iter()
+        .map(|t| (t.to_string(), best.get(t).cloned().unwrap_or(false)))
+        .collect();
+
+    Ok(Some(MaxSatSolution { model, cost, falsified }))
+}
+
+/// Finds an assignment to the terms of the given propositions that makes
+/// all of the hard assumptions true and maximizes the total weight of the
+/// true soft assumptions, returning None if the hard assumptions contradict
+/// each other. As with `solve_max_sat`, an error is returned if the least
+/// total weight of the broken soft assumptions is more than `u64::MAX`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let hard = vec!(parse_proposition("~(admin /\\ guest)").unwrap());
+/// let soft = vec!(
+///     (parse_proposition("admin").unwrap(), 5),
+///     (parse_proposition("guest").unwrap(), 1),
+///     (parse_proposition("guest -> logged").unwrap(), 1),
+/// );
+///
+/// let solution = max_sat(&hard, &soft).unwrap().unwrap();
+///
+/// assert_eq!(solution.cost, 1);
+/// assert_eq!(solution.falsified, vec!(1));
+/// assert_eq!(solution.model["admin"], true);
+/// ```
+pub fn max_sat(hard: &[Proposition], soft: &[(Proposition, u64)]) -> Result<Option<MaxSatSolution>, MaxSatError> {
+    let mut hard_clauses: Vec<Clause> = hard.iter()
+        .flat_map(|p| Clause::from_proposition(p.clone()))
+        .collect();
+
+    // Each soft assumption holds whenever its selecting term does, so
+    // breaking it costs the weight of that term's unit clause
+    let mut soft_clauses: Vec<WeightedClause> = vec!();
+    for (i, &(ref p, weight)) in soft.iter().enumerate() {
+        let selector = format!("{{soft {}}}", i);
+        hard_clauses.extend(Clause::from_proposition(implies(term(selector.clone()), p.clone())));
+        soft_clauses.push(WeightedClause {
+            clause: Clause { parts: vec!(ClausePart::Term(selector)) },
+            weight
+        });
+    }
+
+    let solution = match solve_max_sat(&hard_clauses, &soft_clauses)? {
+        Some(s) => s,
+        None => return Ok(None)
+    };
+
+    let model: HashMap<String, bool> = hard.iter()
+        .chain(soft.iter().map(|s| &s.0))
+        .flat_map(|p| p.terms())
+        .map(|t| {
+            let value = solution.model.get(&t).cloned().unwrap_or(false);
+            (t, value)
+        })
+        .collect();
+    let falsified: Vec<usize> = (0..soft.len())
+        .filter(|&i| soft[i].0.evaluate(&model) != Some(true))
+        .collect();
+    let cost = falsified.iter()
+        .try_fold(0u64, |total, &i| total.checked_add(soft[i].1))
+        .ok_or_else(too_heavy)?;
+
+    Ok(Some(MaxSatSolution { model, cost, falsified }))
+}
+
+/// A weighted MaxSAT problem made up of hard and soft clauses.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct WeightedCnf {
+    pub hard: Vec<Clause>,
+    pub soft: Vec<WeightedClause>
+}
+
+impl WeightedCnf {
+    /// Finds an optimal assignment for the problem, as with `solve_max_sat`.
+    pub fn solve(&self) -> Result<Option<MaxSatSolution>, MaxSatError> {
+        solve_max_sat(&self.hard, &self.soft)
+    }
+
+    /// Writes the problem in the WCNF format used by the MaxSAT Evaluations
+    /// since 2022, where hard clauses start with `h` and soft clauses start
+    /// with their weight.
+    ///
+    /// Terms named by positive numbers keep their numbers. Otherwise, terms
+    /// are numbered in the order they first appear, and a comment records
+    /// the name of each number.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let wcnf = WeightedCnf {
+    ///     hard: Clause::from_proposition(parse_proposition("~p \\/ q").unwrap()),
+    ///     soft: vec!(WeightedClause {
+    ///         clause: Clause::from_proposition(parse_proposition("p").unwrap()).remove(0),
+    ///         weight: 4
+    ///     })
+    /// };
+    ///
+    /// assert_eq!(wcnf.to_wcnf(), "\
+    /// c 1 = p
+    /// c 2 = q
+    /// h -1 2 0
+    /// 4 1 0
+    /// ");
+    /// ```
+    pub fn to_wcnf(&self) -> String {
+        let clauses = self.hard.iter().chain(self.soft.iter().map(|s| &s.clause));
+
+        let mut names: Vec<&str> = vec!();
+        for c in clauses.clone() {
+            for p in &c.parts {
+                if !names.contains(&p.name()) {
+                    names.push(p.name());
+                }
+            }
+        }
+
+        let numbered = names.iter().all(|n| n.parse::<u64>().is_ok_and(|i| i > 0)
+            && !n.starts_with('0') && !n.starts_with('+'));
+        let mut numbers: HashMap<&str, String> = HashMap::new();
+        let mut output = String::new();
+        for (i, n) in names.iter().enumerate() {
+            if numbered {
+                numbers.insert(n, n.to_string());
+            } else {
+                numbers.insert(n, (i + 1).to_string());
+                writeln!(output, "c {} = {}", i + 1, n).unwrap();
+            }
+        }
+
+        let line = |c: &Clause| {
+            let mut literals: Vec<String> = c.parts.iter()
+                .map(|p| match *p {
+                    ClausePart::Term(ref n) => numbers[n.as_str()].clone(),
+                    ClausePart::NegatedTerm(ref n) => format!("-{}", numbers[n.as_str()]),
+                })
+                .collect();
+            literals.push("0".to_string());
+            literals.join(" ")
+        };
+
+        for c in &self.hard {
+            writeln!(output, "h {}", line(c)).unwrap();
+        }
+        for s in &self.soft {
+            writeln!(output, "{} {}", s.weight, line(&s.clause)).unwrap();
+        }
+
+        output
+    }
+}
+
+/// Parses a weighted MaxSAT problem in the WCNF format, naming each term by
+/// its number.
+///
+/// Both the format used by the MaxSAT Evaluations since 2022, with hard
+/// clauses starting with `h`, and the older format, with a `p wcnf` line
+/// giving the weight at or above which clauses are hard, are accepted. Lines
+/// starting with `c` are comments.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let wcnf = parse_wcnf("
+///     p wcnf 2 3 10
+///     10 -1 -2 0
+///     3 1 0
+///     5 2 0
+/// ").unwrap();
+///
+/// assert_eq!(wcnf.hard.len(), 1);
+/// assert_eq!(wcnf.soft.len(), 2);
+///
+/// let solution = wcnf.solve().unwrap().unwrap();
+/// assert_eq!(solution.cost, 3);
+/// assert_eq!(solution.model["2"], true);
+/// ```
+pub fn parse_wcnf(input: &str) -> Result<WeightedCnf, ParseError> {
+    let mut wcnf = WeightedCnf::default();
+    let mut top: Option<u64> = None;
+    let mut seen_clause = false;
+
+    for (i, text) in input.lines().enumerate() {
+        let line = i + 1;
+        let error = |column: usize, message: &str| ParseError {
+            message: message.to_string(),
+            line,
+            column
+        };
+
+        let words = split_words(text);
+        let (column, first) = match words.first() {
+            Some(&w) => w,
+            None => continue
+        };
+
+        if first.starts_with('c') {
+            continue
+        }
+
+        if first == "p" {
+            if seen_clause || top.is_some() {
+                return Err(error(column, "Expected the problem line before any clauses"))
+            }
+            if words.get(1).map(|w| w.1) != Some("wcnf") {
+                return Err(error(column, "Expected \"p wcnf\""))
+            }
+            match words.get(4) {
+                Some(&(c, w)) => match w.parse::<u64>() {
+                    Ok(t) if words.len() == 5 => top = Some(t),
+                    _ => return Err(error(c, "Expected the weight of hard clauses to end the problem line"))
+                },
+                None => top = Some(u64::MAX)
+            }
+            continue
+        }
+
+        seen_clause = true;
+        let weight = if first == "h" {
+            None
+        } else {
+            match first.parse::<u64>() {
+                Ok(w) if w > 0 => if top.is_some_and(|t| w >= t) { None } else { Some(w) },
+                _ => return Err(error(column, "Expected \"h\" or a positive weight"))
+            }
+        };
+
+        let mut parts: Vec<ClausePart> = vec!();
+        let mut ended = false;
+        for &(c, w) in &words[1..] {
+            if ended {
+                return Err(error(c, "Expected the clause to end after 0"))
+            }
+            match w.parse::<i64>() {
+                Ok(0) => ended = true,
+                Ok(l) if l > 0 => parts.push(ClausePart::Term(l.to_string())),
+                Ok(l) => parts.push(ClausePart::NegatedTerm((-l).to_string())),
+                Err(_) => return Err(error(c, "Expected a literal"))
+            }
+        }
+        if !ended {
+            return Err(error(text.len() + 1, "Expected the clause to end with 0"))
+        }
+
+        let clause = Clause { parts };
+        match weight {
+            Some(weight) => wcnf.soft.push(WeightedClause { clause, weight }),
+            None => wcnf.hard.push(clause)
+        }
+    }
+
+    Ok(wcnf)
+}
+
+/// Splits a line into its words, each with the column it starts at.
+pub(crate) fn split_words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = vec!();
+    let mut start = None;
+
+    for (i, c) in text.char_indices().chain(Some((text.len(), ' '))) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                words.push((text[..s].chars().count() + 1, &text[s..i]));
+                start = None;
+            },
+            _ => ()
+        }
+    }
+
+    words
+}
+
+fn clause_satisfied(clause: &Clause, model: &HashMap<String, bool>) -> bool {
+    clause.parts.iter().any(|p| match *p {
+        ClausePart::Term(ref n) => model.get(n) == Some(&true),
+        ClausePart::NegatedTerm(ref n) => model.get(n) != Some(&true),
+    })
+}
+
+/// Returns the total weight of the soft clauses that the model breaks, or
+/// None if it is more than `u64::MAX`.
+fn falsified_weight(soft: &[WeightedClause], model: &HashMap<String, bool>) -> Option<u64> {
+    soft.iter()
+        .filter(|s| !clause_satisfied(&s.clause, model))
+        .try_fold(0u64, |total, s| total.checked_add(s.weight))
+}
+
+fn too_heavy() -> MaxSatError {
+    MaxSatError {
+        message: format!("Expected the broken soft clauses or assumptions to weigh at most {} in total", u64::MAX)
+    }
+}
+
+/// Returns the total weight of each suffix of the given terms, with the
+/// empty suffix last, or None for those that weigh more than `u64::MAX`.
+fn suffix_sums(terms: &[(&str, u64)]) -> Vec<Option<u64>> {
+    let mut sums: Vec<Option<u64>> = vec!(Some(0));
+    for &(_, w) in terms.iter().rev() {
+        let next = sums[sums.len() - 1].and_then(|s| s.checked_add(w));
+        sums.push(next);
+    }
+    sums.reverse();
+    sums
+}
+
+/// A node of the decision diagram for a bound, which is either constant or
+/// a term that implies the bound holds for the remaining terms.
+#[derive(Clone)]
+#[derive(Debug)]
+enum Node {
+    True,
+    False,
+    Term(String)
+}
+
+/// Builds clauses requiring the total weight of the true terms to be at most
+/// a bound, with a node for each term and remaining bound that comes up.
+struct Bound<'a> {
+    terms: &'a [(&'a str, u64)],
+    remaining: Vec<Option<u64>>,
+    round: usize,
+    nodes: HashMap<(usize, u64), Node>,
+    clauses: Vec<Clause>
+}
+
+impl<'a> Bound<'a> {
+    /// Returns the node requiring the terms from the given index on to weigh
+    /// at most the given bound.
+    fn node(&mut self, index: usize, bound: u64) -> Node {
+        if self.remaining[index].is_some_and(|r| r <= bound) {
+            return Node::True
+        }
+        if let Some(n) = self.nodes.get(&(index, bound)) {
+            return n.clone()
+        }
+
+        let (term, weight) = self.terms[index];
+        let when_true = match bound.checked_sub(weight) {
+            Some(b) => self.node(index + 1, b),
+            None => Node::False
+        };
+        let when_false = self.node(index + 1, bound);
+
+        let name = format!("{{bound {} {} {}}}", self.round, index, bound);
+        for &(ref branch, positive) in &[(when_true, true), (when_false, false)] {
+            let mut parts = vec!(
+                ClausePart::NegatedTerm(name.clone()),
+                if positive {
+                    ClausePart::NegatedTerm(term.to_string())
+                } else {
+                    ClausePart::Term(term.to_string())
+                }
+            );
+            match *branch {
+                Node::True => continue,
+                Node::False => (),
+                Node::Term(ref n) => parts.push(ClausePart::Term(n.clone()))
+            }
+            self.clauses.push(Clause { parts });
+        }
+
+        let node = Node::Term(name);
+        self.nodes.insert((index, bound), node.clone());
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maxsat::*;
+    use models::*;
+    use random::*;
+
+    fn clause(literals: &[i64]) -> Clause {
+        Clause {
+            parts: literals.iter()
+                .map(|&l| if l > 0 {
+                    ClausePart::Term(l.to_string())
+                } else {
+                    ClausePart::NegatedTerm((-l).to_string())
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn solve_max_sat_matches_enumeration() {
+        let mut random = Random::new(12345);
+
+        for _ in 0..20 {
+            let literal = |v: u64, negated: u64| if negated == 0 { v as i64 + 1 } else { -(v as i64) - 1 };
+
+            let mut hard = vec!();
+            for _ in 0..3 {
+                hard.push(clause(&[literal(random.next(6), random.next(2)), literal(random.next(6), random.next(2)), literal(random.next(6), random.next(2))]));
+            }
+            let mut soft = vec!();
+            for _ in 0..8 {
+                let literals: Vec<i64> = (0..random.next(3) + 1).map(|_| literal(random.next(6), random.next(2))).collect();
+                soft.push(WeightedClause { clause: clause(&literals), weight: random.next(10) + 1 });
+            }
+
+            let variables: Vec<String> = (1..7).map(|v| v.to_string()).collect();
+            let best = enumerate_models_over(&hard, &variables)
+                .map(|m| falsified_weight(&soft, &m))
+                .min();
+
+            let solution = solve_max_sat(&hard, &soft).unwrap();
+            assert_eq!(solution.as_ref().map(|s| Some(s.cost)), best);
+
+            if let Some(s) = solution {
+                assert!(hard.iter().all(|c| clause_satisfied(c, &s.model)));
+                assert_eq!(falsified_weight(&soft, &s.model), Some(s.cost));
+            }
+        }
+    }
+
+    #[test]
+    fn solve_max_sat_hard_unsatisfiable() {
+        let hard = vec!(clause(&[1]), clause(&[-1]));
+        let soft = vec!(WeightedClause { clause: clause(&[2]), weight: 1 });
+
+        assert_eq!(solve_max_sat(&hard, &soft), Ok(None));
+    }
+
+    #[test]
+    fn solve_max_sat_unbreakable_soft() {
+        let soft = vec!(
+            WeightedClause { clause: clause(&[]), weight: 7 },
+            WeightedClause { clause: clause(&[1]), weight: u64::MAX },
+            WeightedClause { clause: clause(&[-1]), weight: 1 },
+        );
+
+        let solution = solve_max_sat(&[], &soft).unwrap().unwrap();
+
+        assert_eq!(solution.cost, 8);
+        assert_eq!(solution.falsified, vec!(0, 2));
+    }
+
+    #[test]
+    fn solve_max_sat_cost_overflow() {
+        let soft = vec!(
+            WeightedClause { clause: clause(&[]), weight: u64::MAX },
+            WeightedClause { clause: clause(&[]), weight: 1 },
+        );
+
+        assert!(solve_max_sat(&[], &soft).is_err());
+        assert!(max_sat(&[], &[(bottom(), u64::MAX), (bottom(), 1)]).is_err());
+    }
+
+    #[test]
+    fn parse_wcnf_formats() {
+        let new = parse_wcnf("c a comment\nh 1 -2 0\n3 2 0\n1 -1 0\n").unwrap();
+        let old = parse_wcnf("p wcnf 2 3 4\n4 1 -2 0\n3 2 0\n1 -1 0\n").unwrap();
+
+        assert_eq!(new, old);
+        assert_eq!(new.hard, vec!(clause(&[1, -2])));
+        assert_eq!(new.to_wcnf(), "h 1 -2 0\n3 2 0\n1 -1 0\n");
+        assert_eq!(parse_wcnf(&new.to_wcnf()), Ok(new));
+    }
+
+    #[test]
+    fn parse_wcnf_errors() {
+        let cases = vec!(
+            ("h 1 2", 1, 6, "Expected the clause to end with 0"),
+            ("h 1 0 2", 1, 7, "Expected the clause to end after 0"),
+            ("0 1 0", 1, 1, "Expected \"h\" or a positive weight"),
+            ("h 1\n2 x 0", 1, 4, "Expected the clause to end with 0"),
+            ("1 1 x 0", 1, 5, "Expected a literal"),
+            ("p cnf 2 1", 1, 1, "Expected \"p wcnf\""),
+            ("p wcnf 2 1 x", 1, 12, "Expected the weight of hard clauses to end the problem line"),
+            ("h 1 0\np wcnf 1 1 2", 2, 1, "Expected the problem line before any clauses"),
+        );
+
+        for (input, line, column, message) in cases {
+            let expected = ParseError {
+                message: message.to_string(),
+                line,
+                column
+            };
+
+            assert_eq!(parse_wcnf(input), Err(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn max_sat_propositions() {
+        let hard = vec!(
+            parse_proposition("rain -> wet").unwrap(),
+            parse_proposition("sprinkler -> wet").unwrap(),
+        );
+        let soft = vec!(
+            (parse_proposition("~wet").unwrap(), 2),
+            (parse_proposition("rain \\/ sprinkler").unwrap(), 3),
+            (parse_proposition("~sprinkler").unwrap(), 1),
+        );
+
+        let solution = max_sat(&hard, &soft).unwrap().unwrap();
+
+        assert_eq!(solution.cost, 2);
+        assert_eq!(solution.falsified, vec!(0));
+        assert!(solution.model["rain"]);
+        assert!(!solution.model["sprinkler"]);
+        assert!(solution.model.keys().all(|k| !k.starts_with('{')));
+    }
+}
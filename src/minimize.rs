@@ -0,0 +1,598 @@
+use clauses::*;
+use normal_forms::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::{BTreeSet, HashMap};
+
+/// The largest number of terms for which `MinimizationMethod::Automatic`
+/// uses the Quine-McCluskey method, which looks at every assignment.
+const QUINE_MCCLUSKEY_LIMIT: usize = 10;
+
+/// The most terms that the Quine-McCluskey method can be used with, since
+/// each row of the truth table is numbered by the bits of a `u64`.
+const TRUTH_TABLE_LIMIT: usize = 63;
+
+/// The way that a proposition is minimized into a sum of products or a
+/// product of sums.
+///
+/// The Quine-McCluskey method finds every prime implicant from the truth
+/// table and then picks the smallest set of them that covers it, so its
+/// result is always minimal but it takes time exponential in the number of
+/// terms. The Espresso method starts from any cover of prime implicants and
+/// repeatedly shrinks and regrows its cubes, asking the SAT solver rather
+/// than the truth table, so it copes with many more terms but may miss the
+/// smallest cover. The automatic method uses Quine-McCluskey for up to ten
+/// terms and Espresso above that, and Espresso is also used for more than
+/// 63 terms even when Quine-McCluskey is asked for.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(Eq)]
+#[derive(PartialEq)]
+pub enum MinimizationMethod {
+    #[default]
+    Automatic,
+    QuineMcCluskey,
+    Espresso
+}
+
+/// A cube over a fixed list of terms, giving the value that each term must
+/// have, or `None` if the cube does not mention the term.
+type Ternary = Vec<Option<bool>>;
+
+/// Returns the fewest cubes, and then the fewest literals, whose disjunction
+/// agrees with `on` wherever `dont_care` is false. Where `dont_care` is true
+/// the cubes may take either value, which can make them smaller.
+///
+/// The cubes only mention the terms of the two propositions, each in the
+/// order of the term names. When the Espresso method is used the cubes are
+/// all prime implicants and none of them can be left out, but there may be
+/// a smaller cover.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let on = parse_proposition("(p /\\ q /\\ ~r) \\/ (p /\\ q /\\ r) \\/ (~p /\\ q /\\ r)").unwrap();
+///
+/// let cubes = minimize_sop(&on, &bottom(), MinimizationMethod::Automatic);
+/// let shown: Vec<String> = cubes.iter().map(|c| c.to_string()).collect();
+///
+/// assert_eq!(shown, vec!("p /\\ q", "q /\\ r"));
+///
+/// // With ~p /\\ q /\\ ~r as a don't care, a single cube is enough
+/// let dont_care = parse_proposition("~p /\\ q /\\ ~r").unwrap();
+/// let cubes = minimize_sop(&on, &dont_care, MinimizationMethod::Automatic);
+///
+/// assert_eq!(cubes.len(), 1);
+/// assert_eq!(cubes[0].to_string(), "q");
+/// ```
+pub fn minimize_sop(on: &Proposition, dont_care: &Proposition, method: MinimizationMethod) -> Vec<Cube> {
+    let variables: Vec<String> = on.terms().into_iter()
+        .chain(dont_care.terms())
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .collect();
+
+    let quine_mccluskey = match method {
+        MinimizationMethod::Automatic => variables.len() <= QUINE_MCCLUSKEY_LIMIT,
+        MinimizationMethod::QuineMcCluskey => variables.len() <= TRUTH_TABLE_LIMIT,
+        MinimizationMethod::Espresso => false,
+    };
+
+    let mut cover = if quine_mccluskey {
+        minimize_exactly(on, dont_care, &variables)
+    } else {
+        Espresso::new(on, dont_care, variables.clone()).minimize()
+    };
+
+    cover.sort_by_key(|c| c.iter()
+        .map(|v| match *v {
+            Some(true) => 0,
+            Some(false) => 1,
+            None => 2,
+        })
+        .collect::<Vec<u8>>());
+
+    cover.iter()
+        .map(|c| Cube { parts: parts(c, &variables, true) })
+        .collect()
+}
+
+/// Returns the fewest clauses, and then the fewest literals, whose
+/// conjunction agrees with `on` wherever `dont_care` is false. This is found
+/// by minimizing the negation of `on` as a sum of products and negating the
+/// result.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let on = parse_proposition("(p \\/ q) /\\ (p \\/ ~q) /\\ (q \\/ r)").unwrap();
+///
+/// let clauses = minimize_pos(&on, &bottom(), MinimizationMethod::Automatic);
+/// let shown: Vec<String> = clauses.iter().map(|c| c.to_string()).collect();
+///
+/// assert_eq!(shown, vec!("p", "q \\/ r"));
+/// ```
+pub fn minimize_pos(on: &Proposition, dont_care: &Proposition, method: MinimizationMethod) -> Vec<Clause> {
+    minimize_sop(&not(on.clone()), dont_care, method).into_iter()
+        .map(|c| Clause { parts: c.parts.iter().map(|p| p.negate()).collect() })
+        .collect()
+}
+
+impl Proposition {
+    /// Returns an equivalent disjunction of conjunctions of possibly negated
+    /// terms that has as few conjunctions, and then literals, as possible.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(p /\\ q) \\/ (p /\\ ~q) \\/ (~p /\\ q)").unwrap();
+    ///
+    /// assert_eq!(prop.to_minimal_dnf().to_string(), "p \\/ q");
+    /// ```
+    pub fn to_minimal_dnf(&self) -> Proposition {
+        or_all(minimize_sop(self, &bottom(), MinimizationMethod::Automatic).iter()
+            .map(|c| and_all(c.parts.iter().map(literal).collect()))
+            .collect())
+    }
+
+    /// Returns an equivalent conjunction of disjunctions of possibly negated
+    /// terms that has as few disjunctions, and then literals, as possible.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(p \\/ q) /\\ (p \\/ ~q) /\\ (~p \\/ q)").unwrap();
+    ///
+    /// assert_eq!(prop.to_minimal_cnf().to_string(), "p /\\ q");
+    /// ```
+    pub fn to_minimal_cnf(&self) -> Proposition {
+        and_all(minimize_pos(self, &bottom(), MinimizationMethod::Automatic).iter()
+            .map(|c| or_all(c.parts.iter().map(literal).collect()))
+            .collect())
+    }
+}
+
+/// Converts the given cube into the literals that it requires, or their
+/// negations if `positive` is false.
+fn parts(cube: &[Option<bool>], variables: &[String], positive: bool) -> Vec<ClausePart> {
+    cube.iter()
+        .zip(variables)
+        .filter_map(|(v, name)| v.map(|value| if value == positive {
+            ClausePart::Term(name.clone())
+        } else {
+            ClausePart::NegatedTerm(name.clone())
+        }))
+        .collect()
+}
+
+/// Returns the number of cubes and then the number of literals in the given
+/// cover, which is what the minimization tries to make smallest.
+fn cost(cover: &[Ternary]) -> (usize, usize) {
+    (cover.len(), cover.iter().map(|c| c.iter().filter(|v| v.is_some()).count()).sum())
+}
+
+/// An implicant in the Quine-McCluskey method, where the bits in `mask` are
+/// the terms that it does not mention and the bits in `value` are the values
+/// of the others.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Ord)]
+#[derive(PartialEq)]
+#[derive(PartialOrd)]
+struct Implicant {
+    value: u64,
+    mask: u64
+}
+
+impl Implicant {
+    fn covers(&self, minterm: u64) -> bool {
+        minterm & !self.mask == self.value
+    }
+
+    fn literals(&self, width: usize) -> usize {
+        width - self.mask.count_ones() as usize
+    }
+}
+
+/// Minimizes the proposition using the Quine-McCluskey method, reading the
+/// truth table over the given variables and finding the smallest cover of
+/// the prime implicants. There must be no more than `TRUTH_TABLE_LIMIT`
+/// variables.
+fn minimize_exactly(on: &Proposition, dont_care: &Proposition, variables: &[String]) -> Vec<Ternary> {
+    let width = variables.len();
+
+    let mut required = vec!();
+    let mut allowed = BTreeSet::new();
+    for minterm in 0..(1u64 << width) {
+        let assignment: HashMap<String, bool> = variables.iter()
+            .enumerate()
+            .map(|(i, v)| (v.clone(), minterm & (1 << i) != 0))
+            .collect();
+
+        let value = |p: &Proposition| p.evaluate(&assignment).unwrap_or(false);
+        if value(dont_care) {
+            allowed.insert(Implicant { value: minterm, mask: 0 });
+        } else if value(on) {
+            required.push(minterm);
+            allowed.insert(Implicant { value: minterm, mask: 0 });
+        }
+    }
+
+    // Two implicants that differ in only one of the terms they mention are
+    // merged into one that does not mention it, and any implicant that is
+    // never merged is prime
+    let mut primes = vec!();
+    let mut current = allowed;
+    while !current.is_empty() {
+        let implicants: Vec<Implicant> = current.into_iter().collect();
+        let mut merged = vec!(false; implicants.len());
+        let mut next = BTreeSet::new();
+
+        for i in 0..implicants.len() {
+            for j in (i + 1)..implicants.len() {
+                let (a, b) = (implicants[i], implicants[j]);
+                let difference = a.value ^ b.value;
+                if a.mask == b.mask && difference.count_ones() == 1 {
+                    next.insert(Implicant {
+                        value: a.value & !difference,
+                        mask: a.mask | difference
+                    });
+                    merged[i] = true;
+                    merged[j] = true;
+                }
+            }
+        }
+
+        primes.extend(implicants.into_iter()
+            .zip(merged)
+            .filter(|&(_, m)| !m)
+            .map(|(i, _)| i));
+        current = next;
+    }
+
+    let mut best = None;
+    cover(&primes, required, &mut vec!(), &mut best, width);
+
+    best.unwrap_or_default().into_iter()
+        .map(|p| (0..width)
+            .map(|i| if p.mask & (1 << i) != 0 {
+                None
+            } else {
+                Some(p.value & (1 << i) != 0)
+            })
+            .collect())
+        .collect()
+}
+
+/// Searches for the smallest set of the primes that covers every one of the
+/// uncovered minterms, branching on the primes that cover the minterm with
+/// the fewest of them and giving up on branches that cannot beat the best
+/// cover found so far.
+fn cover(primes: &[Implicant], uncovered: Vec<u64>, chosen: &mut Vec<Implicant>,
+         best: &mut Option<Vec<Implicant>>, width: usize) {
+    let literals = |cover: &[Implicant]| cover.iter().map(|p| p.literals(width)).sum::<usize>();
+
+    if uncovered.is_empty() {
+        let better = match *best {
+            Some(ref b) => (chosen.len(), literals(chosen)) < (b.len(), literals(b)),
+            None => true
+        };
+        if better {
+            *best = Some(chosen.clone());
+        }
+        return
+    }
+
+    if let Some(ref b) = *best {
+        if (chosen.len() + 1, literals(chosen)) >= (b.len(), literals(b)) {
+            return
+        }
+    }
+
+    let options = |m: u64| primes.iter().filter(move |p| p.covers(m));
+    let minterm = *uncovered.iter()
+        .min_by_key(|&&m| options(m).count())
+        .unwrap();
+
+    let mut candidates: Vec<Implicant> = options(minterm).cloned().collect();
+    candidates.sort_by_key(|p| (
+        uncovered.iter().filter(|&&m| !p.covers(m)).count(),
+        p.literals(width)
+    ));
+
+    for p in candidates {
+        let remaining = uncovered.iter().cloned().filter(|&m| !p.covers(m)).collect();
+        chosen.push(p);
+        cover(primes, remaining, chosen, best, width);
+        chosen.pop();
+    }
+}
+
+/// The state of an Espresso-style minimization, which keeps clauses for the
+/// assignments that no cube may contain and for those that some cube must
+/// contain.
+struct Espresso {
+    variables: Vec<String>,
+    forbidden: Vec<Clause>,
+    required: Vec<Clause>
+}
+
+impl Espresso {
+    fn new(on: &Proposition, dont_care: &Proposition, variables: Vec<String>) -> Espresso {
+        Espresso {
+            variables,
+            forbidden: Clause::from_proposition_defined(not(or(on.clone(), dont_care.clone()))),
+            required: Clause::from_proposition_defined(and(on.clone(), not(dont_care.clone())))
+        }
+    }
+
+    /// Builds a cover of prime implicants, and then repeatedly reduces each
+    /// cube to the part that no other cube covers, expands them again in a
+    /// different order and removes those that are no longer needed, for as
+    /// long as that makes the cover smaller.
+    fn minimize(&self) -> Vec<Ternary> {
+        let forward: Vec<usize> = (0..self.variables.len()).collect();
+        let backward: Vec<usize> = forward.iter().rev().cloned().collect();
+
+        let mut cover = vec!();
+        while let Some(model) = self.uncovered(&[], &cover, None) {
+            let minterm = self.variables.iter()
+                .map(|v| Some(model.get(v).cloned().unwrap_or(false)))
+                .collect();
+            cover.push(self.expand(minterm, &forward));
+        }
+        let mut cover = self.irredundant(cover);
+
+        for pass in 0.. {
+            let order = if pass % 2 == 0 { &backward } else { &forward };
+
+            let expanded = self.reduce(cover.clone()).into_iter()
+                .map(|c| self.expand(c, order))
+                .collect();
+            let next = self.irredundant(expanded);
+
+            if cost(&next) >= cost(&cover) {
+                break
+            }
+            cover = next;
+        }
+
+        cover
+    }
+
+    /// Returns an assignment that some cube must contain, lies within the
+    /// given cube and is not covered by the cubes of the cover other than
+    /// the skipped one, if there is such an assignment.
+    fn uncovered(&self, cube: &[Option<bool>], cover: &[Ternary], skip: Option<usize>) -> Option<HashMap<String, bool>> {
+        let mut clauses = self.required.clone();
+        clauses.extend(parts(cube, &self.variables, true).into_iter()
+            .map(|p| Clause { parts: vec!(p) }));
+        clauses.extend(cover.iter()
+            .enumerate()
+            .filter(|&(i, _)| Some(i) != skip)
+            .map(|(_, c)| Clause { parts: parts(c, &self.variables, false) }));
+
+        find_clause_model(&clauses)
+    }
+
+    /// Checks that the cube contains no assignment that must not be covered.
+    fn is_implicant(&self, cube: &[Option<bool>]) -> bool {
+        let mut clauses = self.forbidden.clone();
+        clauses.extend(parts(cube, &self.variables, true).into_iter()
+            .map(|p| Clause { parts: vec!(p) }));
+
+        find_clause_model(&clauses).is_none()
+    }
+
+    /// Leaves out each literal of the cube in the given order of the terms,
+    /// whenever it stays an implicant without it, making it prime.
+    fn expand(&self, mut cube: Ternary, order: &[usize]) -> Ternary {
+        for &i in order {
+            if let Some(value) = cube[i] {
+                cube[i] = None;
+                if !self.is_implicant(&cube) {
+                    cube[i] = Some(value);
+                }
+            }
+        }
+        cube
+    }
+
+    /// Removes the cubes that are covered by the rest, trying the cubes with
+    /// the most literals first.
+    fn irredundant(&self, mut cover: Vec<Ternary>) -> Vec<Ternary> {
+        cover.sort_by_key(|c| c.iter().filter(|v| v.is_none()).count());
+
+        let mut i = 0;
+        while i < cover.len() {
+            if self.uncovered(&cover[i], &cover, Some(i)).is_none() {
+                cover.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        cover
+    }
+
+    /// Shrinks each cube in turn to the smallest cube containing the
+    /// assignments that only it covers, dropping it if there are none.
+    fn reduce(&self, mut cover: Vec<Ternary>) -> Vec<Ternary> {
+        let mut i = 0;
+        while i < cover.len() {
+            if self.uncovered(&cover[i], &cover, Some(i)).is_none() {
+                cover.remove(i);
+                continue
+            }
+
+            for j in 0..self.variables.len() {
+                if cover[i][j].is_some() {
+                    continue
+                }
+                for &value in &[true, false] {
+                    let mut other = cover[i].clone();
+                    other[j] = Some(!value);
+                    if self.uncovered(&other, &cover, Some(i)).is_none() {
+                        cover[i][j] = Some(value);
+                        break
+                    }
+                }
+            }
+            i += 1;
+        }
+        cover
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use minimize::*;
+    use parser::*;
+    use random::*;
+
+    /// Builds the disjunction of the given minterms over the given terms,
+    /// where the first term is the most significant bit.
+    fn minterms(terms: &[&str], numbers: &[u64]) -> Proposition {
+        or_all(numbers.iter()
+            .map(|n| and_all(terms.iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    let t = term(t.to_string());
+                    if n & (1 << (terms.len() - 1 - i)) != 0 { t } else { not(t) }
+                })
+                .collect()))
+            .collect())
+    }
+
+    fn sop(cubes: &[Cube]) -> Proposition {
+        or_all(cubes.iter()
+            .map(|c| and_all(c.parts.iter().map(literal).collect()))
+            .collect())
+    }
+
+    fn literals(cubes: &[Cube]) -> (usize, usize) {
+        (cubes.len(), cubes.iter().map(|c| c.parts.len()).sum())
+    }
+
+    /// Checks that the two propositions agree wherever the don't care
+    /// proposition is false.
+    fn agree(a: &Proposition, b: &Proposition, dont_care: &Proposition) -> bool {
+        equivalent(&and(a.clone(), not(dont_care.clone())), &and(b.clone(), not(dont_care.clone())))
+    }
+
+    #[test]
+    fn minimize_sop_with_dont_cares() {
+        let terms = ["a", "b", "c", "d"];
+        let on = minterms(&terms, &[4, 8, 10, 11, 12, 15]);
+        let dont_care = minterms(&terms, &[9, 14]);
+
+        for &method in &[MinimizationMethod::QuineMcCluskey, MinimizationMethod::Espresso] {
+            let cubes = minimize_sop(&on, &dont_care, method);
+
+            assert!(agree(&sop(&cubes), &on, &dont_care), "{}", sop(&cubes));
+            assert_eq!(literals(&cubes), (3, 7), "{}", sop(&cubes));
+        }
+
+        let cubes = minimize_sop(&on, &dont_care, MinimizationMethod::QuineMcCluskey);
+        assert_eq!(sop(&cubes), parse_proposition("(a /\\ c) \\/ (a /\\ ~d) \\/ (b /\\ ~c /\\ ~d)").unwrap());
+    }
+
+    #[test]
+    fn minimize_cyclic() {
+        // Every minterm is covered by exactly two primes, so there are no
+        // essential primes and the cover has to be searched for
+        let terms = ["a", "b", "c"];
+        let on = minterms(&terms, &[0, 1, 2, 5, 6, 7]);
+
+        let cubes = minimize_sop(&on, &bottom(), MinimizationMethod::QuineMcCluskey);
+
+        assert!(equivalent(&sop(&cubes), &on));
+        assert_eq!(literals(&cubes), (3, 6));
+    }
+
+    #[test]
+    fn minimize_random() {
+        let mut random = Random::new(45);
+
+        let terms = ["p", "q", "r", "s", "t"];
+        for _ in 0..25 {
+            let mut numbers = |count: u64| -> Vec<u64> {
+                (0..count).map(|_| random.next(32)).collect()
+            };
+            let on = minterms(&terms, &numbers(12));
+            let dont_care = minterms(&terms, &numbers(4));
+
+            let exact = minimize_sop(&on, &dont_care, MinimizationMethod::QuineMcCluskey);
+            let heuristic = minimize_sop(&on, &dont_care, MinimizationMethod::Espresso);
+
+            assert!(agree(&sop(&exact), &on, &dont_care), "{} and {}", sop(&exact), on);
+            assert!(agree(&sop(&heuristic), &on, &dont_care), "{} and {}", sop(&heuristic), on);
+            assert!(literals(&exact) <= literals(&heuristic));
+
+            let plain = minimize_sop(&on, &bottom(), MinimizationMethod::QuineMcCluskey);
+            assert!(equivalent(&sop(&plain), &on));
+            assert!(plain.len() <= Cube::from_proposition(on.clone()).len());
+            assert!(literals(&exact) <= literals(&plain));
+        }
+    }
+
+    #[test]
+    fn minimize_espresso_many_terms() {
+        // The pairs of terms written as a product of sums, which has a
+        // clause for each way of picking one term from every pair
+        let pairs: Vec<Proposition> = (0..6)
+            .map(|i| and(term(format!("a{}", i)), term(format!("b{}", i))))
+            .collect();
+        let on = or_all(pairs.clone()).to_cnf();
+
+        let cubes = minimize_sop(&on, &bottom(), MinimizationMethod::Automatic);
+
+        assert!(equivalent(&sop(&cubes), &on));
+        assert_eq!(literals(&cubes), (6, 12));
+    }
+
+    #[test]
+    fn minimize_quine_mccluskey_too_many_terms() {
+        // Too many terms for the truth table, so Espresso is used instead
+        let on = and_all((0..64).map(|i| term(format!("p{}", i))).collect());
+
+        let cubes = minimize_sop(&on, &bottom(), MinimizationMethod::QuineMcCluskey);
+
+        assert_eq!(literals(&cubes), (1, 64));
+    }
+
+    #[test]
+    fn minimize_pos_with_dont_cares() {
+        let terms = ["a", "b", "c", "d"];
+        let on = minterms(&terms, &[4, 8, 10, 11, 12, 15]);
+        let dont_care = minterms(&terms, &[9, 14]);
+
+        let clauses = minimize_pos(&on, &dont_care, MinimizationMethod::Automatic);
+        let product = and_all(clauses.iter()
+            .map(|c| or_all(c.parts.iter().map(literal).collect()))
+            .collect());
+
+        assert!(agree(&product, &on, &dont_care), "{}", product);
+        assert_eq!(product, parse_proposition("(a \\/ b) /\\ (a \\/ ~c) /\\ (c \\/ ~d)").unwrap());
+    }
+
+    #[test]
+    fn minimize_constants() {
+        let valid = parse_proposition("p \\/ ~p").unwrap();
+        let invalid = parse_proposition("p /\\ ~p").unwrap();
+
+        assert_eq!(valid.to_minimal_dnf(), top());
+        assert_eq!(valid.to_minimal_cnf(), top());
+        assert_eq!(invalid.to_minimal_dnf(), bottom());
+        assert_eq!(invalid.to_minimal_cnf(), bottom());
+
+        let p = term("p".to_string());
+        assert_eq!(minimize_sop(&p, &top(), MinimizationMethod::Espresso), vec!());
+        assert_eq!(minimize_sop(&bottom(), &bottom(), MinimizationMethod::Espresso), vec!());
+        assert_eq!(minimize_sop(&top(), &bottom(), MinimizationMethod::Espresso), vec!(Cube { parts: vec!() }));
+    }
+}
@@ -0,0 +1,421 @@
+use clauses::*;
+use sat::*;
+
+use std::collections::{BTreeSet, HashMap};
+
+/// An iterator over the satisfying assignments of a set of clauses, which
+/// finds each one only when it is asked for.
+///
+/// After each assignment is found, a blocking clause that rules it out is
+/// added, so that the next search finds a different one.
+pub struct Models {
+    solver: Solver,
+    variables: Vec<String>,
+    finished: bool
+}
+
+impl Iterator for Models {
+    type Item = HashMap<String, bool>;
+
+    fn next(&mut self) -> Option<HashMap<String, bool>> {
+        if self.finished {
+            return None
+        }
+
+        let model = match self.solver.find_model() {
+            Some(m) => m,
+            None => {
+                self.finished = true;
+                return None
+            }
+        };
+
+        let projected: HashMap<String, bool> = self.variables.iter()
+            .map(|v| (v.clone(), model[v]))
+            .collect();
+
+        let blocking = Clause {
+            parts: self.variables.iter()
+                .map(|v| if projected[v] {
+                    ClausePart::NegatedTerm(v.clone())
+                } else {
+                    ClausePart::Term(v.clone())
+                })
+                .collect()
+        };
+
+        // Blocking every assignment to no variables leaves no assignments
+        if blocking.parts.is_empty() {
+            self.finished = true;
+        }
+        self.solver.add_clause(&blocking);
+
+        Some(projected)
+    }
+}
+
+/// Returns an iterator over every assignment to the terms of the given
+/// clauses that satisfies them.
+///
+/// The clauses from `Clause::from_proposition` may contain terms defined to
+/// stand for subformulas, which are left out of each assignment. Since
+/// their values follow from the other terms, leaving them out does not
+/// change the number of assignments.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses = Clause::from_proposition(parse_proposition("p \\/ q").unwrap());
+///
+/// let mut models: Vec<(bool, bool)> = enumerate_models(&clauses)
+///     .map(|m| (m["p"], m["q"]))
+///     .collect();
+/// models.sort();
+///
+/// assert_eq!(models, vec!((false, true), (true, false), (true, true)));
+/// ```
+pub fn enumerate_models(clauses: &[Clause]) -> Models {
+    let variables: BTreeSet<String> = clauses.iter()
+        .flat_map(|c| c.parts.iter().map(|p| p.name().to_string()))
+        .filter(|t| !is_defined_term(t))
+        .collect();
+
+    enumerate_models_over(clauses, &variables.into_iter().collect::<Vec<String>>())
+}
+
+/// Returns an iterator over the assignments to the given variables that can
+/// be extended to satisfy the given clauses, each given once.
+///
+/// Variables that do not appear in the clauses can have either value.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses = Clause::from_proposition(parse_proposition("(p -> q) /\\ r").unwrap());
+///
+/// let models: Vec<_> = enumerate_models_over(&clauses, &["r".to_string()]).collect();
+///
+/// assert_eq!(models.len(), 1);
+/// assert_eq!(models[0]["r"], true);
+/// ```
+pub fn enumerate_models_over(clauses: &[Clause], variables: &[String]) -> Models {
+    let mut solver = Solver::new(clauses);
+
+    // Mentions every variable in a clause that always holds, so that
+    // variables missing from the clauses still get a value
+    for v in variables {
+        solver.add_clause(&Clause {
+            parts: vec!(ClausePart::Term(v.clone()), ClausePart::NegatedTerm(v.clone()))
+        });
+    }
+
+    let mut unique: Vec<String> = vec!();
+    for v in variables {
+        if !unique.contains(v) {
+            unique.push(v.clone());
+        }
+    }
+
+    Models {
+        solver,
+        variables: unique,
+        finished: false
+    }
+}
+
+/// Counts the assignments to the terms of the given clauses that satisfy
+/// them.
+///
+/// The clauses are split into components that share no terms, which are
+/// counted separately and multiplied together, and the count of each
+/// component is cached, so that it is only found once however many times it
+/// comes up. Within a component, the count is the sum of the counts with a
+/// chosen term set to each value.
+///
+/// Terms defined to stand for subformulas in the clauses from
+/// `Clause::from_proposition` have their values fixed by the other terms, so
+/// they do not change the count.
+///
+/// Returns `None` if there are 2<sup>128</sup> or more models, which can
+/// only happen with at least 128 terms.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses = Clause::from_proposition(
+///     parse_proposition("(a \\/ b) /\\ (c <+> d) /\\ (e -> f)").unwrap()
+/// );
+///
+/// assert_eq!(count_models(&clauses), Some(3 * 2 * 3));
+/// assert_eq!(count_models(&clauses), Some(enumerate_models(&clauses).count() as u128));
+/// ```
+pub fn count_models(clauses: &[Clause]) -> Option<u128> {
+    let mut indices: HashMap<&str, i64> = HashMap::new();
+    let mut numbered: Vec<Vec<i64>> = vec!();
+
+    for c in clauses {
+        let mut literals: Vec<i64> = vec!();
+        for p in &c.parts {
+            let next = indices.len() as i64 + 1;
+            let index = *indices.entry(p.name()).or_insert(next);
+            let literal = match *p {
+                ClausePart::Term(_) => index,
+                ClausePart::NegatedTerm(_) => -index,
+            };
+
+            if !literals.contains(&literal) {
+                literals.push(literal);
+            }
+        }
+
+        // Clauses that always hold do not affect the count
+        if !literals.iter().any(|l| literals.contains(&-l)) {
+            numbered.push(literals);
+        }
+    }
+
+    let variables = indices.values().cloned().collect();
+    let mut counter = Counter { cache: HashMap::new() };
+
+    counter.count(numbered, variables)
+}
+
+/// Counts models of clauses over numbered terms, where a negative literal
+/// is a negated term. Returns None if a count overflows.
+struct Counter {
+    cache: HashMap<Vec<Vec<i64>>, u128>
+}
+
+impl Counter {
+    /// Counts the assignments to the given variables that satisfy the
+    /// clauses, which must only use those variables.
+    fn count(&mut self, clauses: Vec<Vec<i64>>, mut variables: BTreeSet<i64>) -> Option<u128> {
+        let mut clauses = clauses;
+        if clauses.iter().any(|c| c.is_empty()) {
+            return Some(0)
+        }
+
+        while let Some(unit) = clauses.iter().find(|c| c.len() == 1).map(|c| c[0]) {
+            clauses = match condition(&clauses, unit) {
+                Some(c) => c,
+                None => return Some(0)
+            };
+            variables.remove(&unit.abs());
+        }
+
+        let used: BTreeSet<i64> = clauses.iter().flatten().map(|l| l.abs()).collect();
+        let mut total = 1u128.checked_shl((variables.len() - used.len()) as u32)?;
+
+        for component in components(clauses) {
+            total = total.checked_mul(self.count_component(component)?)?;
+            if total == 0 {
+                break
+            }
+        }
+
+        Some(total)
+    }
+
+    /// Counts the assignments to the variables of the given clauses, which
+    /// are connected by sharing variables, that satisfy them.
+    fn count_component(&mut self, mut clauses: Vec<Vec<i64>>) -> Option<u128> {
+        for c in clauses.iter_mut() {
+            c.sort_unstable();
+        }
+        clauses.sort_unstable();
+
+        if let Some(&n) = self.cache.get(&clauses) {
+            return Some(n)
+        }
+
+        // Branches on the variable in the most clauses
+        let mut occurrences: HashMap<i64, usize> = HashMap::new();
+        for l in clauses.iter().flatten() {
+            *occurrences.entry(l.abs()).or_insert(0) += 1;
+        }
+        let variable = occurrences.iter()
+            .max_by_key(|&(v, n)| (n, -v))
+            .map(|(&v, _)| v)
+            .unwrap();
+
+        let remaining: BTreeSet<i64> = occurrences.keys()
+            .cloned()
+            .filter(|&v| v != variable)
+            .collect();
+
+        let mut total = 0u128;
+        for &literal in &[variable, -variable] {
+            if let Some(c) = condition(&clauses, literal) {
+                total = total.checked_add(self.count(c, remaining.clone())?)?;
+            }
+        }
+
+        self.cache.insert(clauses, total);
+        Some(total)
+    }
+}
+
+/// Returns the clauses that remain when the given literal is true, or None
+/// if a clause becomes empty.
+fn condition(clauses: &[Vec<i64>], literal: i64) -> Option<Vec<Vec<i64>>> {
+    let mut result = vec!();
+
+    for c in clauses {
+        if c.contains(&literal) {
+            continue
+        }
+
+        let remaining: Vec<i64> = c.iter().cloned().filter(|&l| l != -literal).collect();
+        if remaining.is_empty() {
+            return None
+        }
+        result.push(remaining);
+    }
+
+    Some(result)
+}
+
+/// Splits the given clauses into groups, such that clauses in different
+/// groups share no variables.
+fn components(clauses: Vec<Vec<i64>>) -> Vec<Vec<Vec<i64>>> {
+    let mut parents: HashMap<i64, i64> = HashMap::new();
+
+    fn find(parents: &mut HashMap<i64, i64>, v: i64) -> i64 {
+        let parent = *parents.entry(v).or_insert(v);
+        if parent == v {
+            v
+        } else {
+            let root = find(parents, parent);
+            parents.insert(v, root);
+            root
+        }
+    }
+
+    for c in &clauses {
+        let first = find(&mut parents, c[0].abs());
+        for l in &c[1..] {
+            let root = find(&mut parents, l.abs());
+            parents.insert(root, first);
+        }
+    }
+
+    let mut groups: Vec<(i64, Vec<Vec<i64>>)> = vec!();
+    for c in clauses {
+        let root = find(&mut parents, c[0].abs());
+        match groups.iter_mut().find(|(r, _)| *r == root) {
+            Some((_, group)) => group.push(c),
+            None => groups.push((root, vec!(c)))
+        }
+    }
+
+    groups.into_iter().map(|(_, g)| g).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use models::*;
+    use parser::*;
+    use propositions::*;
+
+    fn clauses_of(input: &str) -> Vec<Clause> {
+        Clause::from_proposition(parse_proposition(input).unwrap())
+    }
+
+    #[test]
+    fn enumerate_models_all_satisfy() {
+        let prop = parse_proposition("(a -> b) /\\ (b <+> c) /\\ ~(a /\\ c)").unwrap();
+        let clauses = Clause::from_proposition(prop.clone());
+
+        let models: Vec<HashMap<String, bool>> = enumerate_models_over(&clauses, &prop.terms()).collect();
+
+        assert_eq!(models.len(), 3);
+        for (i, m) in models.iter().enumerate() {
+            assert_eq!(prop.evaluate(m), Some(true));
+            assert!(!models[..i].contains(m));
+        }
+    }
+
+    #[test]
+    fn enumerate_models_lazily() {
+        // 2^40 models, of which only the first few are ever found
+        let props: Vec<Proposition> = (0..40)
+            .map(|i| or(term(format!("x{}", i)), term(format!("y{}", i))))
+            .collect();
+        let clauses = Clause::from_proposition(and_all(props));
+
+        assert_eq!(enumerate_models(&clauses).take(5).count(), 5);
+    }
+
+    #[test]
+    fn enumerate_models_leaves_out_defined_terms() {
+        let clauses = clauses_of("(a /\\ b) <+> c");
+
+        let models: Vec<HashMap<String, bool>> = enumerate_models(&clauses).collect();
+
+        assert_eq!(models.len(), 4);
+        for m in &models {
+            let mut terms: Vec<&String> = m.keys().collect();
+            terms.sort();
+            assert_eq!(terms, vec!("a", "b", "c"));
+        }
+    }
+
+    #[test]
+    fn enumerate_models_over_missing_variable() {
+        let clauses = clauses_of("p");
+
+        let models: Vec<_> = enumerate_models_over(&clauses, &["q".to_string()]).collect();
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(enumerate_models_over(&clauses, &[]).count(), 1);
+        assert_eq!(enumerate_models_over(&clauses_of("p /\\ ~p"), &[]).count(), 0);
+    }
+
+    #[test]
+    fn count_models_matches_enumeration() {
+        let inputs = vec!(
+            "a <+> b <+> c <+> d",
+            "(a \\/ b \\/ c) /\\ (~a \\/ ~b) /\\ (d -> a) /\\ (e <-> ~c)",
+            "if a then b /\\ c else (d ~/\\ e) \\/ f",
+            "(a -> b) /\\ (b -> c) /\\ (c -> d) /\\ (d -> a) /\\ (e \\/ f)",
+            "a /\\ ~a",
+            "true",
+        );
+
+        for input in inputs {
+            let clauses = clauses_of(input);
+
+            assert_eq!(
+                count_models(&clauses),
+                Some(enumerate_models(&clauses).count() as u128),
+                "{}", input
+            );
+        }
+    }
+
+    #[test]
+    fn count_models_tautology() {
+        assert_eq!(count_models(&clauses_of("p /\\ (q \\/ ~q)")), Some(2));
+    }
+
+    #[test]
+    fn count_models_large() {
+        // 80 independent pairs with 3 models each
+        let props: Vec<Proposition> = (0..80)
+            .map(|i| or(term(format!("x{}", i)), term(format!("y{}", i))))
+            .collect();
+        let clauses = Clause::from_proposition(and_all(props));
+
+        assert_eq!(count_models(&clauses), Some(3u128.pow(80)));
+    }
+
+    #[test]
+    fn count_models_overflow() {
+        let props: Vec<Proposition> = (0..130)
+            .map(|i| or(term(format!("x{}", i)), not(term(format!("y{}", i)))))
+            .collect();
+        let clauses = Clause::from_proposition(and_all(props));
+
+        assert_eq!(count_models(&clauses), None);
+    }
+}
@@ -0,0 +1,240 @@
+use proofs::*;
+use propositions::*;
+
+/// The format to write a proof narration in.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum NarrationFormat {
+    PlainText,
+    Markdown
+}
+
+impl NarrationFormat {
+    /// Formats the given snippet of logic so that it stands out from the
+    /// surrounding prose.
+    fn code(&self, text: &str) -> String {
+        match *self {
+            NarrationFormat::PlainText => text.to_string(),
+            NarrationFormat::Markdown => format!("`{}`", text),
+        }
+    }
+}
+
+impl Proof {
+    /// Describes the proof in prose as a numbered list of steps, in the same
+    /// manner as the walkthrough in the README.
+    ///
+    /// Each step names the clauses that were resolved together and the
+    /// terms that were eliminated. Input clauses are traced back to the
+    /// assumption or negated goal that they came from.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let assumptions = vec!(
+    ///     term("p".to_string()),
+    ///     implies(term("p".to_string()), term("q".to_string()))
+    /// );
+    ///
+    /// let goal = term("q".to_string());
+    ///
+    /// let proof = prove(assumptions, goal).unwrap();
+    ///
+    /// let expected = "\
+    /// 1. Negate the goal q, giving the clause ~q.
+    /// 2. Resolve ~q with ~p \\/ q, which comes from assumption 2 (p -> q), eliminating ~q and q to get ~p.
+    /// 3. Resolve ~p with p, which comes from assumption 1 (p), eliminating ~p and p to get the empty clause.
+    /// 4. Since the empty clause was reached, the negation of the goal contradicts the assumptions, so q follows from them.
+    /// ";
+    ///
+    /// assert_eq!(proof.narrate(NarrationFormat::PlainText), expected);
+    /// ```
+    pub fn narrate(&self, format: NarrationFormat) -> String {
+        let mut sentences = vec!();
+
+        let goal = self.goal.to_string();
+        let first = &self.steps[0];
+        match first.justification {
+            Justification::Assumption(i) if self.steps.len() == 1 => sentences.push(format!(
+                "Assumption {} ({}) gives the empty clause, so the assumptions \
+                 are contradictory and any goal follows from them.",
+                i + 1,
+                format.code(&self.assumptions[i].to_string())
+            )),
+            Justification::Assumption(i) => sentences.push(format!(
+                "Start from the clause {}, which comes from assumption {} ({}).",
+                format.code(&first.clause.to_string()),
+                i + 1,
+                format.code(&self.assumptions[i].to_string())
+            )),
+            _ => sentences.push(format!(
+                "Negate the goal {}, giving {}.",
+                format.code(&goal),
+                if first.clause.parts.is_empty() {
+                    "the empty clause".to_string()
+                } else {
+                    format!("the clause {}", format.code(&first.clause.to_string()))
+                }
+            )),
+        }
+
+        for step in &self.steps {
+            if let Justification::Resolution { left, right, ref pivot } = step.justification {
+                let resolvent = if step.clause.parts.is_empty() {
+                    "the empty clause".to_string()
+                } else {
+                    format.code(&step.clause.to_string())
+                };
+
+                sentences.push(format!(
+                    "Resolve {} with {}, eliminating {} and {} to get {}.",
+                    self.describe_step(left, format),
+                    self.describe_step(right, format),
+                    format.code(&pivot.to_string()),
+                    format.code(&pivot.negate().to_string()),
+                    resolvent
+                ));
+            }
+        }
+
+        if self.steps.iter().any(|s| s.justification == Justification::NegatedGoal) {
+            sentences.push(format!(
+                "Since the empty clause was reached, the negation of the goal \
+                 contradicts the assumptions, so {} follows from them.",
+                format.code(&goal)
+            ));
+        } else if self.steps.len() > 1 {
+            sentences.push(
+                "Since the empty clause was reached without the negation of the \
+                 goal, the assumptions are contradictory and any goal follows \
+                 from them.".to_string()
+            );
+        }
+
+        sentences.iter()
+            .enumerate()
+            .map(|(i, s)| format!("{}. {}\n", i + 1, s))
+            .collect()
+    }
+
+    /// Describes the clause of the given step, along with where it came from
+    /// if it is an input clause other than the starting clause.
+    fn describe_step(&self, index: usize, format: NarrationFormat) -> String {
+        let step = &self.steps[index];
+        let clause = format.code(&step.clause.to_string());
+
+        match step.justification {
+            Justification::Assumption(i) if index != 0 => format!(
+                "{}, which comes from assumption {} ({})",
+                clause,
+                i + 1,
+                format.code(&self.assumptions[i].to_string())
+            ),
+            Justification::NegatedGoal if index != 0 => format!(
+                "{}, which comes from the negated goal ({})",
+                clause,
+                format.code(&not(self.goal.clone()).to_string())
+            ),
+            _ => clause,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use narration::*;
+    use resolution::*;
+
+    #[test]
+    fn narrate_markdown_readme_example() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(
+                and(term("p".to_string()), term("q".to_string())),
+                term("r".to_string())
+            ),
+            implies(
+                or(term("s".to_string()), term("t".to_string())),
+                term("q".to_string())
+            ),
+            term("t".to_string())
+        );
+
+        let goal = term("r".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        let expected = "\
+1. Negate the goal `r`, giving the clause `~r`.
+2. Resolve `~r` with `~p \\/ ~q \\/ r`, which comes from assumption 2 (`p /\\ q -> r`), eliminating `~r` and `r` to get `~p \\/ ~q`.
+3. Resolve `~p \\/ ~q` with `p`, which comes from assumption 1 (`p`), eliminating `~p` and `p` to get `~q`.
+4. Resolve `~q` with `~t \\/ q`, which comes from assumption 3 (`s \\/ t -> q`), eliminating `~q` and `q` to get `~t`.
+5. Resolve `~t` with `t`, which comes from assumption 4 (`t`), eliminating `~t` and `t` to get the empty clause.
+6. Since the empty clause was reached, the negation of the goal contradicts the assumptions, so `r` follows from them.
+";
+
+        assert_eq!(proof.narrate(NarrationFormat::Markdown), expected);
+    }
+
+    #[test]
+    fn narrate_plain_text_negated_goal_clause() {
+        let assumptions = vec!();
+
+        let goal = or(
+            term("p".to_string()),
+            not(term("p".to_string()))
+        );
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        let expected = "\
+1. Negate the goal p \\/ ~(p), giving the clause ~p.
+2. Resolve ~p with p, which comes from the negated goal (~(p \\/ ~(p))), eliminating ~p and p to get the empty clause.
+3. Since the empty clause was reached, the negation of the goal contradicts the assumptions, so p \\/ ~(p) follows from them.
+";
+
+        assert_eq!(proof.narrate(NarrationFormat::PlainText), expected);
+    }
+
+    #[test]
+    fn narrate_plain_text_contradictory_assumption() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            bottom()
+        );
+
+        let goal = term("q".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        let expected = "\
+1. Assumption 2 (false) gives the empty clause, so the assumptions are contradictory and any goal follows from them.
+";
+
+        assert_eq!(proof.narrate(NarrationFormat::PlainText), expected);
+    }
+
+    #[test]
+    fn narrate_plain_text_contradictory_clauses() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(term("p".to_string()), term("q".to_string())),
+            not(term("q".to_string()))
+        );
+
+        let goal = term("r".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        let expected = "\
+1. Start from the clause p, which comes from assumption 1 (p).
+2. Resolve p with ~p \\/ q, which comes from assumption 2 (p -> q), eliminating p and ~p to get q.
+3. Resolve q with ~q, which comes from assumption 3 (~(q)), eliminating q and ~q to get the empty clause.
+4. Since the empty clause was reached without the negation of the goal, the assumptions are contradictory and any goal follows from them.
+";
+
+        assert_eq!(proof.narrate(NarrationFormat::PlainText), expected);
+    }
+}
@@ -0,0 +1,1088 @@
+use clauses::*;
+use proofs::*;
+use propositions::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::error::Error;
+use std::fmt;
+
+/// The rules that lines of a natural deduction proof can be justified by.
+///
+/// The rules of replacement (`DoubleNegation` through `Distribution`) can be
+/// applied to any number of non-overlapping subformulas of the referenced
+/// line at once, and in either direction. `Commutation` also covers
+/// association, and `Tautology` additionally allows repeated disjuncts or
+/// conjuncts to be merged. Both of those rules apply to the top level of the
+/// line only.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DeductionRule {
+    Premise,
+    Assumption,
+    ModusPonens,
+    ModusTollens,
+    HypotheticalSyllogism,
+    DisjunctiveSyllogism,
+    Conjunction,
+    Simplification,
+    Addition,
+    DoubleNegation,
+    DeMorgan,
+    MaterialImplication,
+    MaterialEquivalence,
+    Distribution,
+    Commutation,
+    Tautology,
+    IndirectProof
+}
+
+impl fmt::Display for DeductionRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            DeductionRule::Premise => "Premise",
+            DeductionRule::Assumption => "Assumption",
+            DeductionRule::ModusPonens => "Modus Ponens",
+            DeductionRule::ModusTollens => "Modus Tollens",
+            DeductionRule::HypotheticalSyllogism => "Hypothetical Syllogism",
+            DeductionRule::DisjunctiveSyllogism => "Disjunctive Syllogism",
+            DeductionRule::Conjunction => "Conjunction",
+            DeductionRule::Simplification => "Simplification",
+            DeductionRule::Addition => "Addition",
+            DeductionRule::DoubleNegation => "Double Negation",
+            DeductionRule::DeMorgan => "De Morgan",
+            DeductionRule::MaterialImplication => "Material Implication",
+            DeductionRule::MaterialEquivalence => "Material Equivalence",
+            DeductionRule::Distribution => "Distribution",
+            DeductionRule::Commutation => "Commutation",
+            DeductionRule::Tautology => "Tautology",
+            DeductionRule::IndirectProof => "Indirect Proof",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A line of a natural deduction proof.
+///
+/// `references` are the indices of the earlier lines that the rule is
+/// applied to, and `depth` is the number of subproofs that the line is
+/// nested within.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DeductionLine {
+    pub formula: Proposition,
+    pub rule: DeductionRule,
+    pub references: Vec<usize>,
+    pub depth: usize
+}
+
+/// A Fitch-style natural deduction proof.
+///
+/// The proof starts with its premises, and the goal is proven by assuming
+/// its negation in a subproof and deriving a contradiction of the form
+/// `p /\ ~(p)`.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct NaturalDeductionProof {
+    pub lines: Vec<DeductionLine>
+}
+
+/// An error encountered while translating a resolution refutation into a
+/// natural deduction proof.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct TranslationError {
+    pub message: String
+}
+
+impl fmt::Display for TranslationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for TranslationError {}
+
+/// A line of a natural deduction proof that is not correctly justified,
+/// numbered starting from 1.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct CheckError {
+    pub line: usize,
+    pub message: String
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for CheckError {}
+
+impl Proof {
+    /// Translates the resolution refutation into a natural deduction proof
+    /// of the goal from the assumptions.
+    ///
+    /// Each input clause is derived from its assumption by the rules of
+    /// replacement that mirror the conversion to conjunctive normal form, and
+    /// each resolution is replayed through syllogisms within a subproof that
+    /// assumes the negation of the goal.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let assumptions = vec!(
+    ///     term("p".to_string()),
+    ///     implies(term("p".to_string()), term("q".to_string()))
+    /// );
+    ///
+    /// let goal = term("q".to_string());
+    ///
+    /// let proof = prove(assumptions, goal.clone()).unwrap();
+    /// let deduction = proof.to_natural_deduction().unwrap();
+    ///
+    /// assert_eq!(deduction.check(), Ok(()));
+    /// assert_eq!(deduction.conclusion(), Some(&goal));
+    /// ```
+    pub fn to_natural_deduction(&self) -> Result<NaturalDeductionProof, TranslationError> {
+        let unsupported = self.assumptions.iter()
+            .chain(Some(&self.goal))
+            .find(|p| contains_unsupported(p));
+        if let Some(p) = unsupported {
+            return Err(TranslationError {
+                message: format!("Cannot translate {}, which uses a constant or an unsupported connective", p)
+            })
+        }
+
+        let mut translator = Translator { lines: vec!(), depth: 0 };
+
+        for a in &self.assumptions {
+            translator.add(a.clone(), DeductionRule::Premise, vec!());
+        }
+
+        translator.depth = 1;
+        let assumption = translator.add(
+            not(self.goal.clone()), DeductionRule::Assumption, vec!()
+        );
+
+        let mut step_lines: Vec<usize> = vec!();
+        for step in &self.steps {
+            let line = match step.justification {
+                Justification::Assumption(i) =>
+                    translator.derive_clause(i, &step.clause)?,
+                Justification::NegatedGoal =>
+                    translator.derive_clause(assumption, &step.clause)?,
+                Justification::Resolution { left, right, ref pivot } =>
+                    translator.resolve(
+                        (step_lines[left], &self.steps[left].clause),
+                        (step_lines[right], &self.steps[right].clause),
+                        pivot,
+                        &step.clause
+                    ),
+            };
+            step_lines.push(line);
+        }
+
+        let contradiction = step_lines[step_lines.len() - 1];
+
+        translator.depth = 0;
+        let negated = translator.add(
+            not(not(self.goal.clone())),
+            DeductionRule::IndirectProof,
+            vec!(assumption, contradiction)
+        );
+        translator.add(
+            self.goal.clone(), DeductionRule::DoubleNegation, vec!(negated)
+        );
+
+        Ok(NaturalDeductionProof { lines: translator.lines })
+    }
+}
+
+impl NaturalDeductionProof {
+    /// Returns the formula proven by the proof, which is its last line if
+    /// that line is outside of all subproofs.
+    pub fn conclusion(&self) -> Option<&Proposition> {
+        match self.lines.last() {
+            Some(line) if line.depth == 0 => Some(&line.formula),
+            _ => None
+        }
+    }
+
+    /// Checks that every line of the proof is correctly justified, only
+    /// refers to earlier lines that are still in scope, and that every
+    /// subproof is closed by the end of the proof.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let premise = DeductionLine {
+    ///     formula: term("p".to_string()),
+    ///     rule: DeductionRule::Premise,
+    ///     references: vec!(),
+    ///     depth: 0
+    /// };
+    ///
+    /// let addition = DeductionLine {
+    ///     formula: or(term("p".to_string()), term("q".to_string())),
+    ///     rule: DeductionRule::Addition,
+    ///     references: vec!(0),
+    ///     depth: 0
+    /// };
+    ///
+    /// let proof = NaturalDeductionProof { lines: vec!(premise, addition) };
+    ///
+    /// assert_eq!(proof.check(), Ok(()));
+    /// ```
+    pub fn check(&self) -> Result<(), CheckError> {
+        let mut open_subproofs: Vec<usize> = vec!();
+        let mut in_scope = vec!(false; self.lines.len());
+        let mut premises_done = false;
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let error = |message: String| Err(CheckError { line: i + 1, message });
+
+            for r in &line.references {
+                if *r >= i || !in_scope[*r] {
+                    return error(format!("Line {} is not in scope", r + 1))
+                }
+            }
+
+            match line.rule {
+                DeductionRule::Premise => {
+                    if premises_done {
+                        return error("Premises must come first".to_string())
+                    }
+                },
+                DeductionRule::Assumption => {
+                    premises_done = true;
+                    open_subproofs.push(i);
+                },
+                DeductionRule::IndirectProof => {
+                    premises_done = true;
+                    let subproof = match open_subproofs.pop() {
+                        Some(s) => s,
+                        None => return error("No subproof to close".to_string())
+                    };
+
+                    if line.references.len() != 2 || line.references[0] != subproof {
+                        return error(
+                            "Expected the assumption of the subproof and a contradiction"
+                                .to_string()
+                        )
+                    }
+
+                    let contradiction = line.references[1];
+                    if !is_contradiction(&self.lines[contradiction].formula) {
+                        return error(format!("Line {} is not a contradiction", contradiction + 1))
+                    }
+
+                    if line.formula != not(self.lines[subproof].formula.clone()) {
+                        return error("Expected the negation of the assumption".to_string())
+                    }
+
+                    for s in in_scope.iter_mut().take(i).skip(subproof) {
+                        *s = false;
+                    }
+                },
+                rule => {
+                    premises_done = true;
+                    let cited: Vec<&Proposition> = line.references.iter()
+                        .map(|r| &self.lines[*r].formula)
+                        .collect();
+
+                    if !follows(rule, &cited, &line.formula) {
+                        return error(format!("Does not follow by {}", rule))
+                    }
+                },
+            }
+
+            if line.depth != open_subproofs.len() {
+                return error(format!(
+                    "Expected a depth of {}, but found {}",
+                    open_subproofs.len(),
+                    line.depth
+                ))
+            }
+
+            in_scope[i] = true;
+        }
+
+        match open_subproofs.last() {
+            Some(s) => Err(CheckError {
+                line: s + 1,
+                message: "Subproof is never closed".to_string()
+            }),
+            None => Ok(())
+        }
+    }
+}
+
+impl fmt::Display for NaturalDeductionProof {
+    /// Displays the proof as a numbered list of lines, in the style of the
+    /// proof at the end of the README. Lines within a subproof are prefixed
+    /// with a bar for each level of nesting.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let number_width = self.lines.len().to_string().len();
+        let formulas: Vec<String> = self.lines.iter()
+            .map(|l| format!("{}{}", "| ".repeat(l.depth), l.formula))
+            .collect();
+        let formula_width = formulas.iter()
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let is_first_non_premise = line.rule != DeductionRule::Premise
+                && (i == 0 || self.lines[i - 1].rule == DeductionRule::Premise);
+            if is_first_non_premise && i > 0 {
+                writeln!(f, "{}", "-".repeat(number_width + formula_width + 4))?;
+            }
+
+            let references = match line.rule {
+                DeductionRule::IndirectProof if line.references.len() == 2 =>
+                    format!("{}-{}", line.references[0] + 1, line.references[1] + 1),
+                _ => line.references.iter()
+                    .map(|r| (r + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            };
+
+            let justification = format!("{} {}", line.rule, references);
+            writeln!(
+                f,
+                "{:>nw$}) {:<fw$}  {}",
+                i + 1,
+                formulas[i],
+                justification.trim_end(),
+                nw = number_width,
+                fw = formula_width
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds up the lines of a natural deduction proof.
+struct Translator {
+    lines: Vec<DeductionLine>,
+    depth: usize
+}
+
+impl Translator {
+    fn add(&mut self, formula: Proposition, rule: DeductionRule, references: Vec<usize>) -> usize {
+        self.lines.push(DeductionLine {
+            formula,
+            rule,
+            references,
+            depth: self.depth
+        });
+
+        self.lines.len() - 1
+    }
+
+    /// Derives the given formula from the given line if it differs from it
+    /// only by the order or repetition of its disjuncts, returning the line
+    /// that the formula is on.
+    fn rearrange(&mut self, line: usize, target: Proposition) -> usize {
+        let current = &self.lines[line].formula;
+
+        if *current == target {
+            line
+        } else if same_operands(current, &target, false) {
+            self.add(target, DeductionRule::Commutation, vec!(line))
+        } else {
+            self.add(target, DeductionRule::Tautology, vec!(line))
+        }
+    }
+
+    /// Applies the given rule of replacement to the formula on the given line
+    /// as many times as possible without overlapping, adding a line for the
+    /// result if anything changed.
+    fn replace(&mut self, line: usize, rule: DeductionRule) -> Option<usize> {
+        rewrite_outermost(&self.lines[line].formula, rule)
+            .map(|f| self.add(f, rule, vec!(line)))
+    }
+
+    /// Derives the given input clause from the formula on the given line,
+    /// which is the assumption or negated goal that the clause came from.
+    fn derive_clause(&mut self, source: usize, clause: &Clause) -> Result<usize, TranslationError> {
+        let mut line = source;
+
+        // Each pass only rewrites the outermost matches, so the rules are
+        // applied until nothing changes to reach nested ones
+        for rules in &[
+            [DeductionRule::MaterialEquivalence, DeductionRule::MaterialImplication],
+            [DeductionRule::DeMorgan, DeductionRule::DoubleNegation]
+        ] {
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for rule in rules {
+                    if let Some(l) = self.replace(line, *rule) {
+                        line = l;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        while let Some(l) = self.replace(line, DeductionRule::Distribution) {
+            line = l;
+        }
+
+        let target = clause_formula(&clause.parts);
+        let path = match find_conjunct(&self.lines[line].formula, &target) {
+            Some(p) => p,
+            None => return Err(TranslationError {
+                message: format!(
+                    "Could not find clause {} in {}",
+                    clause,
+                    self.lines[source].formula
+                )
+            })
+        };
+
+        for take_left in path {
+            let conjunct = match self.lines[line].formula {
+                Proposition::And(ref a, _) if take_left => *a.clone(),
+                Proposition::And(_, ref b) => *b.clone(),
+                _ => unreachable!(),
+            };
+            line = self.add(conjunct, DeductionRule::Simplification, vec!(line));
+        }
+
+        Ok(self.rearrange(line, target))
+    }
+
+    /// Replays the resolution of the two given clauses on the given pivot,
+    /// returning the line of the resolvent, or of a contradiction if the
+    /// resolvent is the empty clause.
+    fn resolve(
+        &mut self,
+        (left, left_clause): (usize, &Clause),
+        (right, right_clause): (usize, &Clause),
+        pivot: &ClausePart,
+        resolvent: &Clause
+    ) -> usize {
+        let negated_pivot = pivot.negate();
+        let a: Vec<ClausePart> = left_clause.parts.iter()
+            .filter(|p| *p != pivot)
+            .cloned()
+            .collect();
+        let b: Vec<ClausePart> = right_clause.parts.iter()
+            .filter(|p| **p != negated_pivot)
+            .cloned()
+            .collect();
+
+        let x = literal_formula(pivot);
+        let nx = literal_formula(&negated_pivot);
+        let positive = match *pivot {
+            ClausePart::Term(_) => true,
+            ClausePart::NegatedTerm(_) => false,
+        };
+
+        match (a.is_empty(), b.is_empty()) {
+            (true, true) => {
+                let l = self.rearrange(left, x.clone());
+                let r = self.rearrange(right, nx.clone());
+
+                if positive {
+                    self.add(and(x, nx), DeductionRule::Conjunction, vec!(l, r))
+                } else {
+                    self.add(and(nx, x), DeductionRule::Conjunction, vec!(r, l))
+                }
+            },
+            (true, false) => {
+                let b_f = clause_formula(&b);
+                let l = self.rearrange(left, x.clone());
+                let r = self.rearrange(right, or(nx, b_f.clone()));
+
+                let not_nx = if positive {
+                    self.add(not(not(x)), DeductionRule::DoubleNegation, vec!(l))
+                } else {
+                    l
+                };
+
+                let d = self.add(b_f, DeductionRule::DisjunctiveSyllogism, vec!(r, not_nx));
+                self.rearrange(d, clause_formula(&resolvent.parts))
+            },
+            (false, true) => {
+                let a_f = clause_formula(&a);
+                let r = self.rearrange(right, nx.clone());
+                let l = self.rearrange(left, or(x, a_f.clone()));
+
+                let not_x = if positive {
+                    r
+                } else {
+                    self.add(not(not(nx)), DeductionRule::DoubleNegation, vec!(r))
+                };
+
+                let d = self.add(a_f, DeductionRule::DisjunctiveSyllogism, vec!(l, not_x));
+                self.rearrange(d, clause_formula(&resolvent.parts))
+            },
+            (false, false) => {
+                let a_f = clause_formula(&a);
+                let b_f = clause_formula(&b);
+
+                let l1 = self.rearrange(left, or(a_f.clone(), x.clone()));
+                let l2 = self.add(
+                    or(not(not(a_f.clone())), x.clone()),
+                    DeductionRule::DoubleNegation,
+                    vec!(l1)
+                );
+                let l3 = self.add(
+                    implies(not(a_f.clone()), x.clone()),
+                    DeductionRule::MaterialImplication,
+                    vec!(l2)
+                );
+
+                let r1 = self.rearrange(right, or(nx.clone(), b_f.clone()));
+                let r2 = if positive {
+                    r1
+                } else {
+                    self.add(
+                        or(not(not(nx)), b_f.clone()),
+                        DeductionRule::DoubleNegation,
+                        vec!(r1)
+                    )
+                };
+                let r3 = self.add(
+                    implies(x, b_f.clone()),
+                    DeductionRule::MaterialImplication,
+                    vec!(r2)
+                );
+
+                let h = self.add(
+                    implies(not(a_f.clone()), b_f.clone()),
+                    DeductionRule::HypotheticalSyllogism,
+                    vec!(l3, r3)
+                );
+                let i = self.add(
+                    or(not(not(a_f.clone())), b_f.clone()),
+                    DeductionRule::MaterialImplication,
+                    vec!(h)
+                );
+                let d = self.add(or(a_f, b_f), DeductionRule::DoubleNegation, vec!(i));
+
+                self.rearrange(d, clause_formula(&resolvent.parts))
+            },
+        }
+    }
+}
+
+/// Returns the formula of the given clause part.
+fn literal_formula(part: &ClausePart) -> Proposition {
+    match *part {
+        ClausePart::Term(ref a) => term(a.clone()),
+        ClausePart::NegatedTerm(ref a) => not(term(a.clone())),
+    }
+}
+
+/// Returns the disjunction of the given non-empty list of clause parts,
+/// grouped to the left.
+fn clause_formula(parts: &[ClausePart]) -> Proposition {
+    let mut formulas = parts.iter().map(literal_formula);
+    let first = formulas.next().expect("Clause should not be empty");
+
+    formulas.fold(first, or)
+}
+
+/// Returns whether the given formula uses a constant, or a connective other
+/// than the ones that the rules deal with.
+fn contains_unsupported(formula: &Proposition) -> bool {
+    match *formula {
+        Proposition::Or(ref a, ref b)
+        | Proposition::And(ref a, ref b)
+        | Proposition::Implies(ref a, ref b)
+        | Proposition::Iff(ref a, ref b) =>
+            contains_unsupported(a) || contains_unsupported(b),
+        Proposition::Not(ref a) => contains_unsupported(a),
+        Proposition::Term(_) => false,
+        _ => true,
+    }
+}
+
+/// Returns whether the given formula has the form `p /\ ~(p)`.
+fn is_contradiction(formula: &Proposition) -> bool {
+    match *formula {
+        Proposition::And(ref a, ref b) => **b == not(*a.clone()),
+        _ => false
+    }
+}
+
+/// Finds the conjunct of the given formula that has the same disjuncts as
+/// the given clause formula, returning the path to it as a list of whether
+/// to take the left side of each conjunction along the way.
+fn find_conjunct(formula: &Proposition, target: &Proposition) -> Option<Vec<bool>> {
+    if same_operands(formula, target, true) {
+        return Some(vec!())
+    }
+
+    match *formula {
+        Proposition::And(ref a, ref b) => {
+            for &(side, take_left) in &[(a, true), (b, false)] {
+                if let Some(mut path) = find_conjunct(side, target) {
+                    path.insert(0, take_left);
+                    return Some(path)
+                }
+            }
+            None
+        },
+        _ => None
+    }
+}
+
+/// Collects the operands of the given formula, flattening any nested uses
+/// of the connective at the top of the formula if it is a disjunction or
+/// conjunction.
+fn operands(formula: &Proposition) -> Vec<&Proposition> {
+    fn collect<'a>(formula: &'a Proposition, disjunction: bool, out: &mut Vec<&'a Proposition>) {
+        match *formula {
+            Proposition::Or(ref a, ref b) if disjunction => {
+                collect(a, disjunction, out);
+                collect(b, disjunction, out);
+            },
+            Proposition::And(ref a, ref b) if !disjunction => {
+                collect(a, disjunction, out);
+                collect(b, disjunction, out);
+            },
+            ref p => out.push(p),
+        }
+    }
+
+    let mut out = vec!();
+    match *formula {
+        Proposition::And(..) => collect(formula, false, &mut out),
+        _ => collect(formula, true, &mut out),
+    }
+    out
+}
+
+/// Checks whether the two formulas have the same top level connective and
+/// the same operands, ignoring their order and grouping. If `ignore_repeats`
+/// is set then operands that appear multiple times are only counted once.
+fn same_operands(a: &Proposition, b: &Proposition, ignore_repeats: bool) -> bool {
+    let is_and = |p: &Proposition| matches!(*p, Proposition::And(..));
+
+    let mut a_ops = operands(a);
+    let mut b_ops = operands(b);
+    if a_ops.len() > 1 && b_ops.len() > 1 && is_and(a) != is_and(b) {
+        return false
+    }
+
+    let key = |p: &&Proposition| p.to_string();
+    a_ops.sort_by_key(key);
+    b_ops.sort_by_key(key);
+    if ignore_repeats {
+        a_ops.dedup();
+        b_ops.dedup();
+    }
+
+    a_ops == b_ops
+}
+
+/// Returns the results of applying the given rule of replacement once to the
+/// top of the given formula, from left to right.
+fn replacements(rule: DeductionRule, formula: &Proposition) -> Vec<Proposition> {
+    match (rule, formula) {
+        (DeductionRule::DoubleNegation, Proposition::Not(a)) => match **a {
+            Proposition::Not(ref b) => vec!(*b.clone()),
+            _ => vec!()
+        },
+        (DeductionRule::DeMorgan, Proposition::Not(a)) => match **a {
+            Proposition::And(ref b, ref c) =>
+                vec!(or(not(*b.clone()), not(*c.clone()))),
+            Proposition::Or(ref b, ref c) =>
+                vec!(and(not(*b.clone()), not(*c.clone()))),
+            _ => vec!()
+        },
+        (DeductionRule::MaterialImplication, Proposition::Implies(a, b)) =>
+            vec!(or(not(*a.clone()), *b.clone())),
+        (DeductionRule::MaterialEquivalence, Proposition::Iff(a, b)) =>
+            vec!(and(
+                implies(*a.clone(), *b.clone()),
+                implies(*b.clone(), *a.clone())
+            )),
+        (DeductionRule::Distribution, Proposition::Or(a, b)) => {
+            let mut results = vec!();
+            if let Proposition::And(ref c, ref d) = **b {
+                results.push(and(
+                    or(*a.clone(), *c.clone()),
+                    or(*a.clone(), *d.clone())
+                ));
+            }
+            if let Proposition::And(ref c, ref d) = **a {
+                results.push(and(
+                    or(*c.clone(), *b.clone()),
+                    or(*d.clone(), *b.clone())
+                ));
+            }
+            results
+        },
+        _ => vec!()
+    }
+}
+
+/// Applies the given rule of replacement to the outermost subformulas of the
+/// given formula that it applies to, returning the result if anything
+/// changed.
+fn rewrite_outermost(formula: &Proposition, rule: DeductionRule) -> Option<Proposition> {
+    if let Some(r) = replacements(rule, formula).into_iter().next() {
+        return Some(r)
+    }
+
+    let binary = |a: &Proposition, b: &Proposition, op: fn(Proposition, Proposition) -> Proposition| {
+        let a2 = rewrite_outermost(a, rule);
+        let b2 = rewrite_outermost(b, rule);
+        if a2.is_none() && b2.is_none() {
+            None
+        } else {
+            Some(op(a2.unwrap_or_else(|| a.clone()), b2.unwrap_or_else(|| b.clone())))
+        }
+    };
+
+    match *formula {
+        Proposition::Or(ref a, ref b) => binary(a, b, or),
+        Proposition::And(ref a, ref b) => binary(a, b, and),
+        Proposition::Implies(ref a, ref b) => binary(a, b, implies),
+        Proposition::Iff(ref a, ref b) => binary(a, b, iff),
+        Proposition::Not(ref a) => rewrite_outermost(a, rule).map(not),
+        _ => None,
+    }
+}
+
+/// Checks whether `to` can be obtained from `from` by applying the given rule
+/// of replacement, in either direction, to any number of subformulas.
+fn replaced(rule: DeductionRule, from: &Proposition, to: &Proposition) -> bool {
+    if from == to
+        || replacements(rule, from).contains(to)
+        || replacements(rule, to).contains(from) {
+        return true
+    }
+
+    match (from, to) {
+        (Proposition::Or(a, b), Proposition::Or(c, d))
+        | (Proposition::And(a, b), Proposition::And(c, d))
+        | (Proposition::Implies(a, b), Proposition::Implies(c, d))
+        | (Proposition::Iff(a, b), Proposition::Iff(c, d)) =>
+            replaced(rule, a, c) && replaced(rule, b, d),
+        (Proposition::Not(a), Proposition::Not(b)) => replaced(rule, a, b),
+        _ => false
+    }
+}
+
+/// Checks whether the given conclusion follows from the cited formulas by
+/// the given rule, which is neither a premise, assumption, nor indirect
+/// proof.
+fn follows(rule: DeductionRule, cited: &[&Proposition], conclusion: &Proposition) -> bool {
+    use propositions::Proposition::*;
+
+    // Checks a two premise rule against both orders of the cited formulas
+    let either_order = |check: &dyn Fn(&Proposition, &Proposition) -> bool| {
+        cited.len() == 2 && (check(cited[0], cited[1]) || check(cited[1], cited[0]))
+    };
+
+    match rule {
+        DeductionRule::ModusPonens => either_order(&|a, b| match *a {
+            Implies(ref p, ref q) => **p == *b && **q == *conclusion,
+            _ => false
+        }),
+        DeductionRule::ModusTollens => either_order(&|a, b| match *a {
+            Implies(ref p, ref q) =>
+                *b == not(*q.clone()) && *conclusion == not(*p.clone()),
+            _ => false
+        }),
+        DeductionRule::HypotheticalSyllogism => either_order(&|a, b| match (a, b, conclusion) {
+            (Implies(p, q), Implies(q2, r), Implies(p2, r2)) =>
+                q == q2 && p == p2 && r == r2,
+            _ => false
+        }),
+        DeductionRule::DisjunctiveSyllogism => either_order(&|a, b| match *a {
+            Or(ref p, ref q) => *b == not(*p.clone()) && **q == *conclusion,
+            _ => false
+        }),
+        DeductionRule::Conjunction => cited.len() == 2
+            && *conclusion == and(cited[0].clone(), cited[1].clone()),
+        DeductionRule::Simplification => cited.len() == 1 && match *cited[0] {
+            And(ref p, ref q) => **p == *conclusion || **q == *conclusion,
+            _ => false
+        },
+        DeductionRule::Addition => cited.len() == 1 && match *conclusion {
+            Or(ref p, ref q) => **p == *cited[0] || **q == *cited[0],
+            _ => false
+        },
+        DeductionRule::Commutation =>
+            cited.len() == 1 && same_operands(cited[0], conclusion, false),
+        DeductionRule::Tautology =>
+            cited.len() == 1 && same_operands(cited[0], conclusion, true),
+        DeductionRule::DoubleNegation
+        | DeductionRule::DeMorgan
+        | DeductionRule::MaterialImplication
+        | DeductionRule::MaterialEquivalence
+        | DeductionRule::Distribution =>
+            cited.len() == 1 && replaced(rule, cited[0], conclusion),
+        DeductionRule::Premise
+        | DeductionRule::Assumption
+        | DeductionRule::IndirectProof => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use natural_deduction::*;
+    use resolution::*;
+
+    fn readme_proof() -> Proof {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(
+                and(term("p".to_string()), term("q".to_string())),
+                term("r".to_string())
+            ),
+            implies(
+                or(term("s".to_string()), term("t".to_string())),
+                term("q".to_string())
+            ),
+            term("t".to_string())
+        );
+
+        let goal = term("r".to_string());
+
+        prove(assumptions, goal).unwrap()
+    }
+
+    fn line(formula: Proposition, rule: DeductionRule, references: Vec<usize>, depth: usize) -> DeductionLine {
+        DeductionLine { formula, rule, references, depth }
+    }
+
+    #[test]
+    fn to_natural_deduction_readme_example() {
+        let deduction = readme_proof().to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&term("r".to_string())));
+    }
+
+    #[test]
+    fn to_natural_deduction_negative_pivots() {
+        let assumptions = vec!(
+            or(not(term("a".to_string())), term("b".to_string())),
+            or(term("a".to_string()), term("c".to_string())),
+            not(term("c".to_string()))
+        );
+
+        let goal = and(term("a".to_string()), term("b".to_string()));
+
+        let proof = prove(assumptions, goal.clone()).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&goal));
+    }
+
+    #[test]
+    fn to_natural_deduction_derived_clauses() {
+        let assumptions = vec!(
+            or(term("p".to_string()), term("q".to_string())),
+            or(not(term("p".to_string())), term("q".to_string())),
+            or(term("p".to_string()), not(term("q".to_string())))
+        );
+
+        let goal = and(term("p".to_string()), term("q".to_string()));
+
+        let proof = prove(assumptions, goal.clone()).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&goal));
+    }
+
+    #[test]
+    fn to_natural_deduction_contradictory_assumptions() {
+        let assumptions = vec!(
+            or(term("p".to_string()), term("q".to_string())),
+            or(not(term("p".to_string())), term("q".to_string())),
+            or(term("p".to_string()), not(term("q".to_string()))),
+            or(not(term("p".to_string())), not(term("q".to_string())))
+        );
+
+        let goal = term("r".to_string());
+
+        let proof = prove(assumptions, goal.clone()).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&goal));
+    }
+
+    #[test]
+    fn to_natural_deduction_nested_implications() {
+        let a = || term("a".to_string());
+        let d = || term("d".to_string());
+
+        let assumptions = vec!(not(implies(a(), implies(not(d()), a()))));
+        let goal = term("b".to_string());
+
+        let proof = prove(assumptions, goal.clone()).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&goal));
+    }
+
+    #[test]
+    fn to_natural_deduction_repeated_literals() {
+        let c = || term("c".to_string());
+        let d = || term("d".to_string());
+
+        let assumptions = vec!(iff(or(c(), d()), iff(d(), c())));
+        let goal = or(not(c()), d());
+
+        let proof = prove(assumptions, goal.clone()).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&goal));
+    }
+
+    #[test]
+    fn to_natural_deduction_iff() {
+        let assumptions = vec!(
+            iff(term("a".to_string()), not(term("b".to_string()))),
+            term("b".to_string())
+        );
+
+        let goal = not(term("a".to_string()));
+
+        let proof = prove(assumptions, goal.clone()).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        assert_eq!(deduction.check(), Ok(()));
+        assert_eq!(deduction.conclusion(), Some(&goal));
+    }
+
+    #[test]
+    fn to_natural_deduction_constant() {
+        let assumptions = vec!(
+            and(term("a".to_string()), top())
+        );
+
+        let goal = term("a".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        assert!(proof.to_natural_deduction().is_err());
+    }
+
+    #[test]
+    fn display_readme_example() {
+        let deduction = readme_proof().to_natural_deduction().unwrap();
+        let text = deduction.to_string();
+
+        assert!(text.starts_with(" 1) p "));
+        assert!(text.contains("\n 5) | ~(r) "));
+        assert!(text.lines().last().unwrap().ends_with("Double Negation 19"));
+    }
+
+    #[test]
+    fn check_readme_proof() {
+        let p = || term("p".to_string());
+        let q = || term("q".to_string());
+        let r = || term("r".to_string());
+        let s = || term("s".to_string());
+        let t = || term("t".to_string());
+
+        let proof = NaturalDeductionProof { lines: vec!(
+            line(p(), DeductionRule::Premise, vec!(), 0),
+            line(implies(and(p(), q()), r()), DeductionRule::Premise, vec!(), 0),
+            line(implies(or(s(), t()), q()), DeductionRule::Premise, vec!(), 0),
+            line(t(), DeductionRule::Premise, vec!(), 0),
+            line(or(s(), t()), DeductionRule::Addition, vec!(3), 0),
+            line(q(), DeductionRule::ModusPonens, vec!(2, 4), 0),
+            line(and(p(), q()), DeductionRule::Conjunction, vec!(0, 5), 0),
+            line(r(), DeductionRule::ModusPonens, vec!(1, 6), 0)
+        ) };
+
+        assert_eq!(proof.check(), Ok(()));
+    }
+
+    #[test]
+    fn check_wrong_rule() {
+        let proof = NaturalDeductionProof { lines: vec!(
+            line(term("p".to_string()), DeductionRule::Premise, vec!(), 0),
+            line(term("q".to_string()), DeductionRule::Addition, vec!(0), 0)
+        ) };
+
+        let expected = CheckError {
+            line: 2,
+            message: "Does not follow by Addition".to_string()
+        };
+
+        assert_eq!(proof.check(), Err(expected));
+    }
+
+    #[test]
+    fn check_closed_subproof_out_of_scope() {
+        let p = || term("p".to_string());
+
+        let proof = NaturalDeductionProof { lines: vec!(
+            line(p(), DeductionRule::Premise, vec!(), 0),
+            line(not(p()), DeductionRule::Assumption, vec!(), 1),
+            line(and(p(), not(p())), DeductionRule::Conjunction, vec!(0, 1), 1),
+            line(not(not(p())), DeductionRule::IndirectProof, vec!(1, 2), 0),
+            line(not(p()), DeductionRule::Simplification, vec!(2), 0)
+        ) };
+
+        let expected = CheckError {
+            line: 5,
+            message: "Line 3 is not in scope".to_string()
+        };
+
+        assert_eq!(proof.check(), Err(expected));
+    }
+
+    #[test]
+    fn check_unclosed_subproof() {
+        let proof = NaturalDeductionProof { lines: vec!(
+            line(term("p".to_string()), DeductionRule::Assumption, vec!(), 1)
+        ) };
+
+        let expected = CheckError {
+            line: 1,
+            message: "Subproof is never closed".to_string()
+        };
+
+        assert_eq!(proof.check(), Err(expected));
+    }
+
+    #[test]
+    fn check_nested_replacement() {
+        let a = || term("a".to_string());
+        let b = || term("b".to_string());
+        let c = || term("c".to_string());
+
+        let proof = NaturalDeductionProof { lines: vec!(
+            line(implies(a(), not(or(b(), c()))), DeductionRule::Premise, vec!(), 0),
+            line(implies(a(), and(not(b()), not(c()))), DeductionRule::DeMorgan, vec!(0), 0),
+            line(implies(not(not(a())), and(not(b()), not(c()))), DeductionRule::DoubleNegation, vec!(1), 0)
+        ) };
+
+        assert_eq!(proof.check(), Ok(()));
+    }
+}
@@ -0,0 +1,409 @@
+use clauses::*;
+use propositions::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+/// A proposition made up of the conjunction of possibly negated terms, which
+/// is the dual of a clause.
+///
+/// For example the proposition `p /\ ~q` would be represented as the
+/// following.
+///
+/// ```
+/// let cube = resolution_prover::Cube {
+///     parts: vec!(
+///         resolution_prover::ClausePart::Term("p".to_string()),
+///         resolution_prover::ClausePart::NegatedTerm("q".to_string())
+///     )
+/// };
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(Hash)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Cube {
+    pub parts: Vec<ClausePart>
+}
+
+impl Cube {
+    /// Converts the given proposition into cubes whose disjunction is
+    /// equivalent to it. A proposition that always holds becomes a single
+    /// empty cube, and one that never holds becomes no cubes.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("p /\\ (q \\/ ~p)").unwrap();
+    ///
+    /// let expected = vec!(Cube {
+    ///     parts: vec!(
+    ///         ClausePart::Term("p".to_string()),
+    ///         ClausePart::Term("q".to_string())
+    ///     )
+    /// });
+    ///
+    /// assert_eq!(Cube::from_proposition(prop), expected);
+    /// ```
+    pub fn from_proposition(prop: Proposition) -> Vec<Cube> {
+        let bubbled = match prop.to_nnf() {
+            Proposition::Top => return vec!(Cube { parts: vec!() }),
+            Proposition::Bottom => return vec!(),
+            p => bubble_up_ors(p),
+        };
+
+        let mut cubes: Vec<Cube> = vec!();
+        for p in split_on_ors(bubbled) {
+            let parts = deduplicated(from_and_not_prop(&p));
+            let cube = Cube { parts };
+
+            // Cubes containing both a term and its negation never hold, so
+            // they are left out
+            if !contradictory(&cube.parts) && !cubes.contains(&cube) {
+                cubes.push(cube);
+            }
+        }
+        cubes
+    }
+}
+
+impl fmt::Display for Cube {
+    /// Displays the cube as the conjunction of its parts, using `true` to
+    /// represent the empty cube.
+    ///
+    /// ```
+    /// let cube = resolution_prover::Cube {
+    ///     parts: vec!(
+    ///         resolution_prover::ClausePart::NegatedTerm("p".to_string()),
+    ///         resolution_prover::ClausePart::Term("q".to_string())
+    ///     )
+    /// };
+    ///
+    /// assert_eq!(cube.to_string(), "~p /\\ q");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.parts.is_empty() {
+            return write!(f, "true")
+        }
+
+        let parts: Vec<String> = self.parts.iter()
+            .map(|p| p.to_string())
+            .collect();
+
+        write!(f, "{}", parts.join(" /\\ "))
+    }
+}
+
+impl Proposition {
+    /// Returns an equivalent proposition in negation normal form, which only
+    /// uses conjunction, disjunction, and negations of terms. Constants are
+    /// removed unless the whole proposition is a constant.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("~(p -> (q /\\ true))").unwrap();
+    ///
+    /// assert_eq!(prop.to_nnf().to_string(), "p /\\ ~(q)");
+    /// ```
+    pub fn to_nnf(&self) -> Proposition {
+        let no_implication = Clause::eliminate_implication(self.clone());
+        let red_negations = Clause::reduce_negation(no_implication);
+        Clause::eliminate_constants(red_negations)
+    }
+
+    /// Returns an equivalent proposition in conjunctive normal form, which is
+    /// a conjunction of disjunctions of possibly negated terms.
+    ///
+    /// Unlike `Clause::from_proposition`, no new terms are introduced, so the
+    /// result can be much larger than the original proposition.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(p /\\ q) \\/ r").unwrap();
+    ///
+    /// assert_eq!(prop.to_cnf(), parse_proposition("(p \\/ r) /\\ (q \\/ r)").unwrap());
+    /// ```
+    pub fn to_cnf(&self) -> Proposition {
+        let bubbled = match self.to_nnf() {
+            p @ Proposition::Top | p @ Proposition::Bottom => return p,
+            p => Clause::bubble_up_ands(p),
+        };
+
+        let mut clauses: Vec<Vec<ClausePart>> = vec!();
+        for p in Clause::split_on_ands(bubbled) {
+            let parts = deduplicated(Clause::from_or_not_prop(&p));
+
+            // Clauses containing both a term and its negation always hold,
+            // so they are left out
+            if !contradictory(&parts) && !clauses.contains(&parts) {
+                clauses.push(parts);
+            }
+        }
+
+        and_all(clauses.into_iter()
+            .map(|c| or_all(c.iter().map(literal).collect()))
+            .collect())
+    }
+
+    /// Returns an equivalent proposition in disjunctive normal form, which is
+    /// a disjunction of conjunctions of possibly negated terms.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("p /\\ (q \\/ r)").unwrap();
+    ///
+    /// assert_eq!(prop.to_dnf(), parse_proposition("(p /\\ q) \\/ (p /\\ r)").unwrap());
+    /// ```
+    pub fn to_dnf(&self) -> Proposition {
+        or_all(Cube::from_proposition(self.clone()).into_iter()
+            .map(|c| and_all(c.parts.iter().map(literal).collect()))
+            .collect())
+    }
+}
+
+/// Bubbles up the disjunctions in the given proposition, which must be in
+/// negation normal form, so that it becomes the disjunction of terms and
+/// conjunctions.
+fn bubble_up_ors(prop: Proposition) -> Proposition {
+    match prop {
+        Proposition::And(a, b) => distribute(bubble_up_ors(*a), bubble_up_ors(*b)),
+        Proposition::Or(a, b) => or(bubble_up_ors(*a), bubble_up_ors(*b)),
+        p => p
+    }
+}
+
+/// Distributes the conjunction of the two given propositions over their
+/// disjunctions, assuming that their disjunctions are already bubbled up.
+fn distribute(a: Proposition, b: Proposition) -> Proposition {
+    match (a, b) {
+        (Proposition::Or(c, d), e) => or(
+            distribute(*c, e.clone()),
+            distribute(*d, e)
+        ),
+        (c, Proposition::Or(d, e)) => or(
+            distribute(c.clone(), *d),
+            distribute(c, *e)
+        ),
+        (c, d) => and(c, d)
+    }
+}
+
+/// Splits the given proposition on its disjunctions.
+fn split_on_ors(prop: Proposition) -> Vec<Proposition> {
+    match prop {
+        Proposition::Or(a, b) => {
+            let mut parts = split_on_ors(*a);
+            parts.append(&mut split_on_ors(*b));
+            parts
+        },
+        p => vec!(p)
+    }
+}
+
+/// Converts the given proposition, which must only contain conjunctions,
+/// negated terms and terms, into the parts of a cube.
+fn from_and_not_prop(prop: &Proposition) -> Vec<ClausePart> {
+    match *prop {
+        Proposition::And(ref a, ref b) => {
+            let mut parts = from_and_not_prop(a);
+            parts.append(&mut from_and_not_prop(b));
+            parts
+        },
+        Proposition::Not(ref inner) => match **inner {
+            Proposition::Term(ref a) => vec!(ClausePart::NegatedTerm(a.clone())),
+            _ => panic!("Proposition contained non-(and, not) term: {}", prop)
+        },
+        Proposition::Term(ref a) => vec!(ClausePart::Term(a.clone())),
+        _ => panic!("Proposition contained non-(and, not) term: {}", prop)
+    }
+}
+
+/// Removes the repeated parts from the given parts, keeping their order.
+fn deduplicated(parts: Vec<ClausePart>) -> Vec<ClausePart> {
+    let mut unique = vec!();
+    for p in parts {
+        if !unique.contains(&p) {
+            unique.push(p);
+        }
+    }
+    unique
+}
+
+/// Checks whether the given parts contain both a term and its negation.
+fn contradictory(parts: &[ClausePart]) -> bool {
+    parts.iter().any(|p| parts.contains(&p.negate()))
+}
+
+/// Converts the given clause part into the equivalent proposition.
+pub(crate) fn literal(part: &ClausePart) -> Proposition {
+    match *part {
+        ClausePart::Term(ref a) => term(a.clone()),
+        ClausePart::NegatedTerm(ref a) => not(term(a.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use normal_forms::*;
+    use random::*;
+
+    use std::collections::HashMap;
+
+    /// Generates pseudorandom propositions from a fixed seed, so that the
+    /// tests are repeatable.
+    struct Generator {
+        random: Random
+    }
+
+    impl Generator {
+        fn proposition(&mut self, depth: usize) -> Proposition {
+            if depth == 0 {
+                return match self.random.next(10) {
+                    0 => top(),
+                    1 => bottom(),
+                    n => term(["p", "q", "r", "s"][(n % 4) as usize].to_string()),
+                }
+            }
+
+            let choice = self.random.next(10);
+            let mut operands: Vec<Proposition> = (0..3)
+                .map(|_| self.proposition(depth - 1))
+                .collect();
+            let (c, b, a) = (
+                operands.pop().unwrap(),
+                operands.pop().unwrap(),
+                operands.pop().unwrap()
+            );
+
+            match choice {
+                0 => or(a, b),
+                1 => and(a, b),
+                2 => implies(a, b),
+                3 => iff(a, b),
+                4 => xor(a, b),
+                5 => nand(a, b),
+                6 => nor(a, b),
+                7 => ite(a, b, c),
+                8 => not(a),
+                _ => a,
+            }
+        }
+    }
+
+    fn equivalent(a: &Proposition, b: &Proposition) -> bool {
+        let terms = ["p", "q", "r", "s"];
+        (0..16).all(|bits| {
+            let assignment: HashMap<String, bool> = terms.iter()
+                .enumerate()
+                .map(|(i, t)| (t.to_string(), bits & (1 << i) != 0))
+                .collect();
+            a.evaluate(&assignment) == b.evaluate(&assignment)
+        })
+    }
+
+    fn is_literal(prop: &Proposition) -> bool {
+        match *prop {
+            Proposition::Term(_) => true,
+            Proposition::Not(ref a) => matches!(**a, Proposition::Term(_)),
+            _ => false
+        }
+    }
+
+    fn is_nnf(prop: &Proposition) -> bool {
+        match *prop {
+            Proposition::And(ref a, ref b) | Proposition::Or(ref a, ref b) =>
+                is_nnf(a) && is_nnf(b),
+            ref p => is_literal(p)
+        }
+    }
+
+    /// Checks that the proposition is a tree of the outer operation whose
+    /// leaves are trees of the inner operation over literals.
+    fn is_normal(prop: &Proposition, disjunction: bool) -> bool {
+        fn only(prop: &Proposition, disjunction: bool, leaf: &dyn Fn(&Proposition) -> bool) -> bool {
+            match (prop, disjunction) {
+                (Proposition::Or(a, b), true) | (Proposition::And(a, b), false) =>
+                    only(a, disjunction, leaf) && only(b, disjunction, leaf),
+                (p, _) => leaf(p)
+            }
+        }
+
+        let inner = |p: &Proposition| only(p, !disjunction, &is_literal);
+        match *prop {
+            Proposition::Top | Proposition::Bottom => true,
+            ref p => only(p, disjunction, &inner)
+        }
+    }
+
+    #[test]
+    fn normal_forms_equivalent() {
+        let mut generator = Generator { random: Random::new(35) };
+
+        for _ in 0..500 {
+            let prop = generator.proposition(3);
+
+            let nnf = prop.to_nnf();
+            let cnf = prop.to_cnf();
+            let dnf = prop.to_dnf();
+
+            assert!(equivalent(&prop, &nnf), "{} and {}", prop, nnf);
+            assert!(equivalent(&prop, &cnf), "{} and {}", prop, cnf);
+            assert!(equivalent(&prop, &dnf), "{} and {}", prop, dnf);
+
+            let constant = |p: &Proposition| matches!(*p, Proposition::Top | Proposition::Bottom);
+            assert!(constant(&nnf) || is_nnf(&nnf), "{}", nnf);
+            assert!(is_normal(&cnf, false), "{}", cnf);
+            assert!(is_normal(&dnf, true), "{}", dnf);
+        }
+    }
+
+    #[test]
+    fn to_cnf_removes_tautologies() {
+        let prop = or(
+            and(term("p".to_string()), not(term("q".to_string()))),
+            term("q".to_string())
+        );
+
+        assert_eq!(prop.to_cnf().to_string(), "p \\/ q");
+    }
+
+    #[test]
+    fn to_cnf_constants() {
+        let valid = or(term("p".to_string()), not(term("p".to_string())));
+        let invalid = and(term("p".to_string()), bottom());
+
+        assert_eq!(valid.to_cnf(), top());
+        assert_eq!(invalid.to_cnf(), bottom());
+    }
+
+    #[test]
+    fn to_dnf_constants() {
+        let valid = or(term("p".to_string()), top());
+        let invalid = and(term("p".to_string()), not(term("p".to_string())));
+
+        assert_eq!(valid.to_dnf(), top());
+        assert_eq!(invalid.to_dnf(), bottom());
+    }
+
+    #[test]
+    fn to_dnf_xor() {
+        let prop = xor(term("p".to_string()), term("q".to_string()));
+
+        assert_eq!(prop.to_dnf().to_string(), "p /\\ ~(q) \\/ q /\\ ~(p)");
+    }
+
+    #[test]
+    fn cube_from_proposition_constants() {
+        assert_eq!(Cube::from_proposition(top()), vec!(Cube { parts: vec!() }));
+        assert_eq!(Cube::from_proposition(bottom()), vec!());
+    }
+}
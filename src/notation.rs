@@ -0,0 +1,457 @@
+use clauses::*;
+use natural_deduction::*;
+use proofs::*;
+use propositions::*;
+
+/// The symbols used to write propositions in a particular notation.
+struct Notation {
+    not: &'static str,
+    and: &'static str,
+    or: &'static str,
+    implies: &'static str,
+    iff: &'static str,
+    xor: &'static str,
+    nand: &'static str,
+    nor: &'static str,
+    top: &'static str,
+    bottom: &'static str,
+    keywords: [&'static str; 3],
+    comparisons: [&'static str; 3],
+    empty_clause: &'static str,
+    name: fn(&str) -> String
+}
+
+const UNICODE: Notation = Notation {
+    not: "¬",
+    and: " ∧ ",
+    or: " ∨ ",
+    implies: " → ",
+    iff: " ↔ ",
+    xor: " ⊕ ",
+    nand: " ↑ ",
+    nor: " ↓ ",
+    top: "⊤",
+    bottom: "⊥",
+    keywords: ["if ", " then ", " else "],
+    comparisons: [" ≤ ", " ≥ ", " = "],
+    empty_clause: "□",
+    name: unicode_name
+};
+
+const LATEX: Notation = Notation {
+    not: "\\neg ",
+    and: " \\land ",
+    or: " \\lor ",
+    implies: " \\to ",
+    iff: " \\leftrightarrow ",
+    xor: " \\oplus ",
+    nand: " \\uparrow ",
+    nor: " \\downarrow ",
+    top: "\\top",
+    bottom: "\\bot",
+    keywords: ["\\mathrm{if}\\ ", "\\ \\mathrm{then}\\ ", "\\ \\mathrm{else}\\ "],
+    comparisons: [" \\leq ", " \\geq ", " = "],
+    empty_clause: "\\Box",
+    name: latex_name
+};
+
+impl Proposition {
+    /// Writes the proposition using the Unicode symbols of logic, such as
+    /// `¬`, `∧`, `∨`, `→`, `↔`, `⊕`, `↑`, `↓`, `⊤` and `⊥`.
+    ///
+    /// Unlike `Display`, parentheses are only added where the precedence of
+    /// the operators needs them, using the same precedence as
+    /// `parse_proposition`, which accepts the result.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(~(p) /\\ q) -> (r \\/ false)").unwrap();
+    ///
+    /// assert_eq!(prop.to_unicode(), "¬p ∧ q → r ∨ ⊥");
+    /// assert_eq!(parse_proposition(&prop.to_unicode()), Ok(prop));
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        write_proposition(self, &UNICODE)
+    }
+
+    /// Writes the proposition as a LaTeX math mode formula, such as
+    /// `\neg p \land q \to r`.
+    ///
+    /// Names longer than a single character are written in italics with
+    /// `\mathit`, and parentheses are added in the same places as for
+    /// `to_unicode`.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("~(p /\\ q) <-> (raining_now <+> true)").unwrap();
+    ///
+    /// assert_eq!(
+    ///     prop.to_latex(),
+    ///     "\\neg (p \\land q) \\leftrightarrow (\\mathit{raining\\_now} \\oplus \\top)"
+    /// );
+    /// ```
+    pub fn to_latex(&self) -> String {
+        write_proposition(self, &LATEX)
+    }
+}
+
+impl Clause {
+    /// Writes the clause as a disjunction using Unicode symbols, with `□`
+    /// for the empty clause.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let clauses = Clause::from_proposition(parse_proposition("p -> q").unwrap());
+    ///
+    /// assert_eq!(clauses[0].to_unicode(), "¬p ∨ q");
+    /// assert_eq!(Clause { parts: vec!() }.to_unicode(), "□");
+    /// ```
+    pub fn to_unicode(&self) -> String {
+        write_clause(self, &UNICODE)
+    }
+
+    /// Writes the clause as a LaTeX math mode disjunction, with `\Box` for
+    /// the empty clause.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let clauses = Clause::from_proposition(parse_proposition("p -> q").unwrap());
+    ///
+    /// assert_eq!(clauses[0].to_latex(), "\\neg p \\lor q");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        write_clause(self, &LATEX)
+    }
+}
+
+/// Writes the given clauses as a LaTeX math mode set of disjunctions.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses = Clause::from_proposition(
+///     parse_proposition("(p -> q) /\\ p").unwrap()
+/// );
+///
+/// assert_eq!(clauses_to_latex(&clauses), "\\{\\neg p \\lor q,\\ p\\}");
+/// ```
+pub fn clauses_to_latex(clauses: &[Clause]) -> String {
+    let clauses: Vec<String> = clauses.iter()
+        .map(|c| c.to_latex())
+        .collect();
+
+    format!("\\{{{}\\}}", clauses.join(",\\ "))
+}
+
+impl Proof {
+    /// Writes the proof as a LaTeX table, with a numbered row for each step
+    /// giving its clause and the reason that it holds.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let assumptions = vec!(parse_proposition("p -> q").unwrap());
+    /// let goal = parse_proposition("~q -> ~p").unwrap();
+    ///
+    /// let proof = prove(assumptions, goal).unwrap();
+    ///
+    /// let expected = "\
+    /// \\begin{tabular}{rll}
+    /// 1 & $\\neg q$ & Negated goal \\\\
+    /// 2 & $\\neg p \\lor q$ & Assumption 1 \\\\
+    /// 3 & $\\neg p$ & Resolution 1, 2 \\\\
+    /// 4 & $p$ & Negated goal \\\\
+    /// 5 & $\\Box$ & Resolution 3, 4 \\\\
+    /// \\end{tabular}
+    /// ";
+    ///
+    /// assert_eq!(proof.to_latex(), expected);
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let mut lines = vec!("\\begin{tabular}{rll}".to_string());
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let justification = match step.justification {
+                Justification::Assumption(a) => format!("Assumption {}", a + 1),
+                Justification::NegatedGoal => "Negated goal".to_string(),
+                Justification::Resolution { left, right, .. } =>
+                    format!("Resolution {}, {}", left + 1, right + 1),
+            };
+
+            lines.push(format!(
+                "{} & ${}$ & {} \\\\",
+                i + 1,
+                step.clause.to_latex(),
+                justification
+            ));
+        }
+
+        lines.push("\\end{tabular}".to_string());
+        lines.push("".to_string());
+        lines.join("\n")
+    }
+}
+
+impl NaturalDeductionProof {
+    /// Writes the proof as a LaTeX table in the style of its `Display`
+    /// output, with a rule below the premises and a bar before each line
+    /// for every subproof that it is nested within.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let proof = NaturalDeductionProof {
+    ///     lines: vec!(
+    ///         DeductionLine {
+    ///             formula: parse_proposition("p").unwrap(),
+    ///             rule: DeductionRule::Premise,
+    ///             references: vec!(),
+    ///             depth: 0
+    ///         },
+    ///         DeductionLine {
+    ///             formula: parse_proposition("p \\/ q").unwrap(),
+    ///             rule: DeductionRule::Addition,
+    ///             references: vec!(0),
+    ///             depth: 0
+    ///         }
+    ///     )
+    /// };
+    ///
+    /// let expected = "\
+    /// \\begin{tabular}{rll}
+    /// 1 & $p$ & Premise \\\\
+    /// \\hline
+    /// 2 & $p \\lor q$ & Addition 1 \\\\
+    /// \\end{tabular}
+    /// ";
+    ///
+    /// assert_eq!(proof.to_latex(), expected);
+    /// ```
+    pub fn to_latex(&self) -> String {
+        let mut lines = vec!("\\begin{tabular}{rll}".to_string());
+
+        for (i, line) in self.lines.iter().enumerate() {
+            let is_first_non_premise = line.rule != DeductionRule::Premise
+                && i > 0
+                && self.lines[i - 1].rule == DeductionRule::Premise;
+            if is_first_non_premise {
+                lines.push("\\hline".to_string());
+            }
+
+            let references = match line.rule {
+                DeductionRule::IndirectProof if line.references.len() == 2 =>
+                    format!("{}--{}", line.references[0] + 1, line.references[1] + 1),
+                _ => line.references.iter()
+                    .map(|r| (r + 1).to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            };
+            let justification = format!("{} {}", line.rule, references);
+
+            lines.push(format!(
+                "{} & ${}{}$ & {} \\\\",
+                i + 1,
+                "\\mid\\ ".repeat(line.depth),
+                line.formula.to_latex(),
+                justification.trim_end()
+            ));
+        }
+
+        lines.push("\\end{tabular}".to_string());
+        lines.push("".to_string());
+        lines.join("\n")
+    }
+}
+
+/// Returns how tightly the top level operation of the given proposition
+/// binds its operands, matching the precedence used by the parser.
+fn precedence(prop: &Proposition) -> usize {
+    match *prop {
+        Proposition::Ite(..) => 0,
+        Proposition::Iff(..) | Proposition::Xor(..) => 1,
+        Proposition::Implies(..) => 2,
+        Proposition::Or(..) | Proposition::Nor(..) => 3,
+        Proposition::And(..) | Proposition::Nand(..) => 4,
+        Proposition::Linear(..)
+        | Proposition::Not(_)
+        | Proposition::Term(_)
+        | Proposition::Top
+        | Proposition::Bottom => 5,
+    }
+}
+
+fn write_proposition(prop: &Proposition, notation: &Notation) -> String {
+    let write = |p: &Proposition| write_proposition(p, notation);
+
+    // Writes an operand, surrounded by parentheses unless it binds more
+    // tightly than the operation it belongs to
+    let operand = |p: &Proposition, bare: bool| if bare {
+        write(p)
+    } else {
+        format!("({})", write(p))
+    };
+
+    // Binary operations other than implication group to the left, so a
+    // left operand of the same precedence needs no parentheses
+    let left_grouped = |a: &Proposition, op: &str, b: &Proposition| {
+        let level = precedence(prop);
+        format!(
+            "{}{}{}",
+            operand(a, precedence(a) >= level),
+            op,
+            operand(b, precedence(b) > level)
+        )
+    };
+
+    match *prop {
+        Proposition::Or(ref a, ref b) => left_grouped(a, notation.or, b),
+        Proposition::And(ref a, ref b) => left_grouped(a, notation.and, b),
+        Proposition::Iff(ref a, ref b) => left_grouped(a, notation.iff, b),
+        Proposition::Xor(ref a, ref b) => left_grouped(a, notation.xor, b),
+        Proposition::Nand(ref a, ref b) => left_grouped(a, notation.nand, b),
+        Proposition::Nor(ref a, ref b) => left_grouped(a, notation.nor, b),
+        Proposition::Implies(ref a, ref b) => format!(
+            "{}{}{}",
+            operand(a, precedence(a) > 2),
+            notation.implies,
+            operand(b, precedence(b) >= 2)
+        ),
+        Proposition::Ite(ref c, ref t, ref e) => format!(
+            "{}{}{}{}{}{}",
+            notation.keywords[0],
+            operand(c, precedence(c) > 0),
+            notation.keywords[1],
+            operand(t, precedence(t) > 0),
+            notation.keywords[2],
+            write(e)
+        ),
+        Proposition::Linear(ref terms, comparison, bound) => {
+            let mut sum = String::new();
+            for (i, &(c, ref p)) in terms.iter().enumerate() {
+                sum.push_str(match (i, c < 0) {
+                    (0, false) => "",
+                    (0, true) => "-",
+                    (_, false) => " + ",
+                    (_, true) => " - ",
+                });
+                if c.unsigned_abs() != 1 {
+                    sum.push_str(&format!("{} ", c.unsigned_abs()));
+                }
+                sum.push_str(&operand(p, precedence(p) == 5));
+            }
+            if terms.is_empty() {
+                sum.push('0');
+            }
+
+            let symbol = match comparison {
+                Comparison::AtMost => notation.comparisons[0],
+                Comparison::AtLeast => notation.comparisons[1],
+                Comparison::Exactly => notation.comparisons[2],
+            };
+            format!("[{}{}{}]", sum, symbol, bound)
+        },
+        Proposition::Not(ref a) =>
+            format!("{}{}", notation.not, operand(a, precedence(a) == 5)),
+        Proposition::Term(ref a) => (notation.name)(a),
+        Proposition::Top => notation.top.to_string(),
+        Proposition::Bottom => notation.bottom.to_string(),
+    }
+}
+
+fn write_clause(clause: &Clause, notation: &Notation) -> String {
+    if clause.parts.is_empty() {
+        return notation.empty_clause.to_string()
+    }
+
+    let parts: Vec<String> = clause.parts.iter()
+        .map(|p| match *p {
+            ClausePart::Term(ref a) => (notation.name)(a),
+            ClausePart::NegatedTerm(ref a) => format!("{}{}", notation.not, (notation.name)(a)),
+        })
+        .collect();
+
+    parts.join(notation.or)
+}
+
+fn unicode_name(name: &str) -> String {
+    name.to_string()
+}
+
+/// Writes the given name for LaTeX math mode, escaping special characters
+/// and using `\mathit` for names longer than one character so that they
+/// are not spaced as a product of variables.
+fn latex_name(name: &str) -> String {
+    let escaped: String = name.chars()
+        .map(|c| match c {
+            '\\' => "\\backslash{}".to_string(),
+            '~' => "\\sim{}".to_string(),
+            '^' => "\\hat{}".to_string(),
+            '{' | '}' | '_' | '&' | '%' | '$' | '#' => format!("\\{}", c),
+            _ => c.to_string()
+        })
+        .collect();
+
+    if name.chars().count() == 1 {
+        escaped
+    } else {
+        format!("\\mathit{{{}}}", escaped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use notation::*;
+    use parser::*;
+
+    #[test]
+    fn to_unicode_parentheses() {
+        let cases = vec!(
+            ("(p /\\ q) /\\ (r /\\ s)", "p ∧ q ∧ (r ∧ s)"),
+            ("p -> (q -> r)", "p → q → r"),
+            ("(p -> q) -> r", "(p → q) → r"),
+            ("~(~(p)) \\/ ~(q \\/ r)", "¬¬p ∨ ¬(q ∨ r)"),
+            ("(p <-> q) <+> (r ~/\\ (s ~\\/ t))", "p ↔ q ⊕ r ↑ (s ↓ t)"),
+            ("(if a then b else c) /\\ if (if d then e else f) then g else if h then i else j",
+                "(if a then b else c) ∧ (if (if d then e else f) then g else if h then i else j)"),
+            ("true -> false", "⊤ → ⊥"),
+            ("[2 p - (q /\\ r) + ~(s) >= 1] -> [p + q = 1]", "[2 p - (q ∧ r) + ¬s ≥ 1] → [p + q = 1]"),
+        );
+
+        for (input, expected) in cases {
+            let prop = parse_proposition(input).unwrap();
+
+            assert_eq!(prop.to_unicode(), expected);
+            assert_eq!(parse_proposition(&prop.to_unicode()), Ok(prop));
+        }
+    }
+
+    #[test]
+    fn to_latex_names() {
+        let prop = parse_proposition("x_1 /\\ ~(y)").unwrap();
+
+        assert_eq!(prop.to_latex(), "\\mathit{x\\_1} \\land \\neg y");
+
+        let linear = parse_proposition("[-x + 2 y <= 1]").unwrap();
+
+        assert_eq!(linear.to_latex(), "[-x + 2 y \\leq 1]");
+
+        let clause = Clause {
+            parts: vec!(
+                ClausePart::NegatedTerm("{a <+> b}".to_string()),
+                ClausePart::Term("c".to_string())
+            )
+        };
+
+        assert_eq!(clause.to_latex(), "\\neg \\mathit{\\{a <+> b\\}} \\lor c");
+    }
+
+    #[test]
+    fn clauses_to_latex_empty() {
+        assert_eq!(clauses_to_latex(&[]), "\\{\\}");
+        assert_eq!(clauses_to_latex(&[Clause { parts: vec!() }]), "\\{\\Box\\}");
+    }
+}
@@ -0,0 +1,709 @@
+use propositions::*;
+
+use std::error::Error;
+use std::fmt;
+
+/// A set of assumptions along with a goal to prove from them.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Problem {
+    pub assumptions: Vec<Proposition>,
+    pub goal: Proposition
+}
+
+/// An error encountered while parsing, along with the line and column (both
+/// starting at 1) where it occurred.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses a proposition written using the same syntax that propositions are
+/// displayed with.
+///
+/// From tightest to loosest binding, the operators are `~`, `/\` and `~/\`,
+/// `\/` and `~\/`, `->`, and `<->` and `<+>`. Implications group to the
+/// right, and the other binary operators group to the left. The words `true`
+/// and `false` stand for the constants rather than for terms, and a
+/// conditional choice is written as `if c then p else q`, where the branch
+/// after `else` extends as far to the right as possible.
+///
+/// A linear constraint is written in brackets, as a sum of operands that
+/// may be preceded by a coefficient, compared against a bound with `<=`,
+/// `>=` or `=`, such as `[2 p - q + (r /\ s) <= 1]`.
+///
+/// The Unicode symbols written by `Proposition::to_unicode` are accepted as
+/// well, with `¬`, `∧`, `∨`, `→` or `⇒`, `↔` or `⇔`, `⊕`, `↑` and `↓` for the
+/// operators, `⊤` and `⊥` for the constants, and `≤` and `≥` for the
+/// comparisons.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let prop = parse_proposition("p /\\ q -> r").unwrap();
+///
+/// let expected = implies(
+///     and(term("p".to_string()), term("q".to_string())),
+///     term("r".to_string())
+/// );
+///
+/// assert_eq!(prop, expected);
+/// ```
+pub fn parse_proposition(input: &str) -> Result<Proposition, ParseError> {
+    let mut parser = Parser::new(input, 1)?;
+
+    let prop = parser.parse_iff()?;
+    parser.expect_end()?;
+
+    Ok(prop)
+}
+
+/// Parses a problem consisting of assumptions and a goal.
+///
+/// Each assumption is written on its own line, followed by a separator line
+/// made up of three or more dashes, followed by the goal. Blank lines and
+/// lines starting with `#` are ignored.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let problem = parse_problem("
+///     p
+///     p -> q
+///     ------
+///     q
+/// ").unwrap();
+///
+/// assert_eq!(problem.assumptions.len(), 2);
+/// assert_eq!(problem.goal, term("q".to_string()));
+/// ```
+pub fn parse_problem(input: &str) -> Result<Problem, ParseError> {
+    let mut assumptions = vec!();
+    let mut goals = vec!();
+    let mut separator_line = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue
+        }
+
+        if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-') {
+            if separator_line.is_some() {
+                return Err(ParseError {
+                    message: "Found more than one separator line".to_string(),
+                    line: line_number,
+                    column: 1
+                })
+            }
+            separator_line = Some(line_number);
+            continue
+        }
+
+        let mut parser = Parser::new(line, line_number)?;
+        let prop = parser.parse_iff()?;
+        parser.expect_end()?;
+
+        if separator_line.is_some() {
+            goals.push((prop, line_number));
+        } else {
+            assumptions.push(prop);
+        }
+    }
+
+    let last_line = input.lines().count().max(1);
+    match separator_line {
+        None => Err(ParseError {
+            message: "Expected a separator line before the goal".to_string(),
+            line: last_line,
+            column: 1
+        }),
+        Some(_) if goals.len() > 1 => Err(ParseError {
+            message: "Expected exactly one goal".to_string(),
+            line: goals[1].1,
+            column: 1
+        }),
+        Some(l) => match goals.pop() {
+            Some((goal, _)) => Ok(Problem { assumptions, goal }),
+            None => Err(ParseError {
+                message: "Expected a goal after the separator line".to_string(),
+                line: l,
+                column: 1
+            })
+        }
+    }
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum Token {
+    Term(String),
+    Not,
+    And,
+    Or,
+    Implies,
+    Iff,
+    Xor,
+    Nand,
+    Nor,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Plus,
+    Minus,
+    Compare(Comparison)
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Term(ref a) => write!(f, "term \"{}\"", a),
+            Token::Not => write!(f, "\"~\""),
+            Token::And => write!(f, "\"/\\\""),
+            Token::Or => write!(f, "\"\\/\""),
+            Token::Implies => write!(f, "\"->\""),
+            Token::Iff => write!(f, "\"<->\""),
+            Token::Xor => write!(f, "\"<+>\""),
+            Token::Nand => write!(f, "\"~/\\\""),
+            Token::Nor => write!(f, "\"~\\/\""),
+            Token::LeftParen => write!(f, "\"(\""),
+            Token::RightParen => write!(f, "\")\""),
+            Token::LeftBracket => write!(f, "\"[\""),
+            Token::RightBracket => write!(f, "\"]\""),
+            Token::Plus => write!(f, "\"+\""),
+            Token::Minus => write!(f, "\"-\""),
+            Token::Compare(c) => write!(f, "\"{}\"", c),
+        }
+    }
+}
+
+/// Recursive descent parser over the tokens of a single line of input.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    line: usize,
+    end_column: usize
+}
+
+impl Parser {
+    fn new(input: &str, line: usize) -> Result<Parser, ParseError> {
+        Ok(Parser {
+            tokens: tokenize(input, line)?,
+            position: 0,
+            line,
+            end_column: input.chars().count() + 1
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(t, _)| t)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens.get(self.position)
+            .map(|&(_, c)| c)
+            .unwrap_or(self.end_column)
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            message,
+            line: self.line,
+            column: self.column()
+        }
+    }
+
+    fn accept(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(t) => Err(self.error(format!("Unexpected {}", t)))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.accept(&Token::Term(keyword.to_string())) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected \"{}\"", keyword)))
+        }
+    }
+
+    fn parse_iff(&mut self) -> Result<Proposition, ParseError> {
+        let mut prop = self.parse_implies()?;
+        loop {
+            if self.accept(&Token::Iff) {
+                prop = iff(prop, self.parse_implies()?);
+            } else if self.accept(&Token::Xor) {
+                prop = xor(prop, self.parse_implies()?);
+            } else {
+                return Ok(prop)
+            }
+        }
+    }
+
+    fn parse_implies(&mut self) -> Result<Proposition, ParseError> {
+        let prop = self.parse_or()?;
+        if self.accept(&Token::Implies) {
+            Ok(implies(prop, self.parse_implies()?))
+        } else {
+            Ok(prop)
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Proposition, ParseError> {
+        let mut prop = self.parse_and()?;
+        loop {
+            if self.accept(&Token::Or) {
+                prop = or(prop, self.parse_and()?);
+            } else if self.accept(&Token::Nor) {
+                prop = nor(prop, self.parse_and()?);
+            } else {
+                return Ok(prop)
+            }
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<Proposition, ParseError> {
+        let mut prop = self.parse_unary()?;
+        loop {
+            if self.accept(&Token::And) {
+                prop = and(prop, self.parse_unary()?);
+            } else if self.accept(&Token::Nand) {
+                prop = nand(prop, self.parse_unary()?);
+            } else {
+                return Ok(prop)
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Proposition, ParseError> {
+        let token = match self.peek() {
+            Some(t) => t.clone(),
+            None => return Err(self.error("Unexpected end of input".to_string()))
+        };
+
+        match token {
+            Token::Not => {
+                self.position += 1;
+                Ok(not(self.parse_unary()?))
+            },
+            Token::LeftParen => {
+                self.position += 1;
+                let prop = self.parse_iff()?;
+                if self.accept(&Token::RightParen) {
+                    Ok(prop)
+                } else {
+                    Err(self.error("Expected \")\"".to_string()))
+                }
+            },
+            Token::LeftBracket => {
+                self.position += 1;
+                self.parse_linear()
+            },
+            Token::Term(ref a) if a == "if" => {
+                self.position += 1;
+                let condition = self.parse_iff()?;
+                self.expect_keyword("then")?;
+                let then = self.parse_iff()?;
+                self.expect_keyword("else")?;
+                let otherwise = self.parse_iff()?;
+                Ok(ite(condition, then, otherwise))
+            },
+            Token::Term(a) => {
+                self.position += 1;
+                Ok(match a.as_str() {
+                    "true" => top(),
+                    "false" => bottom(),
+                    _ => term(a)
+                })
+            },
+            t => Err(self.error(format!("Unexpected {}", t)))
+        }
+    }
+
+    /// Parses the rest of a linear constraint after its opening bracket.
+    fn parse_linear(&mut self) -> Result<Proposition, ParseError> {
+        let mut terms = vec!();
+        let mut negative = self.accept(&Token::Minus);
+        loop {
+            // A number is a coefficient when an operand follows it, and is
+            // otherwise a term's name
+            let is_coefficient = match (self.peek(), self.tokens.get(self.position + 1)) {
+                (Some(Token::Term(a)), Some((next, _))) => is_number(a) && matches!(
+                    *next,
+                    Token::Term(_) | Token::Not | Token::LeftParen | Token::LeftBracket
+                ),
+                _ => false
+            };
+            let coefficient = if is_coefficient { self.parse_number()? } else { 1 };
+
+            let operand = self.parse_unary()?;
+            terms.push((if negative { -coefficient } else { coefficient }, operand));
+
+            if self.accept(&Token::Plus) {
+                negative = false;
+            } else if self.accept(&Token::Minus) {
+                negative = true;
+            } else {
+                break
+            }
+        }
+
+        let comparison = match self.peek() {
+            Some(&Token::Compare(c)) => c,
+            _ => return Err(self.error("Expected \"<=\", \">=\" or \"=\"".to_string()))
+        };
+        self.position += 1;
+
+        let bound = if self.accept(&Token::Minus) {
+            -self.parse_number()?
+        } else {
+            self.parse_number()?
+        };
+
+        if self.accept(&Token::RightBracket) {
+            Ok(pseudo_boolean(terms, comparison, bound))
+        } else {
+            Err(self.error("Expected \"]\"".to_string()))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<i64, ParseError> {
+        let number = match self.peek() {
+            Some(Token::Term(a)) if is_number(a) => a.parse::<i64>().ok(),
+            _ => return Err(self.error("Expected a number".to_string()))
+        };
+
+        match number {
+            Some(n) => {
+                self.position += 1;
+                Ok(n)
+            },
+            None => Err(self.error("Expected a number that fits in 64 bits".to_string()))
+        }
+    }
+}
+
+fn is_number(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Splits the given line into tokens, paired with the column that each token
+/// starts at.
+fn tokenize(input: &str, line: usize) -> Result<Vec<(Token, usize)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec!();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        let rest: String = chars[i..].iter().take(3).collect();
+
+        let (token, length) = if chars[i].is_whitespace() {
+            i += 1;
+            continue
+        } else if chars[i].is_alphanumeric() || chars[i] == '_' {
+            let name: String = chars[i..].iter()
+                .take_while(|c| c.is_alphanumeric() || **c == '_')
+                .collect();
+            let length = name.chars().count();
+            (Token::Term(name), length)
+        } else if rest.starts_with("<->") {
+            (Token::Iff, 3)
+        } else if rest.starts_with("<+>") {
+            (Token::Xor, 3)
+        } else if rest.starts_with("~/\\") {
+            (Token::Nand, 3)
+        } else if rest.starts_with("~\\/") {
+            (Token::Nor, 3)
+        } else if rest.starts_with("<=") {
+            (Token::Compare(Comparison::AtMost), 2)
+        } else if rest.starts_with(">=") {
+            (Token::Compare(Comparison::AtLeast), 2)
+        } else if rest.starts_with("->") {
+            (Token::Implies, 2)
+        } else if rest.starts_with("/\\") {
+            (Token::And, 2)
+        } else if rest.starts_with("\\/") {
+            (Token::Or, 2)
+        } else if chars[i] == '~' || chars[i] == '¬' {
+            (Token::Not, 1)
+        } else if chars[i] == '∧' {
+            (Token::And, 1)
+        } else if chars[i] == '∨' {
+            (Token::Or, 1)
+        } else if chars[i] == '→' || chars[i] == '⇒' {
+            (Token::Implies, 1)
+        } else if chars[i] == '↔' || chars[i] == '⇔' {
+            (Token::Iff, 1)
+        } else if chars[i] == '⊕' {
+            (Token::Xor, 1)
+        } else if chars[i] == '↑' {
+            (Token::Nand, 1)
+        } else if chars[i] == '↓' {
+            (Token::Nor, 1)
+        } else if chars[i] == '⊤' {
+            (Token::Term("true".to_string()), 1)
+        } else if chars[i] == '⊥' {
+            (Token::Term("false".to_string()), 1)
+        } else if chars[i] == '≤' {
+            (Token::Compare(Comparison::AtMost), 1)
+        } else if chars[i] == '≥' {
+            (Token::Compare(Comparison::AtLeast), 1)
+        } else if chars[i] == '=' {
+            (Token::Compare(Comparison::Exactly), 1)
+        } else if chars[i] == '+' {
+            (Token::Plus, 1)
+        } else if chars[i] == '-' {
+            (Token::Minus, 1)
+        } else if chars[i] == '[' {
+            (Token::LeftBracket, 1)
+        } else if chars[i] == ']' {
+            (Token::RightBracket, 1)
+        } else if chars[i] == '(' {
+            (Token::LeftParen, 1)
+        } else if chars[i] == ')' {
+            (Token::RightParen, 1)
+        } else {
+            return Err(ParseError {
+                message: format!("Unexpected character '{}'", chars[i]),
+                line,
+                column
+            })
+        };
+
+        tokens.push((token, column));
+        i += length;
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use propositions::*;
+    use parser::*;
+
+    #[test]
+    fn parse_proposition_precedence() {
+        let prop = parse_proposition("~p \\/ q /\\ r <-> s -> t -> u").unwrap();
+
+        let expected = iff(
+            or(
+                not(term("p".to_string())),
+                and(term("q".to_string()), term("r".to_string()))
+            ),
+            implies(
+                term("s".to_string()),
+                implies(term("t".to_string()), term("u".to_string()))
+            )
+        );
+
+        assert_eq!(prop, expected);
+    }
+
+    #[test]
+    fn parse_proposition_unicode_round_trip() {
+        let prop = or(
+            and(
+                not(or(term("a".to_string()), term("b".to_string()))),
+                implies(term("c".to_string()), term("d".to_string()))
+            ),
+            iff(term("e".to_string()), term("f".to_string()))
+        );
+
+        assert_eq!(parse_proposition(&prop.to_unicode()), Ok(prop));
+    }
+
+    #[test]
+    fn parse_proposition_unclosed_paren() {
+        let expected = ParseError {
+            message: "Expected \")\"".to_string(),
+            line: 1,
+            column: 8
+        };
+
+        assert_eq!(parse_proposition("(p /\\ q"), Err(expected));
+    }
+
+    #[test]
+    fn parse_proposition_unexpected_character() {
+        let expected = ParseError {
+            message: "Unexpected character '&'".to_string(),
+            line: 1,
+            column: 3
+        };
+
+        assert_eq!(parse_proposition("p & q"), Err(expected));
+    }
+
+    #[test]
+    fn parse_problem_missing_goal() {
+        let expected = ParseError {
+            message: "Expected a goal after the separator line".to_string(),
+            line: 2,
+            column: 1
+        };
+
+        assert_eq!(parse_problem("p\n---\n"), Err(expected));
+    }
+
+    #[test]
+    fn parse_problem_error_line() {
+        let result = parse_problem("p\nq ->\n---\nq");
+
+        assert_eq!(result.unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn parse_proposition_constants() {
+        let prop = parse_proposition("p /\\ true -> false").unwrap();
+
+        let expected = implies(
+            and(term("p".to_string()), top()),
+            bottom()
+        );
+
+        assert_eq!(prop, expected);
+    }
+
+    #[test]
+    fn parse_proposition_and_all_round_trip() {
+        let prop = and_all(vec!(
+            term("p".to_string()),
+            or_all(vec!(
+                term("q".to_string()),
+                term("r".to_string()),
+                term("s".to_string())
+            )),
+            term("t".to_string())
+        ));
+
+        assert_eq!(prop.to_string(), "p /\\ q \\/ r \\/ s /\\ t");
+        assert_eq!(parse_proposition(&prop.to_unicode()), Ok(prop));
+    }
+
+    #[test]
+    fn parse_proposition_connectives() {
+        let prop = parse_proposition("a <+> b ~\\/ c ~/\\ d <-> if e then f else g").unwrap();
+
+        let expected = iff(
+            xor(
+                term("a".to_string()),
+                nor(
+                    term("b".to_string()),
+                    nand(term("c".to_string()), term("d".to_string()))
+                )
+            ),
+            ite(
+                term("e".to_string()),
+                term("f".to_string()),
+                term("g".to_string())
+            )
+        );
+
+        assert_eq!(prop, expected);
+    }
+
+    #[test]
+    fn parse_proposition_connectives_round_trip() {
+        let prop = xor(
+            xor(
+                term("a".to_string()),
+                ite(
+                    and(term("b".to_string()), term("c".to_string())),
+                    nand(term("d".to_string()), term("e".to_string())),
+                    nor(term("f".to_string()), term("g".to_string()))
+                )
+            ),
+            term("h".to_string())
+        );
+
+        assert_eq!(parse_proposition(&prop.to_unicode()), Ok(prop));
+    }
+
+    #[test]
+    fn parse_proposition_missing_else() {
+        let error = parse_proposition("if a then b").unwrap_err();
+
+        assert_eq!(error.message, "Expected \"else\"");
+        assert_eq!(error.column, 12);
+    }
+
+    #[test]
+    fn parse_proposition_unicode() {
+        let unicode = parse_proposition("¬p ∧ ⊤ ⇒ q ∨ ⊥ ⇔ r ⊕ s ↑ t ↓ u").unwrap();
+        let ascii = parse_proposition("~p /\\ true -> q \\/ false <-> r <+> s ~/\\ t ~\\/ u").unwrap();
+
+        assert_eq!(unicode, ascii);
+    }
+
+    #[test]
+    fn parse_proposition_linear() {
+        let prop = parse_proposition("[2 p - q + (r /\\ s) + 3 <= -1] -> [-x + 10 10 ≥ 2]").unwrap();
+
+        let expected = implies(
+            pseudo_boolean(
+                vec!(
+                    (2, term("p".to_string())),
+                    (-1, term("q".to_string())),
+                    (1, and(term("r".to_string()), term("s".to_string()))),
+                    (1, term("3".to_string()))
+                ),
+                Comparison::AtMost,
+                -1
+            ),
+            pseudo_boolean(
+                vec!((-1, term("x".to_string())), (10, term("10".to_string()))),
+                Comparison::AtLeast,
+                2
+            )
+        );
+
+        assert_eq!(prop, expected);
+        assert_eq!(parse_proposition(&prop.to_string()), Ok(prop));
+    }
+
+    #[test]
+    fn parse_proposition_linear_errors() {
+        let cases = vec!(
+            ("[p + q]", 7, "Expected \"<=\", \">=\" or \"=\""),
+            ("[p + q <= r]", 11, "Expected a number"),
+            ("[p <= 1", 8, "Expected \"]\""),
+            ("[p <= 99999999999999999999]", 7, "Expected a number that fits in 64 bits"),
+            ("[<= 1]", 2, "Unexpected \"<=\""),
+        );
+
+        for (input, column, message) in cases {
+            let error = parse_proposition(input).unwrap_err();
+
+            assert_eq!((error.column, error.message.as_str()), (column, message), "{}", input);
+        }
+    }
+}
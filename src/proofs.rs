@@ -0,0 +1,245 @@
+use clauses::*;
+use propositions::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A resolution refutation showing that a goal follows from a set of
+/// assumptions.
+///
+/// The steps of the proof form a directed acyclic graph, where input clauses
+/// come from the assumptions or the negated goal, and every other clause is
+/// the resolvent of two earlier steps. The final step is the empty clause.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let assumptions = vec!(term("p".to_string()));
+/// let goal = term("p".to_string());
+///
+/// let proof = prove(assumptions, goal).unwrap();
+///
+/// assert_eq!(proof.steps.len(), 3);
+/// assert_eq!(proof.conclusion().to_string(), "[]");
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Proof {
+    pub assumptions: Vec<Proposition>,
+    pub goal: Proposition,
+    pub steps: Vec<ProofStep>
+}
+
+/// A clause in a proof along with the reason that it holds.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ProofStep {
+    pub clause: Clause,
+    pub justification: Justification
+}
+
+/// The reason that a clause in a proof holds.
+///
+/// Resolutions refer to earlier steps of the proof by index, with `pivot`
+/// being the part of the `left` clause whose negation appears in the `right`
+/// clause.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Justification {
+    Assumption(usize),
+    NegatedGoal,
+    Resolution { left: usize, right: usize, pivot: ClausePart }
+}
+
+impl Proof {
+    /// Returns the clause derived by the final step of the proof, which is
+    /// the empty clause.
+    pub fn conclusion(&self) -> &Clause {
+        &self.steps[self.steps.len() - 1].clause
+    }
+
+    /// Returns the indices of the assumptions that the proof uses, in
+    /// increasing order. The goal follows from these assumptions alone, or,
+    /// if the proof does not use the negated goal, they are contradictory on
+    /// their own.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let assumptions = vec!(
+    ///     term("p".to_string()),
+    ///     term("r".to_string()),
+    ///     implies(term("p".to_string()), term("q".to_string()))
+    /// );
+    ///
+    /// let goal = term("q".to_string());
+    ///
+    /// let proof = prove(assumptions, goal).unwrap();
+    ///
+    /// assert_eq!(proof.used_assumptions(), vec!(0, 2));
+    /// ```
+    pub fn used_assumptions(&self) -> Vec<usize> {
+        let mut used: Vec<usize> = self.steps.iter()
+            .filter_map(|s| match s.justification {
+                Justification::Assumption(i) => Some(i),
+                _ => None
+            })
+            .collect();
+
+        used.sort_unstable();
+        used.dedup();
+        used
+    }
+
+    /// Exports the proof as a Graphviz DOT graph.
+    ///
+    /// Clauses from the assumptions are drawn as boxes, clauses from the
+    /// negated goal as shaded boxes, and resolvents as ellipses. Each
+    /// resolvent has an edge from both of its parent clauses labelled with
+    /// the pivot, and the empty clause is drawn as a double octagon.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let assumptions = vec!(term("p".to_string()));
+    /// let goal = term("p".to_string());
+    ///
+    /// let dot = prove(assumptions, goal).unwrap().to_dot();
+    ///
+    /// assert!(dot.starts_with("digraph proof {"));
+    /// assert!(dot.contains("s0 -> s2 [label=\"~p\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!(
+            "digraph proof {".to_string(),
+            "    rankdir=TB;".to_string()
+        );
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let label = escape_dot(&step.clause.to_string());
+            let style = match step.justification {
+                Justification::Assumption(_) => "shape=box",
+                Justification::NegatedGoal =>
+                    "shape=box, style=filled, fillcolor=lightgrey",
+                Justification::Resolution { .. } if step.clause.parts.is_empty() =>
+                    "shape=doubleoctagon",
+                Justification::Resolution { .. } => "shape=ellipse",
+            };
+
+            lines.push(format!("    s{} [label=\"{}\", {}];", i, label, style));
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Justification::Resolution { left, right, ref pivot } = step.justification {
+                let label = escape_dot(&pivot.to_string());
+
+                lines.push(format!("    s{} -> s{} [label=\"{}\"];", left, i, label));
+                lines.push(format!("    s{} -> s{} [label=\"{}\"];", right, i, label));
+            }
+        }
+
+        lines.push("}".to_string());
+        lines.push("".to_string());
+
+        lines.join("\n")
+    }
+}
+
+/// Escapes the given text for use within a quoted DOT string.
+pub(crate) fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use proofs::*;
+    use resolution::*;
+
+    #[test]
+    fn proof_steps_simple() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(term("p".to_string()), term("q".to_string()))
+        );
+
+        let goal = term("q".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        let expected = vec!(
+            ProofStep {
+                clause: Clause { parts: vec!(
+                    ClausePart::NegatedTerm("q".to_string())
+                ) },
+                justification: Justification::NegatedGoal
+            },
+            ProofStep {
+                clause: Clause { parts: vec!(
+                    ClausePart::NegatedTerm("p".to_string()),
+                    ClausePart::Term("q".to_string())
+                ) },
+                justification: Justification::Assumption(1)
+            },
+            ProofStep {
+                clause: Clause { parts: vec!(
+                    ClausePart::NegatedTerm("p".to_string())
+                ) },
+                justification: Justification::Resolution {
+                    left: 0,
+                    right: 1,
+                    pivot: ClausePart::NegatedTerm("q".to_string())
+                }
+            },
+            ProofStep {
+                clause: Clause { parts: vec!(
+                    ClausePart::Term("p".to_string())
+                ) },
+                justification: Justification::Assumption(0)
+            },
+            ProofStep {
+                clause: Clause { parts: vec!() },
+                justification: Justification::Resolution {
+                    left: 2,
+                    right: 3,
+                    pivot: ClausePart::NegatedTerm("p".to_string())
+                }
+            }
+        );
+
+        assert_eq!(proof.steps, expected);
+    }
+
+    #[test]
+    fn to_dot_simple() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(term("p".to_string()), term("q".to_string()))
+        );
+
+        let goal = term("q".to_string());
+
+        let dot = prove(assumptions, goal).unwrap().to_dot();
+
+        let expected = "digraph proof {
+    rankdir=TB;
+    s0 [label=\"~q\", shape=box, style=filled, fillcolor=lightgrey];
+    s1 [label=\"~p \\\\/ q\", shape=box];
+    s2 [label=\"~p\", shape=ellipse];
+    s3 [label=\"p\", shape=box];
+    s4 [label=\"[]\", shape=doubleoctagon];
+    s0 -> s2 [label=\"~q\"];
+    s1 -> s2 [label=\"~q\"];
+    s2 -> s4 [label=\"~p\"];
+    s3 -> s4 [label=\"~p\"];
+}
+";
+
+        assert_eq!(dot, expected);
+    }
+}
@@ -1,4 +1,6 @@
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
+use std::ops;
 
 /// A statement in propositional logic.
 ///
@@ -20,8 +22,40 @@ pub enum Proposition {
     And(Box<Proposition>, Box<Proposition>),
     Implies(Box<Proposition>, Box<Proposition>),
     Iff(Box<Proposition>, Box<Proposition>),
+    Xor(Box<Proposition>, Box<Proposition>),
+    Nand(Box<Proposition>, Box<Proposition>),
+    Nor(Box<Proposition>, Box<Proposition>),
+    Ite(Box<Proposition>, Box<Proposition>, Box<Proposition>),
+    /// A linear constraint comparing the sum of the coefficients of the true
+    /// operands against a bound.
+    Linear(Vec<(i64, Proposition)>, Comparison, i64),
     Not(Box<Proposition>),
-    Term(String)
+    Term(String),
+    Top,
+    Bottom
+}
+
+/// The way that the sum of a linear constraint is compared against its
+/// bound.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(PartialEq)]
+pub enum Comparison {
+    AtMost,
+    AtLeast,
+    Exactly
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Comparison::AtMost => write!(f, "<="),
+            Comparison::AtLeast => write!(f, ">="),
+            Comparison::Exactly => write!(f, "="),
+        }
+    }
 }
 
 /// Creates a proposition that is the disjunction of the two given
@@ -64,6 +98,56 @@ pub fn and(a: Proposition, b: Proposition) -> Proposition {
     )
 }
 
+/// Creates a proposition that is the disjunction of all of the given
+/// propositions, grouped from the left. The disjunction of no propositions is
+/// `Bottom`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let props = vec!(
+///     term("p".to_string()),
+///     term("q".to_string()),
+///     term("r".to_string())
+/// );
+///
+/// assert_eq!(or_all(props).to_string(), "p \\/ q \\/ r");
+/// assert_eq!(or_all(vec!()), bottom());
+/// ```
+pub fn or_all(props: Vec<Proposition>) -> Proposition {
+    props.into_iter()
+        .fold(None, |acc, p| Some(match acc {
+            Some(a) => or(a, p),
+            None => p
+        }))
+        .unwrap_or(Proposition::Bottom)
+}
+
+/// Creates a proposition that is the conjunction of all of the given
+/// propositions, grouped from the left. The conjunction of no propositions is
+/// `Top`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let props = vec!(
+///     term("p".to_string()),
+///     term("q".to_string()),
+///     term("r".to_string())
+/// );
+///
+/// assert_eq!(and_all(props).to_string(), "p /\\ q /\\ r");
+/// assert_eq!(and_all(vec!()), top());
+/// ```
+pub fn and_all(props: Vec<Proposition>) -> Proposition {
+    props.into_iter()
+        .fold(None, |acc, p| Some(match acc {
+            Some(a) => and(a, p),
+            None => p
+        }))
+        .unwrap_or(Proposition::Top)
+}
+
 /// Creates a proposition that is the implication consisting of the two given
 /// propositions as the antecedent and consequent repsectively.
 ///
@@ -104,6 +188,149 @@ pub fn iff(a: Proposition, b: Proposition) -> Proposition {
     )
 }
 
+/// Creates a proposition that is the exclusive disjunction of the two given
+/// propositions, which holds when exactly one of them does.
+///
+/// ```
+/// let p = resolution_prover::term("p".to_string());
+/// let q = resolution_prover::term("q".to_string());
+///
+/// let p_xor_q = resolution_prover::xor(p, q);
+///
+/// let expected = "p <+> q";
+///
+/// assert_eq!(p_xor_q.to_string(), expected);
+/// ```
+pub fn xor(a: Proposition, b: Proposition) -> Proposition {
+    Proposition::Xor(
+        Box::new(a),
+        Box::new(b)
+    )
+}
+
+/// Creates a proposition that is the negated conjunction of the two given
+/// propositions.
+///
+/// ```
+/// let p = resolution_prover::term("p".to_string());
+/// let q = resolution_prover::term("q".to_string());
+///
+/// let p_nand_q = resolution_prover::nand(p, q);
+///
+/// let expected = "p ~/\\ q";
+///
+/// assert_eq!(p_nand_q.to_string(), expected);
+/// ```
+pub fn nand(a: Proposition, b: Proposition) -> Proposition {
+    Proposition::Nand(
+        Box::new(a),
+        Box::new(b)
+    )
+}
+
+/// Creates a proposition that is the negated disjunction of the two given
+/// propositions.
+///
+/// ```
+/// let p = resolution_prover::term("p".to_string());
+/// let q = resolution_prover::term("q".to_string());
+///
+/// let p_nor_q = resolution_prover::nor(p, q);
+///
+/// let expected = "p ~\\/ q";
+///
+/// assert_eq!(p_nor_q.to_string(), expected);
+/// ```
+pub fn nor(a: Proposition, b: Proposition) -> Proposition {
+    Proposition::Nor(
+        Box::new(a),
+        Box::new(b)
+    )
+}
+
+/// Creates a proposition that is the conditional choice between the two
+/// given branches, which holds when the condition and the first branch hold,
+/// or when the condition does not hold and the second branch does.
+///
+/// ```
+/// let c = resolution_prover::term("c".to_string());
+/// let p = resolution_prover::term("p".to_string());
+/// let q = resolution_prover::term("q".to_string());
+///
+/// let choice = resolution_prover::ite(c, p, q);
+///
+/// let expected = "if c then p else q";
+///
+/// assert_eq!(choice.to_string(), expected);
+/// ```
+pub fn ite(condition: Proposition, then: Proposition, otherwise: Proposition) -> Proposition {
+    Proposition::Ite(
+        Box::new(condition),
+        Box::new(then),
+        Box::new(otherwise)
+    )
+}
+
+/// Creates a proposition that holds when at most `k` of the given
+/// propositions are true.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let props = vec!(Proposition::from("p"), "q".into(), "r".into());
+///
+/// assert_eq!(at_most_k(props, 2).to_string(), "[p + q + r <= 2]");
+/// ```
+pub fn at_most_k(props: Vec<Proposition>, k: i64) -> Proposition {
+    pseudo_boolean(props.into_iter().map(|p| (1, p)).collect(), Comparison::AtMost, k)
+}
+
+/// Creates a proposition that holds when at least `k` of the given
+/// propositions are true.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let props = vec!(Proposition::from("p"), "q".into(), "r".into());
+///
+/// assert_eq!(at_least_k(props, 1).to_string(), "[p + q + r >= 1]");
+/// ```
+pub fn at_least_k(props: Vec<Proposition>, k: i64) -> Proposition {
+    pseudo_boolean(props.into_iter().map(|p| (1, p)).collect(), Comparison::AtLeast, k)
+}
+
+/// Creates a proposition that holds when exactly `k` of the given
+/// propositions are true.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let props = vec!(Proposition::from("p"), "q".into(), "r".into());
+///
+/// assert_eq!(exactly_k(props, 1).to_string(), "[p + q + r = 1]");
+/// ```
+pub fn exactly_k(props: Vec<Proposition>, k: i64) -> Proposition {
+    pseudo_boolean(props.into_iter().map(|p| (1, p)).collect(), Comparison::Exactly, k)
+}
+
+/// Creates a proposition that holds when the sum of the coefficients of the
+/// true propositions compares to the bound in the given way.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let prop = pseudo_boolean(
+///     vec!((3, term("p".to_string())), (-2, term("q".to_string())), (1, term("r".to_string()))),
+///     Comparison::AtLeast,
+///     2
+/// );
+///
+/// assert_eq!(prop.to_string(), "[3 p - 2 q + r >= 2]");
+/// ```
+pub fn pseudo_boolean(terms: Vec<(i64, Proposition)>, comparison: Comparison, bound: i64) -> Proposition {
+    Proposition::Linear(terms, comparison, bound)
+}
+
 /// Creates a proposition that is the negation of the given proposition.
 ///
 /// ```
@@ -132,6 +359,299 @@ pub fn term(value: String) -> Proposition {
     Proposition::Term(value)
 }
 
+/// Creates the proposition that is always true.
+///
+/// ```
+/// let t = resolution_prover::top();
+///
+/// assert_eq!(t.to_string(), "true");
+/// ```
+pub fn top() -> Proposition {
+    Proposition::Top
+}
+
+/// Creates the proposition that is always false.
+///
+/// ```
+/// let f = resolution_prover::bottom();
+///
+/// assert_eq!(f.to_string(), "false");
+/// ```
+pub fn bottom() -> Proposition {
+    Proposition::Bottom
+}
+
+impl Proposition {
+    /// Creates a proposition that is the implication with this proposition
+    /// as the antecedent and the given proposition as the consequent.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = Proposition::from("p").implies("q");
+    ///
+    /// assert_eq!(prop, implies(term("p".to_string()), term("q".to_string())));
+    /// ```
+    pub fn implies<T: Into<Proposition>>(self, other: T) -> Proposition {
+        implies(self, other.into())
+    }
+
+    /// Creates a proposition that is the biconditional of this proposition
+    /// and the given proposition.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = Proposition::from("p").iff("q");
+    ///
+    /// assert_eq!(prop, iff(term("p".to_string()), term("q".to_string())));
+    /// ```
+    pub fn iff<T: Into<Proposition>>(self, other: T) -> Proposition {
+        iff(self, other.into())
+    }
+
+    /// Returns the names of the terms used in the proposition, in sorted
+    /// order and without repeats.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(q -> p) /\\ ~q").unwrap();
+    ///
+    /// assert_eq!(prop.terms(), vec!("p", "q"));
+    /// ```
+    pub fn terms(&self) -> Vec<String> {
+        fn collect<'a>(prop: &'a Proposition, out: &mut BTreeSet<&'a String>) {
+            match *prop {
+                Proposition::Or(ref a, ref b)
+                | Proposition::And(ref a, ref b)
+                | Proposition::Implies(ref a, ref b)
+                | Proposition::Iff(ref a, ref b)
+                | Proposition::Xor(ref a, ref b)
+                | Proposition::Nand(ref a, ref b)
+                | Proposition::Nor(ref a, ref b) => {
+                    collect(a, out);
+                    collect(b, out);
+                },
+                Proposition::Ite(ref c, ref t, ref e) => {
+                    collect(c, out);
+                    collect(t, out);
+                    collect(e, out);
+                },
+                Proposition::Linear(ref terms, _, _) => {
+                    for (_, p) in terms {
+                        collect(p, out);
+                    }
+                },
+                Proposition::Not(ref a) => collect(a, out),
+                Proposition::Term(ref a) => {
+                    out.insert(a);
+                },
+                Proposition::Top | Proposition::Bottom => {},
+            }
+        }
+
+        let mut terms = BTreeSet::new();
+        collect(self, &mut terms);
+        terms.into_iter().cloned().collect()
+    }
+
+    /// Returns the operands of the connective at the top of the proposition.
+    pub(crate) fn operands(&self) -> Vec<&Proposition> {
+        match *self {
+            Proposition::Or(ref a, ref b)
+            | Proposition::And(ref a, ref b)
+            | Proposition::Implies(ref a, ref b)
+            | Proposition::Iff(ref a, ref b)
+            | Proposition::Xor(ref a, ref b)
+            | Proposition::Nand(ref a, ref b)
+            | Proposition::Nor(ref a, ref b) => vec!(a, b),
+            Proposition::Ite(ref c, ref t, ref e) => vec!(c, t, e),
+            Proposition::Linear(ref terms, _, _) => terms.iter().map(|(_, p)| p).collect(),
+            Proposition::Not(ref a) => vec!(a),
+            _ => vec!()
+        }
+    }
+
+    /// Returns the proposition with each term replaced by the proposition
+    /// that the given function gives for its name.
+    pub(crate) fn map_terms(&self, f: &dyn Fn(&str) -> Proposition) -> Proposition {
+        let m = |p: &Proposition| p.map_terms(f);
+        match *self {
+            Proposition::Or(ref a, ref b) => or(m(a), m(b)),
+            Proposition::And(ref a, ref b) => and(m(a), m(b)),
+            Proposition::Implies(ref a, ref b) => implies(m(a), m(b)),
+            Proposition::Iff(ref a, ref b) => iff(m(a), m(b)),
+            Proposition::Xor(ref a, ref b) => xor(m(a), m(b)),
+            Proposition::Nand(ref a, ref b) => nand(m(a), m(b)),
+            Proposition::Nor(ref a, ref b) => nor(m(a), m(b)),
+            Proposition::Ite(ref c, ref t, ref e) => ite(m(c), m(t), m(e)),
+            Proposition::Linear(ref terms, comparison, bound) => Proposition::Linear(
+                terms.iter().map(|(c, p)| (*c, m(p))).collect(),
+                comparison,
+                bound
+            ),
+            Proposition::Not(ref a) => not(m(a)),
+            Proposition::Term(ref a) => f(a),
+            Proposition::Top => top(),
+            Proposition::Bottom => bottom(),
+        }
+    }
+
+    /// Evaluates the proposition with each term taking the value that it is
+    /// given in the assignment, returning `None` if any of the terms are not
+    /// given values.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let prop = parse_proposition("p -> q").unwrap();
+    ///
+    /// let mut assignment = HashMap::new();
+    /// assignment.insert("p".to_string(), true);
+    ///
+    /// assert_eq!(prop.evaluate(&assignment), None);
+    ///
+    /// assignment.insert("q".to_string(), false);
+    ///
+    /// assert_eq!(prop.evaluate(&assignment), Some(false));
+    /// ```
+    pub fn evaluate(&self, assignment: &HashMap<String, bool>) -> Option<bool> {
+        let value = match *self {
+            Proposition::Or(ref a, ref b)
+            | Proposition::And(ref a, ref b)
+            | Proposition::Implies(ref a, ref b)
+            | Proposition::Iff(ref a, ref b)
+            | Proposition::Xor(ref a, ref b)
+            | Proposition::Nand(ref a, ref b)
+            | Proposition::Nor(ref a, ref b) => {
+                let (a2, b2) = (a.evaluate(assignment)?, b.evaluate(assignment)?);
+                match *self {
+                    Proposition::Or(..) => a2 || b2,
+                    Proposition::And(..) => a2 && b2,
+                    Proposition::Implies(..) => !a2 || b2,
+                    Proposition::Iff(..) => a2 == b2,
+                    Proposition::Xor(..) => a2 != b2,
+                    Proposition::Nand(..) => !(a2 && b2),
+                    _ => !(a2 || b2),
+                }
+            },
+            Proposition::Ite(ref c, ref t, ref e) => {
+                let (c2, t2, e2) = (
+                    c.evaluate(assignment)?,
+                    t.evaluate(assignment)?,
+                    e.evaluate(assignment)?
+                );
+                if c2 { t2 } else { e2 }
+            },
+            Proposition::Linear(ref terms, comparison, bound) => {
+                // Summed in a wider type so that large coefficients cannot
+                // overflow
+                let mut sum: i128 = 0;
+                for &(c, ref p) in terms {
+                    if p.evaluate(assignment)? {
+                        sum += i128::from(c);
+                    }
+                }
+                let bound = i128::from(bound);
+                match comparison {
+                    Comparison::AtMost => sum <= bound,
+                    Comparison::AtLeast => sum >= bound,
+                    Comparison::Exactly => sum == bound,
+                }
+            },
+            Proposition::Not(ref a) => !a.evaluate(assignment)?,
+            Proposition::Term(ref a) => *assignment.get(a)?,
+            Proposition::Top => true,
+            Proposition::Bottom => false,
+        };
+
+        Some(value)
+    }
+}
+
+impl<'a> From<&'a str> for Proposition {
+    /// Creates a term with the given name.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// assert_eq!(Proposition::from("p"), term("p".to_string()));
+    /// ```
+    fn from(value: &'a str) -> Proposition {
+        term(value.to_string())
+    }
+}
+
+impl<T: Into<Proposition>> ops::BitAnd<T> for Proposition {
+    type Output = Proposition;
+
+    /// Creates the conjunction of the two propositions.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = Proposition::from("p") & "q";
+    ///
+    /// assert_eq!(prop, and(term("p".to_string()), term("q".to_string())));
+    /// ```
+    fn bitand(self, other: T) -> Proposition {
+        and(self, other.into())
+    }
+}
+
+impl<T: Into<Proposition>> ops::BitOr<T> for Proposition {
+    type Output = Proposition;
+
+    /// Creates the disjunction of the two propositions.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = Proposition::from("p") | "q";
+    ///
+    /// assert_eq!(prop, or(term("p".to_string()), term("q".to_string())));
+    /// ```
+    fn bitor(self, other: T) -> Proposition {
+        or(self, other.into())
+    }
+}
+
+impl<T: Into<Proposition>> ops::BitXor<T> for Proposition {
+    type Output = Proposition;
+
+    /// Creates the exclusive disjunction of the two propositions.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = Proposition::from("p") ^ "q";
+    ///
+    /// assert_eq!(prop, xor(term("p".to_string()), term("q".to_string())));
+    /// ```
+    fn bitxor(self, other: T) -> Proposition {
+        xor(self, other.into())
+    }
+}
+
+impl ops::Not for Proposition {
+    type Output = Proposition;
+
+    /// Creates the negation of the proposition.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = !Proposition::from("p");
+    ///
+    /// assert_eq!(prop, not(term("p".to_string())));
+    /// ```
+    fn not(self) -> Proposition {
+        not(self)
+    }
+}
+
 impl fmt::Display for Proposition {
     /// Displays the proposition using cominations of ascii characters to
     /// represent the propositional logic operations.
@@ -162,8 +682,87 @@ impl fmt::Display for Proposition {
             Proposition::Iff(ref a, ref b) => {
                 write!(f, "{} <-> {}", a, b)
             },
+            Proposition::Xor(ref a, ref b) => {
+                write!(f, "{} <+> {}", a, b)
+            },
+            Proposition::Nand(ref a, ref b) => {
+                write!(f, "{} ~/\\ {}", a, b)
+            },
+            Proposition::Nor(ref a, ref b) => {
+                write!(f, "{} ~\\/ {}", a, b)
+            },
+            Proposition::Ite(ref c, ref t, ref e) => {
+                write!(f, "if {} then {} else {}", c, t, e)
+            },
+            Proposition::Linear(ref terms, comparison, bound) => {
+                write!(f, "[")?;
+                for (i, &(c, ref p)) in terms.iter().enumerate() {
+                    let sign = match (i, c < 0) {
+                        (0, false) => "",
+                        (0, true) => "-",
+                        (_, false) => " + ",
+                        (_, true) => " - ",
+                    };
+                    match c.unsigned_abs() {
+                        1 => write!(f, "{}{}", sign, Parenthesized(p))?,
+                        n => write!(f, "{}{} {}", sign, n, Parenthesized(p))?,
+                    }
+                }
+                if terms.is_empty() {
+                    write!(f, "0")?;
+                }
+                write!(f, " {} {}]", comparison, bound)
+            },
             Proposition::Not(ref a) => write!(f, "~({})", a),
             Proposition::Term(ref a) => write!(f, "{}", a),
+            Proposition::Top => write!(f, "true"),
+            Proposition::Bottom => write!(f, "false"),
+        }
+    }
+}
+
+/// Wrapper used to display an operand of a linear constraint, surrounding it
+/// in parentheses if it is itself a binary operation or a conditional choice.
+struct Parenthesized<'a>(&'a Proposition);
+
+impl<'a> fmt::Display for Parenthesized<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            Proposition::Linear(..)
+            | Proposition::Not(_)
+            | Proposition::Term(_)
+            | Proposition::Top
+            | Proposition::Bottom => write!(f, "{}", self.0),
+            ref p => write!(f, "({})", p),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use propositions::*;
+
+    #[test]
+    fn operators_precedence() {
+        let p = Proposition::from("p");
+        let q = Proposition::from("q");
+
+        let prop = (!p.clone() | q.clone() & "r").implies(p ^ q);
+
+        assert_eq!(prop.to_string(), "~(p) \\/ q /\\ r -> p <+> q");
+    }
+
+    #[test]
+    fn evaluate_linear_large_coefficients() {
+        let prop = Proposition::Linear(
+            vec!((i64::MAX, term("p".to_string())), (i64::MAX, term("q".to_string()))),
+            Comparison::AtLeast,
+            i64::MAX
+        );
+        let assignment = vec!(("p".to_string(), true), ("q".to_string(), true))
+            .into_iter()
+            .collect();
+
+        assert_eq!(prop.evaluate(&assignment), Some(true));
+    }
+}
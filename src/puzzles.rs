@@ -0,0 +1,729 @@
+use bmc::*;
+use clauses::*;
+use parser::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::HashMap;
+
+/// A Sudoku puzzle, made up of a grid of `n` by `n` cells, where `n` is the
+/// square of the box size, some of which are already filled in with values
+/// from 1 to `n`.
+///
+/// Every row, column and box of a solution holds each value exactly once.
+/// The clauses use a term `cell_r_c_v` for each row `r` and column `c`,
+/// counting from zero, that is true when the cell holds the value `v`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let sudoku = parse_sudoku("\
+/// 1 . | . .
+/// . . | 1 .
+/// ----+----
+/// . 1 | . .
+/// . . | . 1
+/// ").unwrap();
+///
+/// let grid = sudoku.solve().unwrap();
+///
+/// assert_eq!(grid[1][2], 1);
+/// for row in &grid {
+///     let mut values = row.clone();
+///     values.sort();
+///     assert_eq!(values, vec!(1, 2, 3, 4));
+/// }
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Sudoku {
+    pub box_size: usize,
+    pub grid: Vec<Vec<Option<usize>>>
+}
+
+/// A Latin square of `n` by `n` cells to be completed, some of which are
+/// already filled in with values from 1 to `n`.
+///
+/// Every row and column of a solution holds each value exactly once. The
+/// clauses use the same terms as those of `Sudoku`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let square = LatinSquare {
+///     grid: vec!(
+///         vec!(Some(1), None, None),
+///         vec!(None, Some(1), None),
+///         vec!(None, None, Some(3))
+///     )
+/// };
+///
+/// assert_eq!(square.solve(), None);
+///
+/// let square = LatinSquare { grid: vec!(vec!(None; 3); 3) };
+///
+/// assert_eq!(count_models(&square.to_clauses()), Some(12));
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct LatinSquare {
+    pub grid: Vec<Vec<Option<usize>>>
+}
+
+/// The problem of placing queens on a board of the given size, so that no
+/// two of them share a row, a column or a diagonal.
+///
+/// The clauses use a term `queen_r_c` for each row `r` and column `c`,
+/// counting from zero, that is true when there is a queen on that square.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// assert_eq!(NQueens { size: 3 }.solve(), None);
+/// assert_eq!(count_models(&NQueens { size: 6 }.to_clauses()), Some(4));
+///
+/// let columns = NQueens { size: 8 }.solve().unwrap();
+///
+/// assert_eq!(columns.len(), 8);
+/// ```
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct NQueens {
+    pub size: usize
+}
+
+/// The problem of coloring the vertices of a graph, numbered from zero, with
+/// the given number of colors, so that the ends of every edge differ.
+///
+/// The clauses use a term `color_v_c` for each vertex `v` and color `c`,
+/// counting from zero, that is true when the vertex has that color.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// // A cycle of five vertices needs three colors
+/// let mut coloring = GraphColoring {
+///     vertices: 5,
+///     edges: vec!((0, 1), (1, 2), (2, 3), (3, 4), (4, 0)),
+///     colors: 2
+/// };
+///
+/// assert_eq!(coloring.solve(), None);
+///
+/// coloring.colors = 3;
+/// let colors = coloring.solve().unwrap();
+///
+/// assert!(coloring.edges.iter().all(|&(a, b)| colors[a] != colors[b]));
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct GraphColoring {
+    pub vertices: usize,
+    pub edges: Vec<(usize, usize)>,
+    pub colors: usize
+}
+
+/// The problem of putting every pigeon in a hole, without putting two
+/// pigeons in the same hole.
+///
+/// This can only be done when there are at least as many holes as pigeons,
+/// but resolution proofs that it cannot be done otherwise grow exponentially
+/// with the number of holes, which makes it a standard hard benchmark. The
+/// clauses use a term `pigeon_p_h` for each pigeon `p` and hole `h`,
+/// counting from zero, that is true when the pigeon is in that hole.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// assert_eq!(Pigeonhole { pigeons: 4, holes: 3 }.solve(), None);
+/// assert_eq!(count_models(&Pigeonhole { pigeons: 3, holes: 3 }.to_clauses()), Some(6));
+/// ```
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Pigeonhole {
+    pub pigeons: usize,
+    pub holes: usize
+}
+
+/// An action of a planning problem, which can be taken when all of its
+/// preconditions are true, and which makes the fluents it adds true and the
+/// fluents it deletes false.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct Action {
+    pub name: String,
+    pub preconditions: Vec<String>,
+    pub add: Vec<String>,
+    pub delete: Vec<String>
+}
+
+/// A planning problem in the style of STRIPS, which asks for a sequence of
+/// actions that leads from the initial state, where exactly the given
+/// fluents are true, to a state where all of the goal fluents are true.
+///
+/// A fluent that an action both adds and deletes is added. The names of the
+/// fluents and actions are used as terms, so they must all be different.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let action = |name: &str, preconditions: &[&str], add: &[&str], delete: &[&str]| Action {
+///     name: name.to_string(),
+///     preconditions: preconditions.iter().map(|s| s.to_string()).collect(),
+///     add: add.iter().map(|s| s.to_string()).collect(),
+///     delete: delete.iter().map(|s| s.to_string()).collect()
+/// };
+///
+/// let problem = PlanningProblem {
+///     fluents: vec!("dark".to_string(), "lit".to_string(), "have_match".to_string()),
+///     actions: vec!(
+///         action("strike", &["have_match"], &["lit"], &["dark", "have_match"]),
+///         action("fetch", &[], &["have_match"], &[])
+///     ),
+///     init: vec!("dark".to_string()),
+///     goal: vec!("lit".to_string())
+/// };
+///
+/// assert_eq!(problem.solve(1), None);
+/// assert_eq!(problem.solve(5), Some(vec!("fetch".to_string(), "strike".to_string())));
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct PlanningProblem {
+    pub fluents: Vec<String>,
+    pub actions: Vec<Action>,
+    pub init: Vec<String>,
+    pub goal: Vec<String>
+}
+
+impl Sudoku {
+    /// Returns clauses whose models are exactly the solutions of the puzzle.
+    pub fn to_clauses(&self) -> Vec<Clause> {
+        let size = self.grid.len();
+        let mut groups = lines(size);
+        for box_row in (0..size).step_by(self.box_size.max(1)) {
+            for box_column in (0..size).step_by(self.box_size.max(1)) {
+                groups.push((0..self.box_size * self.box_size)
+                    .map(|i| (box_row + i / self.box_size, box_column + i % self.box_size))
+                    .collect());
+            }
+        }
+
+        grid_clauses(&self.grid, &groups)
+    }
+
+    /// Reads the filled in grid out of a model of the clauses, returning
+    /// `None` if some cell has no value.
+    pub fn decode(&self, model: &HashMap<String, bool>) -> Option<Vec<Vec<usize>>> {
+        decode_grid(self.grid.len(), model)
+    }
+
+    /// Solves the puzzle, returning the filled in grid, or `None` if it has
+    /// no solution.
+    pub fn solve(&self) -> Option<Vec<Vec<usize>>> {
+        find_clause_model(&self.to_clauses()).and_then(|m| self.decode(&m))
+    }
+}
+
+impl LatinSquare {
+    /// Returns clauses whose models are exactly the completions of the
+    /// square.
+    pub fn to_clauses(&self) -> Vec<Clause> {
+        grid_clauses(&self.grid, &lines(self.grid.len()))
+    }
+
+    /// Reads the completed square out of a model of the clauses, returning
+    /// `None` if some cell has no value.
+    pub fn decode(&self, model: &HashMap<String, bool>) -> Option<Vec<Vec<usize>>> {
+        decode_grid(self.grid.len(), model)
+    }
+
+    /// Completes the square, returning `None` if it cannot be completed.
+    pub fn solve(&self) -> Option<Vec<Vec<usize>>> {
+        find_clause_model(&self.to_clauses()).and_then(|m| self.decode(&m))
+    }
+}
+
+impl NQueens {
+    /// Returns clauses whose models are exactly the ways of placing the
+    /// queens.
+    pub fn to_clauses(&self) -> Vec<Clause> {
+        let queen = |r: usize, c: usize| format!("queen_{}_{}", r, c);
+        let n = self.size;
+
+        let mut clauses = vec!();
+        for i in 0..n {
+            clauses.append(&mut exactly_one(&(0..n).map(|c| queen(i, c)).collect::<Vec<String>>()));
+            clauses.append(&mut exactly_one(&(0..n).map(|r| queen(r, i)).collect::<Vec<String>>()));
+        }
+
+        // Each diagonal is given by the difference or the sum of the row and
+        // column of its squares
+        for d in 0..(2 * n).saturating_sub(1) {
+            let falling: Vec<String> = (0..n)
+                .filter(|&r| r + n > d && r + n - 1 - d < n)
+                .map(|r| queen(r, r + n - 1 - d))
+                .collect();
+            let rising: Vec<String> = (0..n)
+                .filter(|&r| r <= d && d - r < n)
+                .map(|r| queen(r, d - r))
+                .collect();
+            clauses.append(&mut at_most_one(&falling));
+            clauses.append(&mut at_most_one(&rising));
+        }
+
+        clauses
+    }
+
+    /// Reads the column of the queen in each row out of a model of the
+    /// clauses, returning `None` if some row has no queen.
+    pub fn decode(&self, model: &HashMap<String, bool>) -> Option<Vec<usize>> {
+        (0..self.size)
+            .map(|r| first_true(model, self.size, |c| format!("queen_{}_{}", r, c)))
+            .collect()
+    }
+
+    /// Places the queens, returning the column of the queen in each row, or
+    /// `None` if they cannot be placed.
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        find_clause_model(&self.to_clauses()).and_then(|m| self.decode(&m))
+    }
+}
+
+impl GraphColoring {
+    /// Returns clauses whose models are exactly the colorings of the graph.
+    pub fn to_clauses(&self) -> Vec<Clause> {
+        let color = |v: usize, c: usize| format!("color_{}_{}", v, c);
+
+        let mut clauses = vec!();
+        for v in 0..self.vertices {
+            clauses.append(&mut exactly_one(&(0..self.colors).map(|c| color(v, c)).collect::<Vec<String>>()));
+        }
+        for &(a, b) in &self.edges {
+            for c in 0..self.colors {
+                clauses.push(Clause {
+                    parts: vec!(ClausePart::NegatedTerm(color(a, c)), ClausePart::NegatedTerm(color(b, c)))
+                });
+            }
+        }
+
+        clauses
+    }
+
+    /// Reads the color of each vertex out of a model of the clauses,
+    /// returning `None` if some vertex has no color.
+    pub fn decode(&self, model: &HashMap<String, bool>) -> Option<Vec<usize>> {
+        (0..self.vertices)
+            .map(|v| first_true(model, self.colors, |c| format!("color_{}_{}", v, c)))
+            .collect()
+    }
+
+    /// Colors the graph, returning the color of each vertex, or `None` if
+    /// there are not enough colors.
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        find_clause_model(&self.to_clauses()).and_then(|m| self.decode(&m))
+    }
+}
+
+impl Pigeonhole {
+    /// Returns clauses whose models are exactly the ways of putting the
+    /// pigeons in the holes.
+    pub fn to_clauses(&self) -> Vec<Clause> {
+        let pigeon = |p: usize, h: usize| format!("pigeon_{}_{}", p, h);
+
+        let mut clauses = vec!();
+        for p in 0..self.pigeons {
+            clauses.append(&mut exactly_one(&(0..self.holes).map(|h| pigeon(p, h)).collect::<Vec<String>>()));
+        }
+        for h in 0..self.holes {
+            clauses.append(&mut at_most_one(&(0..self.pigeons).map(|p| pigeon(p, h)).collect::<Vec<String>>()));
+        }
+
+        clauses
+    }
+
+    /// Reads the hole of each pigeon out of a model of the clauses, returning
+    /// `None` if some pigeon has no hole.
+    pub fn decode(&self, model: &HashMap<String, bool>) -> Option<Vec<usize>> {
+        (0..self.pigeons)
+            .map(|p| first_true(model, self.holes, |h| format!("pigeon_{}_{}", p, h)))
+            .collect()
+    }
+
+    /// Puts the pigeons in the holes, returning the hole of each pigeon, or
+    /// `None` if there are too few holes.
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        find_clause_model(&self.to_clauses()).and_then(|m| self.decode(&m))
+    }
+}
+
+impl PlanningProblem {
+    /// Returns a transition system whose variables are the fluents and whose
+    /// inputs are the actions, where at most one action is taken at each
+    /// step, and whose property is that the goal has not been reached.
+    ///
+    /// A run of the system that breaks the property is a plan, so it can be
+    /// found by `TransitionSystem::check`.
+    pub fn to_transition_system(&self) -> TransitionSystem {
+        let next = |f: &str| term(format!("{{next {}}}", f));
+        let all = |names: &[String]| and_all(names.iter().map(|n| term(n.clone())).collect());
+
+        let mut parts = vec!();
+        for a in &self.actions {
+            let taken = term(a.name.clone());
+            let mut effects: Vec<Proposition> = a.add.iter().map(|f| next(f)).collect();
+            effects.extend(a.delete.iter()
+                .filter(|f| !a.add.contains(f))
+                .map(|f| not(next(f))));
+
+            parts.push(implies(taken.clone(), all(&a.preconditions)));
+            parts.push(implies(taken, and_all(effects)));
+        }
+
+        // A fluent can only change when an action changes it
+        for f in &self.fluents {
+            let changing = |add: bool| or_all(self.actions.iter()
+                .filter(|a| if add {
+                    a.add.contains(f)
+                } else {
+                    a.delete.contains(f) && !a.add.contains(f)
+                })
+                .map(|a| term(a.name.clone()))
+                .collect());
+            let now = term(f.clone());
+
+            parts.push(implies(and(not(now.clone()), next(f)), changing(true)));
+            parts.push(implies(and(now, not(next(f))), changing(false)));
+        }
+
+        parts.push(at_most_k(self.actions.iter().map(|a| term(a.name.clone())).collect(), 1));
+
+        TransitionSystem {
+            variables: self.fluents.iter()
+                .map(|f| (f.clone(), format!("{{next {}}}", f)))
+                .collect(),
+            init: and_all(self.fluents.iter()
+                .map(|f| if self.init.contains(f) { term(f.clone()) } else { not(term(f.clone())) })
+                .collect()),
+            transition: and_all(parts),
+            property: not(all(&self.goal))
+        }
+    }
+
+    /// Returns a proposition whose models are exactly the plans that take
+    /// the given number of steps, where a step can also be spent idle.
+    ///
+    /// The terms are renamed for each step, as in `TransitionSystem::unroll`.
+    pub fn to_proposition(&self, steps: usize) -> Proposition {
+        self.to_transition_system().unroll(steps)
+    }
+
+    /// Reads the actions taken in a plan out of a run of its transition
+    /// system, leaving out the idle steps.
+    pub fn decode(&self, trace: &Trace) -> Vec<String> {
+        // The inputs of the last state are not used by any step
+        let steps = trace.states.len().saturating_sub(1);
+
+        trace.inputs[..steps.min(trace.inputs.len())].iter()
+            .filter_map(|inputs| self.actions.iter()
+                .find(|a| inputs.get(&a.name).cloned().unwrap_or(false)))
+            .map(|a| a.name.clone())
+            .collect()
+    }
+
+    /// Searches for the shortest plan of at most the given number of steps,
+    /// returning its actions, or `None` if there is no such plan.
+    pub fn solve(&self, bound: usize) -> Option<Vec<String>> {
+        self.to_transition_system().check(bound).map(|trace| self.decode(&trace))
+    }
+}
+
+/// Parses a Sudoku puzzle with a row on each line, where each cell is a
+/// digit, with `.` or `0` for an empty cell.
+///
+/// Any other characters, along with lines without any cells, are ignored,
+/// so the boxes can be drawn with `|`, `-` and `+`. There must be 1, 4 or 9
+/// rows, each with as many cells as there are rows.
+pub fn parse_sudoku(input: &str) -> Result<Sudoku, ParseError> {
+    let mut grid = vec!();
+    let mut lines = vec!();
+
+    for (i, line) in input.lines().enumerate() {
+        let row: Vec<Option<usize>> = line.chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.')
+            .map(|c| c.to_digit(10).filter(|&d| d > 0).map(|d| d as usize))
+            .collect();
+
+        if !row.is_empty() {
+            grid.push(row);
+            lines.push(i + 1);
+        }
+    }
+
+    let box_size = (1..4).find(|b| b * b == grid.len());
+    let box_size = match box_size {
+        Some(b) => b,
+        None => return Err(ParseError {
+            message: format!("Expected 1, 4 or 9 rows, but found {}", grid.len()),
+            line: lines.last().map_or(1, |l| l + 1),
+            column: 1
+        })
+    };
+
+    for (row, line) in grid.iter().zip(&lines) {
+        if row.len() != grid.len() {
+            return Err(ParseError {
+                message: format!("Expected {} cells, but found {}", grid.len(), row.len()),
+                line: *line,
+                column: 1
+            })
+        }
+        if row.iter().any(|&v| v.is_some_and(|v| v > grid.len())) {
+            return Err(ParseError {
+                message: format!("Expected the values to be at most {}", grid.len()),
+                line: *line,
+                column: 1
+            })
+        }
+    }
+
+    Ok(Sudoku { box_size, grid })
+}
+
+/// Returns the cells of each row and each column of a grid of the given
+/// size.
+fn lines(size: usize) -> Vec<Vec<(usize, usize)>> {
+    (0..size).map(|r| (0..size).map(|c| (r, c)).collect())
+        .chain((0..size).map(|c| (0..size).map(|r| (r, c)).collect()))
+        .collect()
+}
+
+/// Returns clauses that fill in each cell of the grid with exactly one
+/// value, keeping the values already given, so that each group of cells
+/// holds each value exactly once.
+fn grid_clauses(grid: &[Vec<Option<usize>>], groups: &[Vec<(usize, usize)>]) -> Vec<Clause> {
+    let size = grid.len();
+    let cell = |r: usize, c: usize, v: usize| format!("cell_{}_{}_{}", r, c, v);
+
+    let mut clauses = vec!();
+    for (r, row) in grid.iter().enumerate() {
+        for c in 0..size {
+            clauses.append(&mut exactly_one(&(1..=size).map(|v| cell(r, c, v)).collect::<Vec<String>>()));
+
+            match row.get(c).cloned().unwrap_or(None) {
+                Some(v) if v >= 1 && v <= size => clauses.push(Clause {
+                    parts: vec!(ClausePart::Term(cell(r, c, v)))
+                }),
+                Some(_) => clauses.push(Clause { parts: vec!() }),
+                None => {}
+            }
+        }
+    }
+
+    for group in groups {
+        for v in 1..=size {
+            clauses.append(&mut exactly_one(&group.iter().map(|&(r, c)| cell(r, c, v)).collect::<Vec<String>>()));
+        }
+    }
+
+    clauses
+}
+
+/// Reads the value of each cell of a grid of the given size out of a model.
+fn decode_grid(size: usize, model: &HashMap<String, bool>) -> Option<Vec<Vec<usize>>> {
+    (0..size)
+        .map(|r| (0..size)
+            .map(|c| first_true(model, size, |v| format!("cell_{}_{}_{}", r, c, v + 1)).map(|v| v + 1))
+            .collect())
+        .collect()
+}
+
+/// Returns the first of the given number of options whose term is true in
+/// the model.
+fn first_true<F: Fn(usize) -> String>(model: &HashMap<String, bool>, options: usize, name: F) -> Option<usize> {
+    (0..options).find(|&i| model.get(&name(i)).cloned().unwrap_or(false))
+}
+
+/// Returns clauses that make at most one of the given terms true, counting
+/// them with the default cardinality encoding.
+fn at_most_one(names: &[String]) -> Vec<Clause> {
+    Clause::from_proposition(at_most_k(names.iter().map(|n| term(n.clone())).collect(), 1))
+}
+
+/// Returns clauses that make exactly one of the given terms true, counting
+/// them with the default cardinality encoding.
+fn exactly_one(names: &[String]) -> Vec<Clause> {
+    Clause::from_proposition(exactly_k(names.iter().map(|n| term(n.clone())).collect(), 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use models::*;
+    use puzzles::*;
+
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn sudoku_solves() {
+        let sudoku = parse_sudoku("\
+            53..7....
+            6..195...
+            .98....6.
+            8...6...3
+            4..8.3..1
+            7...2...6
+            .6....28.
+            ...419..5
+            ....8..79").unwrap();
+
+        let grid = sudoku.solve().unwrap();
+
+        for (given, row) in sudoku.grid.iter().zip(&grid) {
+            for (g, v) in given.iter().zip(row) {
+                assert!(g.is_none_or(|g| g == *v));
+            }
+        }
+
+        let groups = (0..9).flat_map(|i| vec!(
+            (0..9).map(|j| grid[i][j]).collect::<HashSet<usize>>(),
+            (0..9).map(|j| grid[j][i]).collect::<HashSet<usize>>(),
+            (0..9).map(|j| grid[i / 3 * 3 + j / 3][i % 3 * 3 + j % 3]).collect::<HashSet<usize>>()
+        ));
+        for group in groups {
+            assert_eq!(group, (1..10).collect());
+        }
+    }
+
+    #[test]
+    fn sudoku_errors() {
+        let sudoku = parse_sudoku("12..\n....\n....\n...1\n2...").unwrap_err();
+        assert_eq!(sudoku, ParseError {
+            message: "Expected 1, 4 or 9 rows, but found 5".to_string(),
+            line: 6,
+            column: 1
+        });
+
+        let sudoku = parse_sudoku("1.1.\n....\n....\n....").unwrap();
+        assert_eq!(sudoku.solve(), None);
+
+        let error = parse_sudoku("12.\n....\n....\n....").unwrap_err();
+        assert_eq!(error.message, "Expected 4 cells, but found 3");
+
+        let error = parse_sudoku("5...\n....\n....\n....").unwrap_err();
+        assert_eq!(error.message, "Expected the values to be at most 4");
+    }
+
+    #[test]
+    fn n_queens_counts() {
+        let counts: Vec<u128> = (1..9)
+            .map(|n| count_models(&NQueens { size: n }.to_clauses()).unwrap())
+            .collect();
+
+        assert_eq!(counts, vec!(1, 0, 0, 2, 10, 4, 40, 92));
+    }
+
+    #[test]
+    fn n_queens_decodes() {
+        let queens = NQueens { size: 10 };
+        let columns = queens.solve().unwrap();
+
+        for (r, &c) in columns.iter().enumerate() {
+            for (s, &d) in columns.iter().enumerate().skip(r + 1) {
+                assert_ne!(c, d);
+                assert_ne!(s - r, c.abs_diff(d));
+            }
+        }
+    }
+
+    #[test]
+    fn graph_coloring_petersen() {
+        let mut edges = vec!();
+        for i in 0..5 {
+            edges.push((i, (i + 1) % 5));
+            edges.push((i, i + 5));
+            edges.push((i + 5, (i + 2) % 5 + 5));
+        }
+        let mut coloring = GraphColoring { vertices: 10, edges, colors: 2 };
+
+        assert_eq!(coloring.solve(), None);
+
+        coloring.colors = 3;
+        let colors = coloring.solve().unwrap();
+        assert!(colors.iter().all(|&c| c < 3));
+        assert!(coloring.edges.iter().all(|&(a, b)| colors[a] != colors[b]));
+    }
+
+    #[test]
+    fn pigeonhole_refutes() {
+        for holes in 1..5 {
+            assert_eq!(Pigeonhole { pigeons: holes + 1, holes }.solve(), None);
+
+            let pigeons = Pigeonhole { pigeons: holes, holes }.solve().unwrap();
+            let distinct: HashSet<&usize> = pigeons.iter().collect();
+            assert_eq!(distinct.len(), holes);
+        }
+    }
+
+    #[test]
+    fn latin_square_counts() {
+        let counts: Vec<u128> = (1..5)
+            .map(|n| count_models(&LatinSquare { grid: vec!(vec!(None; n); n) }.to_clauses()).unwrap())
+            .collect();
+
+        assert_eq!(counts, vec!(1, 2, 12, 576));
+    }
+
+    #[test]
+    fn planning_blocks_world() {
+        // Block a starts on b, and the goal is to put b on a
+        let action = |name: String, preconditions: Vec<String>, add: Vec<String>, delete: Vec<String>| Action {
+            name, preconditions, add, delete
+        };
+        let names = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<String>>();
+
+        let mut actions = vec!();
+        for &(x, y) in &[("a", "b"), ("b", "a")] {
+            actions.push(action(
+                format!("unstack_{}_{}", x, y),
+                names(&[&format!("{}_on_{}", x, y), &format!("clear_{}", x)]),
+                names(&[&format!("{}_on_table", x), &format!("clear_{}", y)]),
+                names(&[&format!("{}_on_{}", x, y)])
+            ));
+            actions.push(action(
+                format!("stack_{}_{}", x, y),
+                names(&[&format!("{}_on_table", x), &format!("clear_{}", x), &format!("clear_{}", y)]),
+                names(&[&format!("{}_on_{}", x, y)]),
+                names(&[&format!("{}_on_table", x), &format!("clear_{}", y)])
+            ));
+        }
+
+        let problem = PlanningProblem {
+            fluents: names(&["a_on_b", "b_on_a", "a_on_table", "b_on_table", "clear_a", "clear_b"]),
+            actions,
+            init: names(&["a_on_b", "b_on_table", "clear_a"]),
+            goal: names(&["b_on_a"])
+        };
+
+        assert_eq!(problem.solve(1), None);
+        assert_eq!(problem.solve(4), Some(names(&["unstack_a_b", "stack_b_a"])));
+
+        // Idle steps are left out
+        let taken = |name: &str| problem.actions.iter()
+            .map(|a| (a.name.clone(), a.name == name))
+            .collect();
+        let trace = Trace {
+            states: vec!(HashMap::new(); 4),
+            inputs: vec!(taken("unstack_a_b"), taken(""), taken("stack_b_a"), taken("unstack_b_a"))
+        };
+        assert_eq!(problem.decode(&trace), names(&["unstack_a_b", "stack_b_a"]));
+    }
+}
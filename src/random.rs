@@ -0,0 +1,19 @@
+/// Generates pseudorandom numbers from a fixed seed with a linear
+/// congruential generator, so that the tests that use them are repeatable.
+pub(crate) struct Random {
+    state: u64
+}
+
+impl Random {
+    pub(crate) fn new(seed: u64) -> Random {
+        Random { state: seed }
+    }
+
+    /// Returns a number below the given bound.
+    pub(crate) fn next(&mut self, bound: u64) -> u64 {
+        self.state = self.state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.state >> 33) % bound
+    }
+}
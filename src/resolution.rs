@@ -1,16 +1,24 @@
 extern crate multimap;
 
 use clauses::*;
+use cores::*;
+use horn::*;
+use proofs::*;
 use propositions::*;
 
 use self::multimap::MultiMap;
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
 
 /// Checks if the given goal proposition is provable via the given assumptions.
 ///
-/// Uses a resolution algorithm to prove provability. Can incorrectly return
-/// false in some cases where the goal statement is a tautology.
+/// The goal is provable when the clauses of the assumptions and the negated
+/// goal cannot all be satisfied. If they only break down into Horn clauses,
+/// a goal that is a single term is first looked for by backward chaining,
+/// and otherwise forward chaining finds the answer in time linear in the
+/// size of the clauses. Otherwise, the clauses are resolved against each
+/// other in the same way as `prove`, so a goal is provable exactly when
+/// `prove` finds a proof of it.
 ///
 /// ```
 /// use resolution_prover::*;
@@ -33,122 +41,361 @@ use std::collections::HashSet;
 /// assert_eq!(resolve(assumptions, goal), true);
 /// ```
 pub fn resolve(assumptions: Vec<Proposition>, goal: Proposition) -> bool {
-    let assumption_clauses: Vec<Clause> = assumptions.iter()
-        .flat_map(|a| Clause::from_proposition(a.clone()))
-        .collect();
-
-    let mut clauses = ClauseStorage::new();
-    assumption_clauses.into_iter()
-        .for_each(|c| clauses.put(c));
-
-    let negated_goal = not(goal);
-    let neg_goal_clauses = Clause::from_proposition(negated_goal);
+    resolve_encoded(assumptions, goal, CardinalityEncoding::default())
+}
 
-    for c in neg_goal_clauses.clone() {
-        let others: Vec<Clause> = neg_goal_clauses.clone().into_iter()
-            .filter(|v| *v != c)
-            .collect();
+fn resolve_encoded(assumptions: Vec<Proposition>, goal: Proposition, encoding: CardinalityEncoding) -> bool {
+    let (mut clauses, first_supported) = store_clauses(&assumptions, &goal, encoding);
+
+    if is_horn(&clauses.clauses) {
+        // A goal that is a single term is looked for by working backwards
+        // from it, which only visits the rules that can lead to it
+        let derived = match goal {
+            Proposition::Term(ref a) => backward_chain(&clauses.clauses, a).is_some(),
+            _ => false
+        };
+        derived || forward_chain(&clauses.clauses).contradiction.is_some()
+    } else {
+        saturate(&mut clauses, first_supported).is_some()
+    }
+}
 
-        let mut all_clauses = clauses.clone();
-        others.into_iter()
-            .for_each(|c| all_clauses.put(c));
+/// Settings that control how `resolve_with` checks a goal.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct ResolveOptions {
+    /// Whether the assumptions are first checked to be consistent, since
+    /// every goal follows from contradictory assumptions.
+    pub check_consistency: bool,
+    /// How linear constraints in the assumptions and goal are broken into
+    /// clauses.
+    pub cardinality_encoding: CardinalityEncoding
+}
 
-        let mut visited = HashSet::new();
-        visited.insert(&c);
+/// The result of checking whether a goal follows from a set of assumptions.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum ResolveOutcome {
+    /// The goal follows from the assumptions.
+    Provable,
+    /// The goal could not be shown to follow from the assumptions.
+    NotProvable,
+    /// The assumptions contradict each other, so any goal would follow from
+    /// them. Holds the indices of a minimal set of assumptions that are
+    /// contradictory together.
+    InconsistentAssumptions(Vec<usize>)
+}
 
-        if resolve_(&all_clauses, &c, visited) {
-            return true
+/// Checks if the given goal proposition is provable via the given
+/// assumptions, using the given options.
+///
+/// Without any options this is the same as `resolve`. When
+/// `check_consistency` is set, contradictory assumptions are reported along
+/// with a minimal subset of them that conflict, rather than proving the
+/// goal. The `cardinality_encoding` chooses how linear constraints, such as
+/// `[p + q + r <= 1]`, are broken into clauses.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let assumptions = vec!(
+///     parse_proposition("admin -> allowed").unwrap(),
+///     parse_proposition("guest -> ~allowed").unwrap(),
+///     parse_proposition("admin").unwrap(),
+///     parse_proposition("guest").unwrap(),
+/// );
+///
+/// let goal = parse_proposition("deleted").unwrap();
+///
+/// let options = ResolveOptions { check_consistency: true, ..ResolveOptions::default() };
+///
+/// assert_eq!(
+///     resolve_with(assumptions.clone(), goal.clone(), options),
+///     ResolveOutcome::InconsistentAssumptions(vec!(0, 1, 2, 3))
+/// );
+/// assert_eq!(
+///     resolve_with(assumptions, goal, ResolveOptions::default()),
+///     ResolveOutcome::Provable
+/// );
+/// ```
+pub fn resolve_with(assumptions: Vec<Proposition>, goal: Proposition, options: ResolveOptions) -> ResolveOutcome {
+    if options.check_consistency {
+        if let Some(conflict) = minimal_unsatisfiable_subset(&assumptions) {
+            return ResolveOutcome::InconsistentAssumptions(conflict)
         }
     }
-    false
+
+    if resolve_encoded(assumptions, goal, options.cardinality_encoding) {
+        ResolveOutcome::Provable
+    } else {
+        ResolveOutcome::NotProvable
+    }
 }
 
-fn resolve_(clauses: &ClauseStorage, current: &Clause, visited: HashSet<&Clause>) -> bool {
-    for p in current.parts.clone() {
-        let matches = clauses.get(&p.negate(), &visited);
-        for m in matches {
-            let next = combine(current, m);
+/// Attempts to prove the given goal proposition from the given assumptions,
+/// returning the found resolution refutation if one exists.
+///
+/// The clauses of the negated goal are resolved against the clauses of the
+/// assumptions and against the clauses derived from them, smallest first,
+/// until the empty clause is reached or no new clauses can be derived. Once
+/// the clauses of the negated goal run out, the clauses of the assumptions
+/// are resolved against each other as well, so a proof is always found when
+/// one exists.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let assumptions = vec!(
+///     term("p".to_string()),
+///     implies(term("p".to_string()), term("q".to_string()))
+/// );
+///
+/// let goal = term("q".to_string());
+///
+/// let proof = prove(assumptions, goal).unwrap();
+///
+/// assert_eq!(proof.conclusion().parts.len(), 0);
+/// ```
+pub fn prove(assumptions: Vec<Proposition>, goal: Proposition) -> Option<Proof> {
+    let (mut clauses, first_supported) = store_clauses(&assumptions, &goal, CardinalityEncoding::default());
+    let empty = saturate(&mut clauses, first_supported)?;
+
+    Some(Proof {
+        steps: build_steps(&clauses, empty),
+        assumptions,
+        goal,
+    })
+}
 
-            if next.parts.len() == 0 {
-                return true
-            }
+/// Stores the clauses of the assumptions followed by the clauses of the
+/// negated goal, returning the index of the first clause of the negated goal.
+fn store_clauses(assumptions: &[Proposition], goal: &Proposition, encoding: CardinalityEncoding) -> (ClauseStorage, usize) {
+    let mut clauses = ClauseStorage::new();
+    for (i, a) in assumptions.iter().enumerate() {
+        Clause::from_proposition_with(a.clone(), encoding).into_iter()
+            .for_each(|c| { clauses.put(c, Justification::Assumption(i), false); });
+    }
+
+    let first_supported = clauses.clauses.len();
+    Clause::from_proposition_with(not(goal.clone()), encoding).into_iter()
+        .for_each(|c| { clauses.put(c, Justification::NegatedGoal, true); });
 
-            let mut new_visited = visited.clone();
-            new_visited.insert(&next);
+    (clauses, first_supported)
+}
+
+/// Resolves the stored clauses against each other until the empty clause is
+/// derived, returning its index, or until no new clauses can be derived.
+///
+/// The clauses from the given index onwards are the clauses of the negated
+/// goal, and the clauses of the assumptions come before them. Each clause
+/// waits until it is given, and is then resolved against every active clause
+/// before becoming active itself. The clauses of the assumptions start out
+/// active, and are only given once no other clauses are waiting, so that the
+/// search starts from the goal but still finds contradictions among the
+/// assumptions. Tautologies and clauses containing an already stored clause
+/// are never needed to reach the empty clause, so they are not stored.
+fn saturate(clauses: &mut ClauseStorage, first_supported: usize) -> Option<usize> {
+    if let Some(i) = clauses.clauses.iter().position(|c| c.parts.is_empty()) {
+        return Some(i)
+    }
 
-            if resolve_(clauses, &next, new_visited) {
-                return true
+    // Waiting clauses are given shortest first, and oldest first among those
+    // of the same length
+    let mut waiting: BTreeSet<(usize, usize)> = (first_supported..clauses.clauses.len())
+        .map(|i| (clauses.clauses[i].parts.len(), i))
+        .collect();
+    for i in 0..first_supported {
+        clauses.activate(i);
+    }
+    let mut assumptions = 0..first_supported;
+
+    loop {
+        let given = match waiting.iter().next().cloned() {
+            Some(next) => {
+                waiting.remove(&next);
+                next.1
+            },
+            None => assumptions.next()?
+        };
+
+        for p in clauses.clauses[given].parts.clone() {
+            for other in clauses.active_with(&p.negate()) {
+                // Clauses that come from the negated goal go on the left,
+                // preferring the given clause if it was derived, so that the
+                // proof reads as a chain starting from the goal
+                let given_first = clauses.supported[given] && clauses.is_derived(given);
+                let (left, right, pivot) = if clauses.supported[other] && !given_first {
+                    (other, given, p.negate())
+                } else {
+                    (given, other, p.clone())
+                };
+                let resolvent = combine(&clauses.clauses[left], &clauses.clauses[right], &pivot);
+
+                if resolvent.parts.is_empty() || !(is_tautology(&resolvent) || clauses.subsumes(&resolvent)) {
+                    let supported = clauses.supported[left] || clauses.supported[right];
+                    let index = clauses.put(
+                        resolvent,
+                        Justification::Resolution { left, right, pivot },
+                        supported
+                    );
+
+                    if clauses.clauses[index].parts.is_empty() {
+                        return Some(index)
+                    }
+                    waiting.insert((clauses.clauses[index].parts.len(), index));
+                }
             }
         }
+
+        if given >= first_supported {
+            clauses.activate(given);
+        }
     }
-    false
 }
 
-fn combine(a: &Clause, b: &Clause) -> Clause {
-    let mut all_parts = HashSet::new();
+/// Converts the stored derivation of the clause at the given index into the
+/// steps of a proof, placing each clause after the clauses it was resolved
+/// from and leaving out clauses that the derivation does not use.
+fn build_steps(clauses: &ClauseStorage, conclusion: usize) -> Vec<ProofStep> {
+    let mut steps = vec!();
+    let mut step_indices: HashMap<usize, usize> = HashMap::new();
+
+    // Each entry holds a stored clause along with whether the clauses it was
+    // resolved from have already been added. The derivations can be deeper
+    // than the call stack allows, so the stack is kept here.
+    let mut stack = vec!((conclusion, false));
+    while let Some((i, parents_added)) = stack.pop() {
+        if step_indices.contains_key(&i) {
+            continue
+        }
 
-    for p in a.parts.clone() {
-        all_parts.insert(p);
-    }
-    for p in b.parts.clone() {
-        all_parts.insert(p);
+        let justification = match clauses.justifications[i] {
+            Justification::Resolution { left, right, .. } if !parents_added => {
+                stack.push((i, true));
+                stack.push((right, false));
+                stack.push((left, false));
+                continue
+            },
+            Justification::Resolution { left, right, ref pivot } =>
+                Justification::Resolution {
+                    left: step_indices[&left],
+                    right: step_indices[&right],
+                    pivot: pivot.clone(),
+                },
+            ref j => j.clone(),
+        };
+
+        steps.push(ProofStep {
+            clause: clauses.clauses[i].clone(),
+            justification,
+        });
+        step_indices.insert(i, steps.len() - 1);
     }
 
-    for ap in a.parts.clone() {
-        for bp in b.parts.clone() {
-            if ap.negate() == bp {
-                all_parts.remove(&ap);
-                all_parts.remove(&bp);
-            }
+    steps
+}
+
+/// Resolves the two given clauses on the given literal of the first clause,
+/// which must appear negated in the second clause.
+pub(crate) fn combine(a: &Clause, b: &Clause, pivot: &ClausePart) -> Clause {
+    let negated_pivot = pivot.negate();
+    let mut parts: Vec<ClausePart> = vec!();
+
+    let remaining = a.parts.iter().filter(|p| *p != pivot)
+        .chain(b.parts.iter().filter(|p| **p != negated_pivot));
+    for p in remaining {
+        if !parts.contains(p) {
+            parts.push(p.clone());
         }
     }
 
     Clause {
-        parts: all_parts.iter()
-            .map(|p| p.clone())
-            .collect()
+        parts
     }
 }
 
+/// Checks whether the given clause contains both a term and its negation.
+fn is_tautology(clause: &Clause) -> bool {
+    clause.parts.iter()
+        .any(|p| clause.parts.contains(&p.negate()))
+}
+
+/// The clauses found while searching for a refutation, along with how each
+/// one was found and whether it comes from the negated goal.
+///
+/// Every stored clause can be looked up by its parts, while the active
+/// clauses, which are the ones that new clauses are resolved against, are
+/// looked up separately.
 #[derive(Clone)]
 #[derive(Debug)]
 struct ClauseStorage {
     lookup_table: MultiMap<ClausePart, usize>,
-    clauses: Vec<Clause>
+    active_table: MultiMap<ClausePart, usize>,
+    clauses: Vec<Clause>,
+    justifications: Vec<Justification>,
+    supported: Vec<bool>
 }
 
 impl ClauseStorage {
     fn new() -> ClauseStorage {
         ClauseStorage {
             lookup_table: MultiMap::new(),
+            active_table: MultiMap::new(),
             clauses: vec!(),
+            justifications: vec!(),
+            supported: vec!(),
         }
     }
 
-    fn get(&self, part: &ClausePart, visited: &HashSet<&Clause>) -> Vec<&Clause> {
-        match self.lookup_table.get_vec(part) {
-            Some(indices) => {
-                indices.iter()
-                    .map(|i| &self.clauses[*i])
-                    .filter(|v| !visited.contains(v))
-                    .collect()
-            }
+    /// Returns the indices of the active clauses containing the given part.
+    fn active_with(&self, part: &ClausePart) -> Vec<usize> {
+        match self.active_table.get_vec(part) {
+            Some(indices) => indices.clone(),
             None => vec!()
         }
     }
 
-    fn put(&mut self, clause: Clause) {
+    /// Checks whether every part of some stored clause appears in the given
+    /// clause.
+    fn subsumes(&self, clause: &Clause) -> bool {
+        clause.parts.iter()
+            .filter_map(|p| self.lookup_table.get_vec(p))
+            .flat_map(|indices| indices.iter())
+            .any(|i| self.clauses[*i].parts.iter().all(|p| clause.parts.contains(p)))
+    }
+
+    fn put(&mut self, clause: Clause, justification: Justification, supported: bool) -> usize {
         let index = self.clauses.len();
 
         clause.parts.iter()
             .for_each(|p| self.lookup_table.insert((*p).clone(), index));
 
         self.clauses.push(clause);
+        self.justifications.push(justification);
+        self.supported.push(supported);
+
+        index
+    }
+
+    fn is_derived(&self, index: usize) -> bool {
+        matches!(self.justifications[index], Justification::Resolution { .. })
+    }
+
+    fn activate(&mut self, index: usize) {
+        for p in &self.clauses[index].parts {
+            self.active_table.insert(p.clone(), index);
+        }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
+    use parser::*;
     use propositions::*;
     use resolution::*;
 
@@ -213,6 +460,196 @@ mod tests {
         assert_eq!(resolve(assumptions, goal), false);
     }
 
+    #[test]
+    fn combine_single_pivot() {
+        let a = Clause { parts: vec!(
+            ClausePart::Term("p".to_string()),
+            ClausePart::Term("q".to_string())
+        ) };
+        let b = Clause { parts: vec!(
+            ClausePart::NegatedTerm("p".to_string()),
+            ClausePart::NegatedTerm("q".to_string())
+        ) };
+
+        let expected = Clause { parts: vec!(
+            ClausePart::Term("q".to_string()),
+            ClausePart::NegatedTerm("q".to_string())
+        ) };
+
+        assert_eq!(combine(&a, &b, &ClausePart::Term("p".to_string())), expected);
+    }
+
+    #[test]
+    fn prove_resolves_derived_clauses() {
+        // No chain from the negated goal through the input clauses alone
+        // reaches the empty clause, so derived clauses must be resolved with
+        // each other
+        let assumptions = vec!(
+            or(term("p".to_string()), term("q".to_string())),
+            or(not(term("p".to_string())), term("q".to_string())),
+            or(term("p".to_string()), not(term("q".to_string())))
+        );
+
+        let goal = and(term("p".to_string()), term("q".to_string()));
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        assert_eq!(proof.conclusion().parts.len(), 0);
+        for (i, step) in proof.steps.iter().enumerate() {
+            if let Justification::Resolution { left, right, ref pivot } = step.justification {
+                assert!(left < i && right < i);
+                assert_eq!(combine(&proof.steps[left].clause, &proof.steps[right].clause, pivot), step.clause);
+            }
+        }
+    }
+
+    #[test]
+    fn prove_contradictory_clauses() {
+        let assumptions = vec!(
+            or(term("p".to_string()), term("q".to_string())),
+            or(not(term("p".to_string())), term("q".to_string())),
+            or(term("p".to_string()), not(term("q".to_string()))),
+            or(not(term("p".to_string())), not(term("q".to_string())))
+        );
+
+        let proof = prove(assumptions, term("r".to_string())).unwrap();
+
+        assert_eq!(proof.used_assumptions(), vec!(0, 1, 2, 3));
+        assert!(proof.steps.iter().all(|s| s.justification != Justification::NegatedGoal));
+    }
+
+    #[test]
+    fn prove_not_provable() {
+        let assumptions = vec!(
+            or(term("p".to_string()), term("q".to_string())),
+            or(not(term("p".to_string())), term("q".to_string()))
+        );
+
+        let goal = and(term("p".to_string()), term("q".to_string()));
+
+        assert_eq!(prove(assumptions, goal), None);
+    }
+
+    #[test]
+    fn prove_long_chain() {
+        let n = 2000;
+        let mut assumptions = vec!(term("p0".to_string()));
+        for i in 0..n {
+            assumptions.push(implies(term(format!("p{}", i)), term(format!("p{}", i + 1))));
+        }
+
+        let proof = prove(assumptions, term(format!("p{}", n))).unwrap();
+
+        assert_eq!(proof.used_assumptions().len(), n + 1);
+    }
+
+    #[test]
+    fn resolve_top_goal() {
+        assert!(resolve(vec!(), top()));
+    }
+
+    #[test]
+    fn resolve_bottom_goal() {
+        let assumptions = vec!(
+            term("a".to_string())
+        );
+
+        assert!(!resolve(assumptions, bottom()));
+    }
+
+    #[test]
+    fn resolve_bottom_assumption() {
+        let assumptions = vec!(
+            or(term("a".to_string()), term("b".to_string())),
+            bottom()
+        );
+
+        let goal = term("c".to_string());
+
+        assert!(resolve(assumptions, goal));
+    }
+
+    #[test]
+    fn prove_bottom_assumption() {
+        let assumptions = vec!(
+            or(term("a".to_string()), term("b".to_string())),
+            and(term("c".to_string()), bottom())
+        );
+
+        let goal = term("d".to_string());
+
+        let proof = prove(assumptions, goal).unwrap();
+
+        assert_eq!(proof.steps.len(), 1);
+        assert_eq!(proof.steps[0].justification, Justification::Assumption(1));
+    }
+
+    #[test]
+    fn resolve_and_all_or_all() {
+        let assumptions = vec!(
+            and_all(vec!(
+                term("a".to_string()),
+                term("b".to_string()),
+                term("c".to_string())
+            ))
+        );
+
+        let goal = or_all(vec!(
+            not(term("a".to_string())),
+            term("c".to_string())
+        ));
+
+        assert!(resolve(assumptions, goal));
+    }
+
+    #[test]
+    fn resolve_xor_chain() {
+        let assumptions = vec!(
+            xor(
+                xor(term("a".to_string()), term("b".to_string())),
+                term("c".to_string())
+            ),
+            term("a".to_string()),
+            term("b".to_string())
+        );
+
+        let goal = term("c".to_string());
+
+        assert!(resolve(assumptions, goal));
+    }
+
+    #[test]
+    fn resolve_xor_chain_false() {
+        let assumptions = vec!(
+            xor(
+                xor(term("a".to_string()), term("b".to_string())),
+                term("c".to_string())
+            ),
+            term("a".to_string())
+        );
+
+        let goal = term("c".to_string());
+
+        assert!(!resolve(assumptions, goal));
+    }
+
+    #[test]
+    fn resolve_ite_nand_nor() {
+        let assumptions = vec!(
+            ite(
+                term("c".to_string()),
+                nand(term("a".to_string()), term("b".to_string())),
+                nor(term("a".to_string()), term("b".to_string()))
+            ),
+            term("a".to_string()),
+            term("b".to_string())
+        );
+
+        let goal = bottom();
+
+        assert!(resolve(assumptions, goal));
+    }
+
     #[test]
     fn resolve_taut_or_not() {
         let assumptions = vec!();
@@ -224,4 +661,108 @@ mod tests {
 
         assert_eq!(resolve(assumptions, goal), true);
     }
+
+    #[test]
+    fn resolve_horn_contradictory_assumptions() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(term("p".to_string()), term("q".to_string())),
+            not(term("q".to_string()))
+        );
+
+        let goal = term("r".to_string());
+
+        assert_eq!(resolve(assumptions, goal), true);
+    }
+
+    #[test]
+    fn resolve_with_consistent_assumptions() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            implies(term("p".to_string()), term("q".to_string()))
+        );
+        let options = ResolveOptions { check_consistency: true, ..ResolveOptions::default() };
+
+        assert_eq!(
+            resolve_with(assumptions.clone(), term("q".to_string()), options),
+            ResolveOutcome::Provable
+        );
+        assert_eq!(
+            resolve_with(assumptions, term("r".to_string()), options),
+            ResolveOutcome::NotProvable
+        );
+    }
+
+    #[test]
+    fn resolve_with_inconsistent_assumptions() {
+        let assumptions = vec!(
+            term("p".to_string()),
+            or(term("q".to_string()), term("r".to_string())),
+            not(term("q".to_string())),
+            implies(term("r".to_string()), not(term("p".to_string()))),
+            term("s".to_string())
+        );
+        let options = ResolveOptions { check_consistency: true, ..ResolveOptions::default() };
+
+        assert_eq!(
+            resolve_with(assumptions, term("s".to_string()), options),
+            ResolveOutcome::InconsistentAssumptions(vec!(0, 1, 2, 3))
+        );
+    }
+
+    #[test]
+    fn resolve_with_linear_constraints() {
+        // At most two of the four shifts can be staffed, morning and evening
+        // are both needed, and night needs someone on call
+        let assumptions = vec!(
+            at_most_k(vec!(
+                term("morning".to_string()),
+                term("day".to_string()),
+                term("evening".to_string()),
+                term("night".to_string())
+            ), 2),
+            term("morning".to_string()),
+            term("evening".to_string()),
+            implies(term("night".to_string()), term("on_call".to_string()))
+        );
+        let goal = and(not(term("day".to_string())), not(term("night".to_string())));
+
+        for &encoding in &[
+            CardinalityEncoding::SequentialCounter,
+            CardinalityEncoding::Totalizer,
+            CardinalityEncoding::SortingNetwork
+        ] {
+            let options = ResolveOptions { cardinality_encoding: encoding, ..ResolveOptions::default() };
+
+            assert_eq!(
+                resolve_with(assumptions.clone(), goal.clone(), options),
+                ResolveOutcome::Provable
+            );
+            assert_eq!(
+                resolve_with(assumptions.clone(), term("on_call".to_string()), options),
+                ResolveOutcome::NotProvable
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_agrees_with_prove() {
+        let assumptions = vec!(
+            parse_proposition("[300 a + 200 b + 100 c <= 350]").unwrap(),
+            parse_proposition("a \\/ b").unwrap(),
+            parse_proposition("b -> c \\/ d").unwrap(),
+        );
+        let goals = vec!("~(c)", "a", "d", "~(a /\\ b)", "c -> a");
+
+        for goal in goals {
+            let goal = parse_proposition(goal).unwrap();
+
+            assert_eq!(
+                resolve(assumptions.clone(), goal.clone()),
+                prove(assumptions.clone(), goal.clone()).is_some(),
+                "{}",
+                goal
+            );
+        }
+    }
 }
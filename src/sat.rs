@@ -0,0 +1,567 @@
+use clauses::*;
+use proofs::*;
+use propositions::*;
+use resolution::*;
+
+use std::collections::HashMap;
+
+/// Searches for an assignment that satisfies all of the given clauses,
+/// returning one if the clauses are satisfiable.
+///
+/// Uses the DPLL procedure with unit propagation, branching on a term of the
+/// shortest clause that is not yet satisfied. The search always gives the
+/// correct answer, so a missing model means the clauses really are
+/// unsatisfiable. Every term in the clauses is given a value.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let clauses = Clause::from_proposition(
+///     and(
+///         or(term("p".to_string()), term("q".to_string())),
+///         not(term("p".to_string()))
+///     )
+/// );
+///
+/// let model = find_clause_model(&clauses).unwrap();
+///
+/// assert_eq!(model["p"], false);
+/// assert_eq!(model["q"], true);
+/// ```
+pub fn find_clause_model(clauses: &[Clause]) -> Option<HashMap<String, bool>> {
+    Solver::new(clauses).find_model()
+}
+
+/// Searches for an assignment to the terms of the given propositions that
+/// makes all of them true.
+///
+/// The returned model only contains the terms that appear in the
+/// propositions, leaving out any terms introduced while breaking them into
+/// clauses.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let p = term("p".to_string());
+/// let q = term("q".to_string());
+///
+/// let model = find_model(&[xor(p.clone(), q.clone()), p.clone()]).unwrap();
+///
+/// assert_eq!(model["p"], true);
+/// assert_eq!(model["q"], false);
+///
+/// assert_eq!(find_model(&[p.clone(), not(p)]), None);
+/// ```
+pub fn find_model(props: &[Proposition]) -> Option<HashMap<String, bool>> {
+    let clauses: Vec<Clause> = props.iter()
+        .flat_map(|p| Clause::from_proposition(p.clone()))
+        .collect();
+
+    let model = find_clause_model(&clauses)?;
+
+    Some(props.iter()
+        .flat_map(|p| p.terms())
+        .map(|t| {
+            let value = model.get(&t).cloned().unwrap_or(false);
+            (t, value)
+        })
+        .collect())
+}
+
+/// Checks whether the two given propositions are equivalent, meaning that
+/// they take the same value under every assignment to their terms.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let a = parse_proposition("~(p /\\ q)").unwrap();
+/// let b = parse_proposition("~p \\/ ~q").unwrap();
+/// let c = parse_proposition("~p /\\ ~q").unwrap();
+///
+/// assert!(equivalent(&a, &b));
+/// assert!(!equivalent(&a, &c));
+/// ```
+pub fn equivalent(a: &Proposition, b: &Proposition) -> bool {
+    find_clause_model(&Clause::from_proposition_defined(xor(a.clone(), b.clone()))).is_none()
+}
+
+/// Searches for a resolution proof that the given propositions contradict
+/// each other, returning `None` if they can all be true at once.
+///
+/// The proof is the one that the DPLL search follows while finding that
+/// there is no model. Each operand of a connective in an assumption that is
+/// not already a possibly negated term is given its own term, named after
+/// the operand in braces, so that no conjunction is ever distributed over a
+/// disjunction. The goal of the proof is `false`.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let props = vec!(
+///     parse_proposition("p <+> q").unwrap(),
+///     parse_proposition("p <-> q").unwrap(),
+///     parse_proposition("r").unwrap()
+/// );
+///
+/// let proof = refute(&props).unwrap();
+///
+/// assert_eq!(proof.conclusion().parts.len(), 0);
+/// assert_eq!(proof.used_assumptions(), vec!(0, 1));
+///
+/// assert!(refute(&props[1..]).is_none());
+/// ```
+pub fn refute(props: &[Proposition]) -> Option<Proof> {
+    let mut clauses = vec!();
+    let mut origins = vec!();
+    for (i, p) in props.iter().enumerate() {
+        for c in Clause::from_proposition_defined(p.clone()) {
+            clauses.push(c);
+            origins.push(i);
+        }
+    }
+
+    let mut steps = Solver::new(&clauses).refute()?;
+    for step in &mut steps {
+        if let Justification::Assumption(ref mut i) = step.justification {
+            *i = origins[*i];
+        }
+    }
+
+    Some(Proof {
+        assumptions: props.to_vec(),
+        goal: bottom(),
+        steps
+    })
+}
+
+/// A literal over the numbered terms of a solver, which is true when the
+/// term has the given value.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+struct Literal {
+    term: usize,
+    positive: bool
+}
+
+/// The state of a DPLL search, which can be given more clauses between
+/// searches.
+pub(crate) struct Solver {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+    clauses: Vec<Vec<Literal>>,
+    values: Vec<Option<bool>>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<usize>
+}
+
+impl Solver {
+    pub(crate) fn new(clauses: &[Clause]) -> Solver {
+        let mut solver = Solver {
+            names: vec!(),
+            indices: HashMap::new(),
+            clauses: vec!(),
+            values: vec!(),
+            reasons: vec!(),
+            trail: vec!()
+        };
+
+        for c in clauses {
+            solver.add_clause(c);
+        }
+
+        solver
+    }
+
+    pub(crate) fn add_clause(&mut self, clause: &Clause) {
+        let mut literals: Vec<Literal> = vec!();
+        for p in &clause.parts {
+            let (name, positive) = match *p {
+                ClausePart::Term(ref n) => (n, true),
+                ClausePart::NegatedTerm(ref n) => (n, false),
+            };
+
+            let term = match self.indices.get(name) {
+                Some(&t) => t,
+                None => {
+                    self.names.push(name.clone());
+                    self.values.push(None);
+                    self.reasons.push(None);
+                    self.indices.insert(name.clone(), self.names.len() - 1);
+                    self.names.len() - 1
+                }
+            };
+
+            let literal = Literal { term, positive };
+            if !literals.contains(&literal) {
+                literals.push(literal);
+            }
+        }
+
+        self.clauses.push(literals);
+    }
+
+    /// Searches for an assignment that satisfies all of the clauses added so
+    /// far, starting again from scratch.
+    pub(crate) fn find_model(&mut self) -> Option<HashMap<String, bool>> {
+        self.undo(0);
+
+        if self.solve() {
+            Some(self.model())
+        } else {
+            None
+        }
+    }
+
+    fn value(&self, literal: Literal) -> Option<bool> {
+        self.values[literal.term].map(|v| v == literal.positive)
+    }
+
+    fn assign(&mut self, literal: Literal, reason: Option<usize>) {
+        self.values[literal.term] = Some(literal.positive);
+        self.reasons[literal.term] = reason;
+        self.trail.push(literal.term);
+    }
+
+    /// Unassigns every term assigned after the trail had the given length.
+    fn undo(&mut self, length: usize) {
+        for t in self.trail.drain(length..) {
+            self.values[t] = None;
+        }
+    }
+
+    /// Repeatedly assigns the only unassigned literal of each clause whose
+    /// other literals are false, returning the index of a clause that
+    /// becomes false if there is one.
+    fn propagate(&mut self) -> Result<(), usize> {
+        loop {
+            let mut changed = false;
+
+            for i in 0..self.clauses.len() {
+                let mut unassigned = None;
+                let mut count = 0;
+                let mut satisfied = false;
+
+                for &l in &self.clauses[i] {
+                    match self.value(l) {
+                        Some(true) => {
+                            satisfied = true;
+                            break
+                        },
+                        Some(false) => (),
+                        None => {
+                            unassigned = Some(l);
+                            count += 1;
+                        }
+                    }
+                }
+
+                if satisfied {
+                    continue
+                }
+                match (count, unassigned) {
+                    (0, _) => return Err(i),
+                    (1, Some(l)) => {
+                        self.assign(l, Some(i));
+                        changed = true;
+                    },
+                    _ => ()
+                }
+            }
+
+            if !changed {
+                return Ok(())
+            }
+        }
+    }
+
+    /// Picks an unassigned literal from the unsatisfied clause with the
+    /// fewest unassigned literals, or returns None if every clause is
+    /// satisfied.
+    fn choose(&self) -> Option<Literal> {
+        self.clauses.iter()
+            .filter(|c| !c.iter().any(|&l| self.value(l) == Some(true)))
+            .map(|c| c.iter().cloned().filter(|&l| self.value(l).is_none()).collect::<Vec<_>>())
+            .min_by_key(|c| c.len())
+            .and_then(|c| c.first().cloned())
+    }
+
+    fn solve(&mut self) -> bool {
+        if self.propagate().is_err() {
+            return false
+        }
+
+        let literal = match self.choose() {
+            Some(l) => l,
+            None => return true
+        };
+
+        let length = self.trail.len();
+        for &positive in &[literal.positive, !literal.positive] {
+            self.assign(Literal { term: literal.term, positive }, None);
+            if self.solve() {
+                return true
+            }
+            self.undo(length);
+        }
+
+        false
+    }
+
+    /// Searches for a model of the clauses added so far, and if there is
+    /// none, returns the steps of a resolution proof of the empty clause.
+    ///
+    /// The input steps are justified by `Justification::Assumption` with the
+    /// index of the clause in the order that they were added.
+    pub(crate) fn refute(&mut self) -> Option<Vec<ProofStep>> {
+        self.undo(0);
+
+        let mut steps = vec!();
+        let last = self.refute_(&mut steps, &mut HashMap::new())?;
+
+        // Only the steps that the empty clause was derived from are kept,
+        // since some branches of the search are not needed in the end
+        let mut needed = vec!(false; steps.len());
+        needed[last] = true;
+        for i in (0..=last).rev() {
+            if let (true, Justification::Resolution { left, right, .. }) = (needed[i], &steps[i].justification) {
+                needed[*left] = true;
+                needed[*right] = true;
+            }
+        }
+
+        let mut numbers = vec!(0; steps.len());
+        let mut kept = vec!();
+        for (i, mut step) in steps.into_iter().enumerate().take(last + 1) {
+            if !needed[i] {
+                continue
+            }
+            if let Justification::Resolution { ref mut left, ref mut right, .. } = step.justification {
+                *left = numbers[*left];
+                *right = numbers[*right];
+            }
+            numbers[i] = kept.len();
+            kept.push(step);
+        }
+
+        Some(kept)
+    }
+
+    /// Searches like `solve`, but when there is no model, adds steps to the
+    /// proof deriving a clause that is false under the assignment at the
+    /// start of the search and returns its index.
+    ///
+    /// This is the resolution proof that the search already follows: a
+    /// clause made false by propagation is resolved with the clauses that
+    /// propagated its literals, and the clauses found for both values of a
+    /// chosen term are resolved on that term.
+    fn refute_(&mut self, steps: &mut Vec<ProofStep>, inputs: &mut HashMap<usize, usize>) -> Option<usize> {
+        let length = self.trail.len();
+
+        let mut derived = match self.propagate() {
+            Err(conflict) => self.input_step(conflict, steps, inputs),
+            Ok(()) => {
+                let literal = self.choose()?;
+
+                let name = self.names[literal.term].clone();
+
+                // A clause that does not mention the chosen term is already
+                // false without it, so the other value need not be tried
+                let mut found = vec!();
+                for &positive in &[literal.positive, !literal.positive] {
+                    let before = self.trail.len();
+                    self.assign(Literal { term: literal.term, positive }, None);
+                    let result = self.refute_(steps, inputs);
+                    self.undo(before);
+
+                    let step = result?;
+                    if !mentions(&steps[step].clause, &name) {
+                        found = vec!((positive, step));
+                        break
+                    }
+                    found.push((positive, step));
+                }
+
+                match found[..] {
+                    [(positive, a), (_, b)] => {
+                        let (when_true, when_false) = if positive { (a, b) } else { (b, a) };
+                        resolve_steps(steps, when_false, when_true, ClausePart::Term(name))
+                    },
+                    _ => found[0].1,
+                }
+            }
+        };
+
+        // Each literal propagated during this search is resolved away with
+        // the clause that propagated it, latest first
+        for i in (length..self.trail.len()).rev() {
+            let t = self.trail[i];
+            if let Some(reason) = self.reasons[t] {
+                let name = &self.names[t];
+                if mentions(&steps[derived].clause, name) {
+                    let pivot = if self.values[t] == Some(true) {
+                        ClausePart::Term(name.clone())
+                    } else {
+                        ClausePart::NegatedTerm(name.clone())
+                    };
+                    let left = self.input_step(reason, steps, inputs);
+                    derived = resolve_steps(steps, left, derived, pivot);
+                }
+            }
+        }
+
+        Some(derived)
+    }
+
+    /// Returns the step for the input clause with the given index, adding it
+    /// to the proof the first time that it is used.
+    fn input_step(&self, clause: usize, steps: &mut Vec<ProofStep>, inputs: &mut HashMap<usize, usize>) -> usize {
+        *inputs.entry(clause).or_insert_with(|| {
+            steps.push(ProofStep {
+                clause: Clause {
+                    parts: self.clauses[clause].iter()
+                        .map(|l| if l.positive {
+                            ClausePart::Term(self.names[l.term].clone())
+                        } else {
+                            ClausePart::NegatedTerm(self.names[l.term].clone())
+                        })
+                        .collect()
+                },
+                justification: Justification::Assumption(clause)
+            });
+            steps.len() - 1
+        })
+    }
+
+    /// Returns the current assignment, giving unassigned terms the value
+    /// false.
+    fn model(&self) -> HashMap<String, bool> {
+        self.names.iter()
+            .cloned()
+            .zip(self.values.iter().map(|v| v.unwrap_or(false)))
+            .collect()
+    }
+}
+
+/// Checks whether the given clause contains the given term, negated or not.
+fn mentions(clause: &Clause, name: &str) -> bool {
+    clause.parts.iter().any(|p| p.name() == name)
+}
+
+/// Adds a step to the proof resolving the two given steps on the given part
+/// of the left step's clause, and returns its index.
+fn resolve_steps(steps: &mut Vec<ProofStep>, left: usize, right: usize, pivot: ClausePart) -> usize {
+    let clause = combine(&steps[left].clause, &steps[right].clause, &pivot);
+    steps.push(ProofStep {
+        clause,
+        justification: Justification::Resolution { left, right, pivot }
+    });
+    steps.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::*;
+    use sat::*;
+
+    #[test]
+    fn find_model_satisfies() {
+        let props = vec!(
+            parse_proposition("(a \\/ b) /\\ (~a \\/ c) /\\ (~b \\/ ~c)").unwrap(),
+            parse_proposition("a <+> d").unwrap(),
+            parse_proposition("if d then e else ~c").unwrap(),
+        );
+
+        let model = find_model(&props).unwrap();
+
+        for p in props {
+            assert_eq!(p.evaluate(&model), Some(true));
+        }
+    }
+
+    #[test]
+    fn find_model_unsatisfiable() {
+        let prop = parse_proposition("(a <-> b) /\\ (b <-> c) /\\ (a <+> c)").unwrap();
+
+        assert_eq!(find_model(&[prop]), None);
+    }
+
+    #[test]
+    fn find_model_pigeonhole() {
+        // Four pigeons cannot each have their own hole out of three
+        let mut props = vec!();
+        for p in 0..4 {
+            props.push(or_all((0..3).map(|h| term(format!("p{}h{}", p, h))).collect()));
+        }
+        for h in 0..3 {
+            for a in 0..4 {
+                for b in (a + 1)..4 {
+                    props.push(not(and(
+                        term(format!("p{}h{}", a, h)),
+                        term(format!("p{}h{}", b, h))
+                    )));
+                }
+            }
+        }
+
+        assert_eq!(find_model(&props), None);
+
+        props.truncate(3);
+        assert!(find_model(&props).is_some());
+    }
+
+    #[test]
+    fn find_clause_model_empty() {
+        assert_eq!(find_clause_model(&[]), Some(HashMap::new()));
+        assert_eq!(find_clause_model(&[Clause { parts: vec!() }]), None);
+    }
+
+    #[test]
+    fn equivalent_laws() {
+        let pairs = vec!(
+            ("p -> q", "~q -> ~p", true),
+            ("p <+> q", "(p \\/ q) /\\ ~(p /\\ q)", true),
+            ("if p then q else r", "(p /\\ q) \\/ (~p /\\ r)", true),
+            ("[p + q + r >= 2]", "(p /\\ q) \\/ (p /\\ r) \\/ (q /\\ r)", true),
+            ("p -> q", "q -> p", false),
+            ("p", "p \\/ q", false),
+        );
+
+        for (a, b, expected) in pairs {
+            let a = parse_proposition(a).unwrap();
+            let b = parse_proposition(b).unwrap();
+            assert_eq!(equivalent(&a, &b), expected, "{} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn refute_steps_are_resolutions() {
+        let mut props = vec!();
+        for p in 0..4 {
+            props.push(or_all((0..3).map(|h| term(format!("p{}h{}", p, h))).collect()));
+        }
+        for h in 0..3 {
+            props.push(parse_proposition(&format!("[p0h{0} + p1h{0} + p2h{0} + p3h{0} <= 1]", h)).unwrap());
+        }
+
+        let proof = refute(&props).unwrap();
+
+        for (i, step) in proof.steps.iter().enumerate() {
+            match step.justification {
+                Justification::Assumption(a) => {
+                    assert!(a < props.len());
+                },
+                Justification::Resolution { left, right, ref pivot } => {
+                    assert!(left < i && right < i);
+                    assert!(proof.steps[left].clause.parts.contains(pivot));
+                    assert!(proof.steps[right].clause.parts.contains(&pivot.negate()));
+                    assert_eq!(step.clause, combine(&proof.steps[left].clause, &proof.steps[right].clause, pivot));
+                },
+                Justification::NegatedGoal => panic!("No goal was given"),
+            }
+        }
+        assert_eq!(proof.conclusion().parts.len(), 0);
+        assert_eq!(proof.used_assumptions(), (0..props.len()).collect::<Vec<usize>>());
+    }
+}
@@ -0,0 +1,552 @@
+use clauses::*;
+use natural_deduction::*;
+use parser::*;
+use proofs::*;
+use propositions::*;
+
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+/// The version of the JSON schema written for proofs, problems and natural
+/// deduction proofs.
+///
+/// Documents are written with a `"version"` field holding this number.
+/// Documents without the field are read as version 1, and documents with a
+/// newer version than this one are rejected.
+pub const SCHEMA_VERSION: u32 = 1;
+
+impl Serialize for Proposition {
+    /// Writes a term as its name, a constant as `true` or `false`, and any
+    /// other proposition as an object with a single field named after its
+    /// connective, such as `{"and": ["p", {"not": "q"}]}`.
+    ///
+    /// The field holds the operand of a negation, or otherwise the list of
+    /// operands. Chains of conjunctions, disjunctions and exclusive
+    /// disjunctions that group to the left are written as a single list.
+    ///
+    /// A linear constraint is written with an `"at_most"`, `"at_least"` or
+    /// `"exactly"` field holding an object with its `"terms"`, as pairs of a
+    /// coefficient and an operand, and its `"bound"`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (name, operands) = match *self {
+            Proposition::Term(ref a) => return serializer.serialize_str(a),
+            Proposition::Top => return serializer.serialize_bool(true),
+            Proposition::Bottom => return serializer.serialize_bool(false),
+            Proposition::Not(ref a) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("not", a)?;
+                return map.end()
+            },
+            Proposition::Or(..) => ("or", left_chain(self)),
+            Proposition::And(..) => ("and", left_chain(self)),
+            Proposition::Xor(..) => ("xor", left_chain(self)),
+            Proposition::Implies(ref a, ref b) => ("implies", vec!(&**a, &**b)),
+            Proposition::Iff(ref a, ref b) => ("iff", vec!(&**a, &**b)),
+            Proposition::Nand(ref a, ref b) => ("nand", vec!(&**a, &**b)),
+            Proposition::Nor(ref a, ref b) => ("nor", vec!(&**a, &**b)),
+            Proposition::Ite(ref c, ref t, ref e) => ("ite", vec!(&**c, &**t, &**e)),
+            Proposition::Linear(ref terms, comparison, bound) => {
+                let document = LinearDocument { terms: terms.clone(), bound };
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(comparison_name(comparison), &document)?;
+                return map.end()
+            },
+        };
+
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(name, &operands)?;
+        map.end()
+    }
+}
+
+/// Returns the operands of the chain of operations of the same kind as the
+/// given proposition that group to the left.
+fn left_chain(prop: &Proposition) -> Vec<&Proposition> {
+    let same = |p: &Proposition| matches!(
+        (prop, p),
+        (Proposition::Or(..), Proposition::Or(..))
+            | (Proposition::And(..), Proposition::And(..))
+            | (Proposition::Xor(..), Proposition::Xor(..))
+    );
+
+    match *prop {
+        Proposition::Or(ref a, ref b)
+        | Proposition::And(ref a, ref b)
+        | Proposition::Xor(ref a, ref b) => {
+            let mut operands = if same(a) { left_chain(a) } else { vec!(&**a) };
+            operands.push(b);
+            operands
+        },
+        ref p => vec!(p)
+    }
+}
+
+/// The written form of the sum and bound of a linear constraint.
+#[derive(Deserialize)]
+#[derive(Serialize)]
+struct LinearDocument {
+    terms: Vec<(i64, Proposition)>,
+    bound: i64
+}
+
+fn comparison_name(comparison: Comparison) -> &'static str {
+    match comparison {
+        Comparison::AtMost => "at_most",
+        Comparison::AtLeast => "at_least",
+        Comparison::Exactly => "exactly",
+    }
+}
+
+impl<'de> Deserialize<'de> for Proposition {
+    /// Reads a proposition in the format that it is written in, where
+    /// conjunctions and disjunctions may have any number of operands.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Proposition, D::Error> {
+        deserializer.deserialize_any(PropositionVisitor)
+    }
+}
+
+struct PropositionVisitor;
+
+impl<'de> Visitor<'de> for PropositionVisitor {
+    type Value = Proposition;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a term name, a boolean, or an object with a single connective")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Proposition, E> {
+        Ok(term(value.to_string()))
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Proposition, E> {
+        Ok(if value { top() } else { bottom() })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Proposition, A::Error> {
+        let name: String = match map.next_key()? {
+            Some(n) => n,
+            None => return Err(de::Error::custom("expected a connective")),
+        };
+
+        let comparison = [Comparison::AtMost, Comparison::AtLeast, Comparison::Exactly].iter()
+            .cloned()
+            .find(|&c| comparison_name(c) == name);
+
+        let prop = if name == "not" {
+            not(map.next_value()?)
+        } else if let Some(c) = comparison {
+            let document: LinearDocument = map.next_value()?;
+            pseudo_boolean(document.terms, c, document.bound)
+        } else {
+            let mut operands: Vec<Proposition> = map.next_value()?;
+
+            let arity = match name.as_str() {
+                "and" | "or" => None,
+                "xor" | "implies" | "iff" | "nand" | "nor" => Some(2),
+                "ite" => Some(3),
+                _ => return Err(de::Error::unknown_field(&name, CONNECTIVES)),
+            };
+            if let Some(n) = arity {
+                if operands.len() != n && !(name == "xor" && operands.len() > n) {
+                    return Err(de::Error::invalid_length(operands.len(), &self))
+                }
+            }
+
+            match name.as_str() {
+                "and" => and_all(operands),
+                "or" => or_all(operands),
+                "xor" => {
+                    let first = operands.remove(0);
+                    operands.into_iter().fold(first, xor)
+                },
+                _ => {
+                    let mut rest = operands.into_iter();
+                    let mut operand = || rest.next().unwrap();
+                    match name.as_str() {
+                        "implies" => implies(operand(), operand()),
+                        "iff" => iff(operand(), operand()),
+                        "nand" => nand(operand(), operand()),
+                        "nor" => nor(operand(), operand()),
+                        _ => ite(operand(), operand(), operand()),
+                    }
+                }
+            }
+        };
+
+        if map.next_key::<String>()?.is_some() {
+            return Err(de::Error::custom("expected a single connective"))
+        }
+
+        Ok(prop)
+    }
+}
+
+const CONNECTIVES: &[&str] = &[
+    "not", "and", "or", "implies", "iff", "xor", "nand", "nor", "ite", "at_most",
+    "at_least", "exactly"
+];
+
+impl Serialize for ClausePart {
+    /// Writes the term's name, prefixed with `~` if it is negated.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ClausePart {
+    /// Reads a term's name, which is negated if it starts with `~`.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ClausePart, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        Ok(if let Some(name) = value.strip_prefix('~') {
+            ClausePart::NegatedTerm(name.to_string())
+        } else {
+            ClausePart::Term(value)
+        })
+    }
+}
+
+/// The written form of a justification, which is tagged with the name of the
+/// rule that it uses.
+#[derive(Deserialize)]
+#[derive(Serialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+enum JustificationDocument {
+    Assumption { index: usize },
+    NegatedGoal,
+    Resolution { left: usize, right: usize, pivot: ClausePart }
+}
+
+impl Serialize for Justification {
+    /// Writes the justification as an object whose `"rule"` field is one of
+    /// `"assumption"`, `"negated_goal"` or `"resolution"`, along with the
+    /// fields of that rule.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let document = match *self {
+            Justification::Assumption(index) =>
+                JustificationDocument::Assumption { index },
+            Justification::NegatedGoal => JustificationDocument::NegatedGoal,
+            Justification::Resolution { left, right, ref pivot } =>
+                JustificationDocument::Resolution { left, right, pivot: pivot.clone() },
+        };
+
+        document.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Justification {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Justification, D::Error> {
+        Ok(match JustificationDocument::deserialize(deserializer)? {
+            JustificationDocument::Assumption { index } =>
+                Justification::Assumption(index),
+            JustificationDocument::NegatedGoal => Justification::NegatedGoal,
+            JustificationDocument::Resolution { left, right, pivot } =>
+                Justification::Resolution { left, right, pivot },
+        })
+    }
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// Checks that a document with the given schema version can be read.
+fn check_version<E: de::Error>(version: u32) -> Result<(), E> {
+    if version > SCHEMA_VERSION {
+        Err(E::custom(format!(
+            "schema version {} is newer than the supported version {}",
+            version,
+            SCHEMA_VERSION
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[derive(Serialize)]
+struct ProofDocument {
+    #[serde(default = "default_version")]
+    version: u32,
+    assumptions: Vec<Proposition>,
+    goal: Proposition,
+    steps: Vec<ProofStep>
+}
+
+impl Serialize for Proof {
+    /// Writes the proof as an object with `"version"`, `"assumptions"`,
+    /// `"goal"` and `"steps"` fields.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProofDocument {
+            version: SCHEMA_VERSION,
+            assumptions: self.assumptions.clone(),
+            goal: self.goal.clone(),
+            steps: self.steps.clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Proof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Proof, D::Error> {
+        let document = ProofDocument::deserialize(deserializer)?;
+        check_version(document.version)?;
+
+        Ok(Proof {
+            assumptions: document.assumptions,
+            goal: document.goal,
+            steps: document.steps,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[derive(Serialize)]
+struct ProblemDocument {
+    #[serde(default = "default_version")]
+    version: u32,
+    assumptions: Vec<Proposition>,
+    goal: Proposition
+}
+
+impl Serialize for Problem {
+    /// Writes the problem as an object with `"version"`, `"assumptions"` and
+    /// `"goal"` fields.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProblemDocument {
+            version: SCHEMA_VERSION,
+            assumptions: self.assumptions.clone(),
+            goal: self.goal.clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Problem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Problem, D::Error> {
+        let document = ProblemDocument::deserialize(deserializer)?;
+        check_version(document.version)?;
+
+        Ok(Problem {
+            assumptions: document.assumptions,
+            goal: document.goal,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[derive(Serialize)]
+struct NaturalDeductionDocument {
+    #[serde(default = "default_version")]
+    version: u32,
+    lines: Vec<DeductionLine>
+}
+
+impl Serialize for NaturalDeductionProof {
+    /// Writes the proof as an object with `"version"` and `"lines"` fields.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        NaturalDeductionDocument {
+            version: SCHEMA_VERSION,
+            lines: self.lines.clone(),
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NaturalDeductionProof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<NaturalDeductionProof, D::Error> {
+        let document = NaturalDeductionDocument::deserialize(deserializer)?;
+        check_version(document.version)?;
+
+        Ok(NaturalDeductionProof {
+            lines: document.lines,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use horn::*;
+    use normal_forms::*;
+    use resolution::*;
+    use serialization::*;
+
+    use self::serde_json::json;
+
+    fn readme_problem() -> Problem {
+        parse_problem("
+            p
+            (p /\\ q) -> r
+            (s \\/ t) -> q
+            t
+            ---
+            r
+        ").unwrap()
+    }
+
+    #[test]
+    fn proposition_schema() {
+        let prop = parse_proposition(
+            "~(a /\\ b /\\ (c /\\ d)) -> true <+> x <+> y \\/ if c then false else a ~\\/ b"
+        ).unwrap();
+
+        let expected = json!({"xor": [
+            {"implies": [
+                {"not": {"and": ["a", "b", {"and": ["c", "d"]}]}},
+                true
+            ]},
+            "x",
+            {"or": [
+                "y",
+                {"ite": ["c", false, {"nor": ["a", "b"]}]}
+            ]}
+        ]});
+
+        assert_eq!(serde_json::to_value(&prop).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Proposition>(expected).unwrap(), prop);
+    }
+
+    #[test]
+    fn linear_schema() {
+        let prop = parse_proposition("[2 p - q <= 1] \\/ [a + b = 1]").unwrap();
+
+        let expected = json!({"or": [
+            {"at_most": {"terms": [[2, "p"], [-1, "q"]], "bound": 1}},
+            {"exactly": {"terms": [[1, "a"], [1, "b"]], "bound": 1}}
+        ]});
+
+        assert_eq!(serde_json::to_value(&prop).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Proposition>(expected).unwrap(), prop);
+    }
+
+    #[test]
+    fn proposition_n_ary_input() {
+        let value = json!({"or": [{"and": []}, {"or": ["p"]}, "q"]});
+
+        let prop: Proposition = serde_json::from_value(value).unwrap();
+
+        assert_eq!(prop.to_string(), "true \\/ p \\/ q");
+    }
+
+    #[test]
+    fn proposition_invalid() {
+        let values = vec!(
+            json!({"implies": ["p"]}),
+            json!({"unless": ["p", "q"]}),
+            json!({"not": "p", "and": ["p", "q"]}),
+            json!(1),
+        );
+
+        for v in values {
+            assert!(serde_json::from_value::<Proposition>(v.clone()).is_err(), "{}", v);
+        }
+    }
+
+    #[test]
+    fn clause_schema() {
+        let clause = Clause { parts: vec!(
+            ClausePart::NegatedTerm("p".to_string()),
+            ClausePart::Term("q".to_string())
+        ) };
+
+        let expected = json!(["~p", "q"]);
+
+        assert_eq!(serde_json::to_value(&clause).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Clause>(expected).unwrap(), clause);
+    }
+
+    #[test]
+    fn cube_round_trip() {
+        let cubes = Cube::from_proposition(parse_proposition("p <+> q").unwrap());
+
+        let text = serde_json::to_string(&cubes).unwrap();
+
+        assert_eq!(serde_json::from_str::<Vec<Cube>>(&text).unwrap(), cubes);
+    }
+
+    #[test]
+    fn problem_schema() {
+        let problem = parse_problem("p\np -> q\n---\nq").unwrap();
+
+        let expected = json!({
+            "version": 1,
+            "assumptions": ["p", {"implies": ["p", "q"]}],
+            "goal": "q"
+        });
+
+        assert_eq!(serde_json::to_value(&problem).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Problem>(expected).unwrap(), problem);
+    }
+
+    #[test]
+    fn problem_without_version() {
+        let value = json!({"assumptions": [], "goal": true});
+
+        let problem: Problem = serde_json::from_value(value).unwrap();
+
+        assert_eq!(problem.goal, top());
+    }
+
+    #[test]
+    fn problem_newer_version() {
+        let value = json!({"version": 2, "assumptions": [], "goal": true});
+
+        let error = serde_json::from_value::<Problem>(value).unwrap_err();
+
+        assert!(error.to_string().contains("newer"));
+    }
+
+    #[test]
+    fn proof_schema() {
+        let problem = parse_problem("p\np -> q\n---\nq").unwrap();
+        let proof = prove(problem.assumptions, problem.goal).unwrap();
+
+        let expected = json!({
+            "version": 1,
+            "assumptions": ["p", {"implies": ["p", "q"]}],
+            "goal": "q",
+            "steps": [
+                {"clause": ["~q"], "justification": {"rule": "negated_goal"}},
+                {"clause": ["~p", "q"], "justification": {"rule": "assumption", "index": 1}},
+                {"clause": ["~p"], "justification": {"rule": "resolution", "left": 0, "right": 1, "pivot": "~q"}},
+                {"clause": ["p"], "justification": {"rule": "assumption", "index": 0}},
+                {"clause": [], "justification": {"rule": "resolution", "left": 2, "right": 3, "pivot": "~p"}}
+            ]
+        });
+
+        assert_eq!(serde_json::to_value(&proof).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<Proof>(expected).unwrap(), proof);
+    }
+
+    #[test]
+    fn natural_deduction_round_trip() {
+        let problem = readme_problem();
+        let proof = prove(problem.assumptions, problem.goal).unwrap();
+        let deduction = proof.to_natural_deduction().unwrap();
+
+        let value = serde_json::to_value(&deduction).unwrap();
+
+        assert_eq!(value["lines"][3]["rule"], json!("premise"));
+        assert_eq!(value["lines"][4]["rule"], json!("assumption"));
+        assert_eq!(serde_json::from_value::<NaturalDeductionProof>(value).unwrap(), deduction);
+    }
+
+    #[test]
+    fn horn_results_round_trip() {
+        let problem = readme_problem();
+        let clauses: Vec<Clause> = problem.assumptions.into_iter()
+            .flat_map(Clause::from_proposition)
+            .collect();
+
+        let chaining = forward_chain(&clauses);
+        let trace = backward_chain(&clauses, "r").unwrap();
+
+        let chaining_text = serde_json::to_string(&chaining).unwrap();
+        let trace_text = serde_json::to_string(&trace).unwrap();
+
+        assert_eq!(serde_json::from_str::<ForwardChaining>(&chaining_text).unwrap(), chaining);
+        assert_eq!(serde_json::from_str::<HornTrace>(&trace_text).unwrap(), trace);
+    }
+}
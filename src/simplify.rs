@@ -0,0 +1,358 @@
+use propositions::*;
+
+use std::convert::TryFrom;
+
+/// Settings that control how a proposition is simplified.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct SimplifyOptions {
+    /// Whether implications and biconditionals are kept as they are written,
+    /// rather than being rewritten in terms of conjunction, disjunction and
+    /// negation.
+    pub preserve_implications: bool
+}
+
+impl Default for SimplifyOptions {
+    fn default() -> SimplifyOptions {
+        SimplifyOptions {
+            preserve_implications: true
+        }
+    }
+}
+
+impl Proposition {
+    /// Returns an equivalent proposition that has been simplified using the
+    /// default options, which keep implications and biconditionals.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let p = term("p".to_string());
+    /// let q = term("q".to_string());
+    ///
+    /// let prop = and(
+    ///     and(p.clone(), or(p.clone(), q.clone())),
+    ///     implies(not(not(q.clone())), top())
+    /// );
+    ///
+    /// assert_eq!(prop.simplify(), p);
+    /// ```
+    pub fn simplify(&self) -> Proposition {
+        self.simplify_with(SimplifyOptions::default())
+    }
+
+    /// Returns an equivalent proposition that has been simplified using the
+    /// given options.
+    ///
+    /// Constants are removed using the identity and annihilator laws, double
+    /// negations are removed, nested conjunctions and disjunctions are
+    /// flattened, and repeated operands are removed. A conjunction containing
+    /// a proposition and its negation becomes `Bottom`, and a disjunction
+    /// containing both becomes `Top`. Operands that are absorbed by other
+    /// operands, like `p \/ q` in `p /\ (p \/ q)`, are removed.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let prop = parse_proposition("(p -> q) /\\ ~~(r /\\ ~r \\/ p)").unwrap();
+    ///
+    /// let keep = SimplifyOptions { preserve_implications: true };
+    /// let drop = SimplifyOptions { preserve_implications: false };
+    ///
+    /// assert_eq!(prop.simplify_with(keep), parse_proposition("(p -> q) /\\ p").unwrap());
+    /// assert_eq!(prop.simplify_with(drop), parse_proposition("(~p \\/ q) /\\ p").unwrap());
+    /// ```
+    pub fn simplify_with(&self, options: SimplifyOptions) -> Proposition {
+        let simplify = |p: &Proposition| p.simplify_with(options);
+
+        match *self {
+            Proposition::Or(..) => simplify_associative(self, true, options),
+            Proposition::And(..) => simplify_associative(self, false, options),
+            Proposition::Implies(ref a, ref b) => {
+                let (a2, b2) = (simplify(a), simplify(b));
+                match (a2, b2) {
+                    (Proposition::Top, b3) => b3,
+                    (Proposition::Bottom, _) | (_, Proposition::Top) =>
+                        Proposition::Top,
+                    (a3, Proposition::Bottom) => simplify(&not(a3)),
+                    (ref a3, ref b3) if a3 == b3 => Proposition::Top,
+                    (a3, b3) => if options.preserve_implications {
+                        implies(a3, b3)
+                    } else {
+                        simplify(&or(not(a3), b3))
+                    }
+                }
+            },
+            Proposition::Iff(ref a, ref b) => {
+                let (a2, b2) = (simplify(a), simplify(b));
+                match (a2, b2) {
+                    (Proposition::Top, p) | (p, Proposition::Top) => p,
+                    (Proposition::Bottom, p) | (p, Proposition::Bottom) =>
+                        simplify(&not(p)),
+                    (ref a3, ref b3) if a3 == b3 => Proposition::Top,
+                    (ref a3, ref b3) if complementary(a3, b3) =>
+                        Proposition::Bottom,
+                    (a3, b3) => if options.preserve_implications {
+                        iff(a3, b3)
+                    } else {
+                        simplify(&and(
+                            or(not(a3.clone()), b3.clone()),
+                            or(not(b3), a3)
+                        ))
+                    }
+                }
+            },
+            Proposition::Xor(ref a, ref b) => {
+                let (a2, b2) = (simplify(a), simplify(b));
+                match (a2, b2) {
+                    (Proposition::Bottom, p) | (p, Proposition::Bottom) => p,
+                    (Proposition::Top, p) | (p, Proposition::Top) =>
+                        simplify(&not(p)),
+                    (ref a3, ref b3) if a3 == b3 => Proposition::Bottom,
+                    (ref a3, ref b3) if complementary(a3, b3) =>
+                        Proposition::Top,
+                    (a3, b3) => xor(a3, b3)
+                }
+            },
+            Proposition::Nand(ref a, ref b) => {
+                let (a2, b2) = (simplify(a), simplify(b));
+                match (a2, b2) {
+                    (Proposition::Bottom, _) | (_, Proposition::Bottom) =>
+                        Proposition::Top,
+                    (Proposition::Top, p) | (p, Proposition::Top) =>
+                        simplify(&not(p)),
+                    (ref a3, ref b3) if a3 == b3 => simplify(&not(a3.clone())),
+                    (ref a3, ref b3) if complementary(a3, b3) =>
+                        Proposition::Top,
+                    (a3, b3) => nand(a3, b3)
+                }
+            },
+            Proposition::Nor(ref a, ref b) => {
+                let (a2, b2) = (simplify(a), simplify(b));
+                match (a2, b2) {
+                    (Proposition::Top, _) | (_, Proposition::Top) =>
+                        Proposition::Bottom,
+                    (Proposition::Bottom, p) | (p, Proposition::Bottom) =>
+                        simplify(&not(p)),
+                    (ref a3, ref b3) if a3 == b3 => simplify(&not(a3.clone())),
+                    (ref a3, ref b3) if complementary(a3, b3) =>
+                        Proposition::Bottom,
+                    (a3, b3) => nor(a3, b3)
+                }
+            },
+            Proposition::Ite(ref c, ref t, ref e) => {
+                let (c2, t2, e2) = (simplify(c), simplify(t), simplify(e));
+                match (c2, t2, e2) {
+                    (Proposition::Top, t3, _) => t3,
+                    (Proposition::Bottom, _, e3) => e3,
+                    (_, ref t3, ref e3) if t3 == e3 => t3.clone(),
+                    (c3, Proposition::Top, Proposition::Bottom) => c3,
+                    (c3, Proposition::Bottom, Proposition::Top) =>
+                        simplify(&not(c3)),
+                    (c3, t3, e3) => ite(c3, t3, e3)
+                }
+            },
+            Proposition::Linear(ref terms, comparison, bound) => {
+                // Constant operands are moved over to the bound
+                let mut bound = i128::from(bound);
+                let mut operands = vec!();
+                for &(c, ref p) in terms {
+                    match simplify(p) {
+                        _ if c == 0 => (),
+                        Proposition::Top => bound -= i128::from(c),
+                        Proposition::Bottom => (),
+                        p2 => operands.push((c, p2)),
+                    }
+                }
+
+                let least: i128 = operands.iter().map(|&(c, _)| i128::from(c.min(0))).sum();
+                let most: i128 = operands.iter().map(|&(c, _)| i128::from(c.max(0))).sum();
+                let (always, never) = match comparison {
+                    Comparison::AtMost => (most <= bound, least > bound),
+                    Comparison::AtLeast => (least >= bound, most < bound),
+                    Comparison::Exactly =>
+                        (least == bound && most == bound, least > bound || most < bound),
+                };
+
+                if always {
+                    Proposition::Top
+                } else if never {
+                    Proposition::Bottom
+                } else {
+                    // Moving very large constants over can take the bound out
+                    // of range, in which case the constraint is left as it is
+                    match i64::try_from(bound) {
+                        Ok(b) => pseudo_boolean(operands, comparison, b),
+                        Err(_) => self.clone()
+                    }
+                }
+            },
+            Proposition::Not(ref a) => match simplify(a) {
+                Proposition::Not(b) => *b,
+                Proposition::Top => Proposition::Bottom,
+                Proposition::Bottom => Proposition::Top,
+                b => not(b),
+            },
+            Proposition::Term(_) | Proposition::Top | Proposition::Bottom =>
+                self.clone(),
+        }
+    }
+}
+
+/// Simplifies the given conjunction, or disjunction if `disjunction` is
+/// true, by flattening it into a list of simplified operands and removing the
+/// operands that make no difference.
+fn simplify_associative(prop: &Proposition, disjunction: bool, options: SimplifyOptions) -> Proposition {
+    let (identity, annihilator) = if disjunction {
+        (Proposition::Bottom, Proposition::Top)
+    } else {
+        (Proposition::Top, Proposition::Bottom)
+    };
+
+    let mut operands: Vec<Proposition> = vec!();
+    for o in flatten(prop, disjunction).into_iter().flat_map(|o| {
+        let simplified = o.simplify_with(options);
+        flatten(&simplified, disjunction).into_iter().cloned().collect::<Vec<_>>()
+    }) {
+        if o == annihilator {
+            return annihilator
+        }
+        if o != identity && !operands.contains(&o) {
+            operands.push(o);
+        }
+    }
+
+    let complement = operands.iter()
+        .any(|a| operands.iter().any(|b| complementary(a, b)));
+    if complement {
+        return annihilator
+    }
+
+    // An operand is absorbed by another whose own operands, of the opposite
+    // kind, are a subset of its operands, such as p \/ q by p in
+    // p /\ (p \/ q)
+    let inner: Vec<Vec<&Proposition>> = operands.iter()
+        .map(|o| flatten(o, !disjunction))
+        .collect();
+    let mut absorbed = vec!(false; operands.len());
+    for i in 0..operands.len() {
+        absorbed[i] = (0..operands.len()).any(|j| {
+            j != i
+                && !absorbed[j]
+                && inner[j].iter().all(|p| inner[i].contains(p))
+                && (j < i || !inner[i].iter().all(|p| inner[j].contains(p)))
+        });
+    }
+
+    let remaining: Vec<Proposition> = operands.into_iter()
+        .zip(absorbed)
+        .filter(|&(_, a)| !a)
+        .map(|(o, _)| o)
+        .collect();
+
+    if disjunction {
+        or_all(remaining)
+    } else {
+        and_all(remaining)
+    }
+}
+
+/// Returns the operands of the given proposition if it is a disjunction, or
+/// conjunction if `disjunction` is false, flattening any nested operations of
+/// the same kind. Any other proposition is its own only operand.
+fn flatten(prop: &Proposition, disjunction: bool) -> Vec<&Proposition> {
+    match (prop, disjunction) {
+        (Proposition::Or(a, b), true) | (Proposition::And(a, b), false) => {
+            let mut operands = flatten(a, disjunction);
+            operands.append(&mut flatten(b, disjunction));
+            operands
+        },
+        (p, _) => vec!(p)
+    }
+}
+
+/// Checks whether one of the given propositions is the negation of the
+/// other.
+fn complementary(a: &Proposition, b: &Proposition) -> bool {
+    match (a, b) {
+        (Proposition::Not(c), d) | (d, Proposition::Not(c)) => **c == *d,
+        _ => false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::*;
+    use simplify::*;
+
+    fn simplified(input: &str) -> String {
+        parse_proposition(input).unwrap().simplify().to_string()
+    }
+
+    #[test]
+    fn simplify_constants() {
+        assert_eq!(simplified("p /\\ true"), "p");
+        assert_eq!(simplified("p /\\ false"), "false");
+        assert_eq!(simplified("p \\/ true"), "true");
+        assert_eq!(simplified("p \\/ false"), "p");
+        assert_eq!(simplified("true -> p"), "p");
+        assert_eq!(simplified("p -> false"), "~(p)");
+        assert_eq!(simplified("false <-> p"), "~(p)");
+        assert_eq!(simplified("if true then p else q"), "p");
+    }
+
+    #[test]
+    fn simplify_linear() {
+        assert_eq!(simplified("[p + true + 2 false <= 1]"), "[p <= 0]");
+        assert_eq!(simplified("[p + q <= 2]"), "true");
+        assert_eq!(simplified("[p - q >= 2]"), "false");
+        assert_eq!(simplified("[2 (p /\\ true) + 0 q = 2]"), "[2 p = 2]");
+    }
+
+    #[test]
+    fn simplify_idempotence_and_flattening() {
+        assert_eq!(simplified("(p /\\ q) /\\ (r /\\ (p /\\ q))"), "p /\\ q /\\ r");
+        assert_eq!(simplified("p \\/ (q \\/ p)"), "p \\/ q");
+    }
+
+    #[test]
+    fn simplify_complement() {
+        assert_eq!(simplified("q /\\ p /\\ ~p"), "false");
+        assert_eq!(simplified("~~p \\/ q \\/ ~p"), "true");
+        assert_eq!(simplified("p <+> ~p"), "true");
+    }
+
+    #[test]
+    fn simplify_absorption() {
+        assert_eq!(simplified("p /\\ (p \\/ q)"), "p");
+        assert_eq!(simplified("(p \\/ q \\/ r) /\\ (q \\/ p)"), "q \\/ p");
+        assert_eq!(simplified("(p /\\ q) \\/ p \\/ (q /\\ p)"), "p");
+        assert_eq!(simplified("(p \\/ q) /\\ (q \\/ p)"), "p \\/ q");
+    }
+
+    #[test]
+    fn simplify_double_negation() {
+        assert_eq!(simplified("~~~p"), "~(p)");
+        assert_eq!(simplified("~(p ~/\\ p)"), "p");
+    }
+
+    #[test]
+    fn simplify_drop_implications() {
+        let options = SimplifyOptions { preserve_implications: false };
+        let prop = parse_proposition("p <-> q").unwrap();
+
+        assert_eq!(
+            prop.simplify_with(options),
+            parse_proposition("(~p \\/ q) /\\ (~q \\/ p)").unwrap()
+        );
+    }
+
+    #[test]
+    fn simplify_unchanged() {
+        let prop = parse_proposition("(p -> q) <-> (r <+> s)").unwrap();
+
+        assert_eq!(prop.simplify(), prop);
+    }
+}
@@ -0,0 +1,884 @@
+use parser::*;
+use propositions::*;
+use sat::*;
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// An interpreter for SMT-LIB 2 scripts over boolean constants, which keeps
+/// its declarations and assertions between scripts.
+///
+/// The supported commands are `set-logic`, `set-option`, `set-info`,
+/// `get-info`, `declare-const`, `declare-fun` and `define-fun` for the `Bool`
+/// sort, `assert`, `check-sat`, `check-sat-assuming`, `get-model`,
+/// `get-value`, `get-assertions`, `push`, `pop`, `reset`,
+/// `reset-assertions`, `echo` and `exit`. Terms may use `true`, `false`,
+/// `not`, `and`, `or`, `xor`, `=>`, `=`, `distinct`, `ite`, `let` and
+/// annotations with `!`.
+///
+/// Satisfiability is always decided, so `check-sat` answers either `sat` or
+/// `unsat`. An error in a command is reported as an `error` response and
+/// the remaining commands are still run, following the standard.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let mut smt = SmtInterpreter::new();
+///
+/// let output = smt.run("
+///     (set-logic QF_UF)
+///     (declare-const p Bool)
+///     (declare-const q Bool)
+///     (assert (=> p q))
+///     (assert p)
+///     (check-sat)
+///     (get-value (q))
+///     (push 1)
+///     (assert (not q))
+///     (check-sat)
+///     (pop 1)
+///     (check-sat)
+/// ");
+///
+/// assert_eq!(output, "sat\n((q true))\nunsat\nsat\n");
+/// ```
+#[derive(Clone)]
+#[derive(Debug)]
+pub struct SmtInterpreter {
+    scopes: Vec<SmtScope>,
+    model: Option<HashMap<String, bool>>,
+    print_success: bool,
+    exited: bool
+}
+
+/// The declarations and assertions made since the last `push`.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(Default)]
+struct SmtScope {
+    constants: Vec<String>,
+    functions: Vec<(String, SmtFunction)>,
+    assertions: Vec<(SExpr, Proposition)>
+}
+
+/// A function defined with `define-fun`, which is expanded wherever it is
+/// applied.
+#[derive(Clone)]
+#[derive(Debug)]
+struct SmtFunction {
+    parameters: Vec<String>,
+    body: SExpr
+}
+
+impl Default for SmtInterpreter {
+    fn default() -> SmtInterpreter {
+        SmtInterpreter::new()
+    }
+}
+
+impl SmtInterpreter {
+    /// Creates an interpreter with no declarations or assertions.
+    pub fn new() -> SmtInterpreter {
+        SmtInterpreter {
+            scopes: vec!(SmtScope::default()),
+            model: None,
+            print_success: false,
+            exited: false
+        }
+    }
+
+    /// Runs the commands of the given script, returning the responses that
+    /// they print, each ending with a newline.
+    ///
+    /// Commands after `exit` are ignored, as are the commands of any later
+    /// script.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let mut smt = SmtInterpreter::new();
+    ///
+    /// let output = smt.run("
+    ///     (declare-const a Bool)
+    ///     (define-fun both ((x Bool) (y Bool)) Bool (and x y))
+    ///     (assert (both a (not a)))
+    ///     (check-sat)
+    ///     (get-model)
+    /// ");
+    ///
+    /// assert_eq!(output, "unsat\n(error \"6:5: No model is available\")\n");
+    /// ```
+    pub fn run(&mut self, script: &str) -> String {
+        let mut output = String::new();
+
+        let commands = match parse_sexprs(script) {
+            Ok(c) => c,
+            Err(e) => return format!("(error \"{}\")\n", escape_string(&e.to_string()))
+        };
+
+        for c in commands {
+            if self.exited {
+                break
+            }
+
+            match self.execute(&c) {
+                Ok(Some(response)) => {
+                    output.push_str(&response);
+                    output.push('\n');
+                },
+                Ok(None) => if self.print_success {
+                    output.push_str("success\n");
+                },
+                Err(e) => {
+                    let message = format!("{}:{}: {}", e.line, e.column, e.message);
+                    output.push_str(&format!("(error \"{}\")\n", escape_string(&message)));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Runs a single command, returning its response, or None if it only
+    /// prints `success`.
+    fn execute(&mut self, command: &SExpr) -> Result<Option<String>, ParseError> {
+        let items = match command.kind {
+            SExprKind::List(ref items) if !items.is_empty() => items,
+            _ => return Err(command.error("Expected a command".to_string()))
+        };
+        let name = match items[0].kind {
+            SExprKind::Symbol(ref n) => n.as_str(),
+            _ => return Err(items[0].error("Expected a command name".to_string()))
+        };
+        let arguments = &items[1..];
+
+        match name {
+            "set-logic" | "set-info" => Ok(None),
+            "set-option" => {
+                match arguments {
+                    [option, value] if option.kind == SExprKind::Keyword(":print-success".to_string()) => {
+                        self.print_success = parse_bool(value)?;
+                    },
+                    [_, _] => (),
+                    _ => return Err(command.error("Expected an option and a value".to_string()))
+                }
+                Ok(None)
+            },
+            "get-info" => match arguments {
+                [info] => match info.kind {
+                    SExprKind::Keyword(ref k) if k == ":name" =>
+                        Ok(Some("(:name \"resolution-prover\")".to_string())),
+                    SExprKind::Keyword(ref k) if k == ":version" =>
+                        Ok(Some(format!("(:version \"{}\")", env!("CARGO_PKG_VERSION")))),
+                    _ => Ok(Some("unsupported".to_string()))
+                },
+                _ => Err(command.error("Expected a keyword".to_string()))
+            },
+            "declare-const" | "declare-fun" => {
+                let (symbol, sort) = match (name, arguments) {
+                    ("declare-const", [symbol, sort]) => (symbol, sort),
+                    ("declare-fun", [symbol, parameters, sort])
+                        if parameters.kind == SExprKind::List(vec!()) => (symbol, sort),
+                    ("declare-fun", [_, parameters, _]) =>
+                        return Err(parameters.error("Only constants can be declared".to_string())),
+                    _ => return Err(command.error("Expected a name and a sort".to_string()))
+                };
+
+                let name = self.fresh_name(symbol)?;
+                expect_bool(sort)?;
+
+                self.model = None;
+                self.top().constants.push(name);
+                Ok(None)
+            },
+            "define-fun" => {
+                let (symbol, parameters, sort, body) = match arguments {
+                    [symbol, parameters, sort, body] => (symbol, parameters, sort, body),
+                    _ => return Err(command.error("Expected a name, parameters, a sort and a body".to_string()))
+                };
+
+                let name = self.fresh_name(symbol)?;
+                let parameters = match parameters.kind {
+                    SExprKind::List(ref ps) => ps.iter()
+                        .map(|p| match p.kind {
+                            SExprKind::List(ref pair) if pair.len() == 2 => {
+                                expect_bool(&pair[1])?;
+                                symbol_name(&pair[0])
+                            },
+                            _ => Err(p.error("Expected a parameter and its sort".to_string()))
+                        })
+                        .collect::<Result<Vec<String>, ParseError>>()?,
+                    _ => return Err(parameters.error("Expected a list of parameters".to_string()))
+                };
+                expect_bool(sort)?;
+
+                // Checks the body now, so that mistakes are reported here
+                let bound = parameters.iter()
+                    .map(|p| (p.clone(), term(p.clone())))
+                    .collect();
+                self.translate(body, &bound)?;
+
+                self.model = None;
+                self.top().functions.push((name, SmtFunction {
+                    parameters,
+                    body: body.clone()
+                }));
+                Ok(None)
+            },
+            "assert" => match arguments {
+                [t] => {
+                    let prop = self.translate(t, &HashMap::new())?;
+                    self.model = None;
+                    self.top().assertions.push((t.clone(), prop));
+                    Ok(None)
+                },
+                _ => Err(command.error("Expected a single term".to_string()))
+            },
+            "check-sat" => match arguments {
+                [] => Ok(Some(self.check(vec!()))),
+                _ => Err(command.error("Expected no arguments".to_string()))
+            },
+            "check-sat-assuming" => match arguments {
+                [SExpr { kind: SExprKind::List(ref ts), .. }] => {
+                    let assumed = ts.iter()
+                        .map(|t| self.translate(t, &HashMap::new()))
+                        .collect::<Result<Vec<Proposition>, ParseError>>()?;
+                    Ok(Some(self.check(assumed)))
+                },
+                _ => Err(command.error("Expected a list of terms".to_string()))
+            },
+            "get-model" => {
+                let model = self.current_model(command)?;
+                let mut lines = vec!("(".to_string());
+                for c in self.scopes.iter().flat_map(|s| &s.constants) {
+                    lines.push(format!("  (define-fun {} () Bool {})", format_symbol(c), model[c]));
+                }
+                lines.push(")".to_string());
+                Ok(Some(lines.join("\n")))
+            },
+            "get-value" => match arguments {
+                [SExpr { kind: SExprKind::List(ref ts), .. }] if !ts.is_empty() => {
+                    let model = self.current_model(command)?;
+                    let values = ts.iter()
+                        .map(|t| {
+                            let prop = self.translate(t, &HashMap::new())?;
+                            let value = prop.evaluate(model).unwrap_or(false);
+                            Ok(format!("({} {})", t, value))
+                        })
+                        .collect::<Result<Vec<String>, ParseError>>()?;
+                    Ok(Some(format!("({})", values.join(" "))))
+                },
+                _ => Err(command.error("Expected a list of terms".to_string()))
+            },
+            "get-assertions" => {
+                let assertions: Vec<String> = self.scopes.iter()
+                    .flat_map(|s| &s.assertions)
+                    .map(|(t, _)| t.to_string())
+                    .collect();
+                Ok(Some(format!("({})", assertions.join(" "))))
+            },
+            "push" | "pop" => {
+                let count = match arguments {
+                    [] => 1,
+                    [SExpr { kind: SExprKind::Numeral(n), .. }] => *n,
+                    _ => return Err(command.error("Expected a number of levels".to_string()))
+                };
+
+                if name == "push" {
+                    for _ in 0..count {
+                        self.scopes.push(SmtScope::default());
+                    }
+                } else if count < self.scopes.len() {
+                    let length = self.scopes.len() - count;
+                    self.scopes.truncate(length);
+                } else {
+                    return Err(command.error(format!(
+                        "Cannot pop {} levels, only {} have been pushed",
+                        count, self.scopes.len() - 1
+                    )))
+                }
+                self.model = None;
+                Ok(None)
+            },
+            "reset" => {
+                *self = SmtInterpreter::new();
+                Ok(None)
+            },
+            "reset-assertions" => {
+                self.scopes = vec!(SmtScope::default());
+                self.model = None;
+                Ok(None)
+            },
+            "echo" => match arguments {
+                [SExpr { kind: SExprKind::String(ref s), .. }] =>
+                    Ok(Some(format!("\"{}\"", s.replace('"', "\"\"")))),
+                _ => Err(command.error("Expected a string".to_string()))
+            },
+            "exit" => {
+                self.exited = true;
+                Ok(None)
+            },
+            _ => Ok(Some("unsupported".to_string()))
+        }
+    }
+
+    fn top(&mut self) -> &mut SmtScope {
+        let last = self.scopes.len() - 1;
+        &mut self.scopes[last]
+    }
+
+    /// Returns the name of the given symbol, which must not already be
+    /// declared or defined.
+    fn fresh_name(&self, symbol: &SExpr) -> Result<String, ParseError> {
+        let name = symbol_name(symbol)?;
+
+        let exists = self.scopes.iter().any(|s| {
+            s.constants.contains(&name) || s.functions.iter().any(|(f, _)| *f == name)
+        });
+        if exists {
+            return Err(symbol.error(format!("\"{}\" is already declared", name)))
+        }
+
+        Ok(name)
+    }
+
+    /// Checks whether the assertions, along with the given assumptions, are
+    /// satisfiable, keeping the found model.
+    fn check(&mut self, assumed: Vec<Proposition>) -> String {
+        let mut props: Vec<Proposition> = self.scopes.iter()
+            .flat_map(|s| s.assertions.iter().map(|(_, p)| p.clone()))
+            .collect();
+        props.extend(assumed);
+
+        match find_model(&props) {
+            Some(mut model) => {
+                // Constants that no assertion mentions can have any value
+                for c in self.scopes.iter().flat_map(|s| &s.constants) {
+                    model.entry(c.clone()).or_insert(false);
+                }
+                self.model = Some(model);
+                "sat".to_string()
+            },
+            None => {
+                self.model = None;
+                "unsat".to_string()
+            }
+        }
+    }
+
+    fn current_model(&self, command: &SExpr) -> Result<&HashMap<String, bool>, ParseError> {
+        self.model.as_ref()
+            .ok_or_else(|| command.error("No model is available".to_string()))
+    }
+
+    fn function(&self, name: &str) -> Option<&SmtFunction> {
+        self.scopes.iter().rev()
+            .flat_map(|s| s.functions.iter().rev())
+            .find(|(f, _)| f == name)
+            .map(|(_, f)| f)
+    }
+
+    fn is_constant(&self, name: &str) -> bool {
+        self.scopes.iter().any(|s| s.constants.iter().any(|c| c == name))
+    }
+
+    /// Converts the given term into a proposition, where `bound` gives the
+    /// values of the variables bound by `let` and function parameters.
+    fn translate(&self, t: &SExpr, bound: &HashMap<String, Proposition>) -> Result<Proposition, ParseError> {
+        let translate_all = |ts: &[SExpr]| ts.iter()
+            .map(|a| self.translate(a, bound))
+            .collect::<Result<Vec<Proposition>, ParseError>>();
+
+        let items = match t.kind {
+            SExprKind::Symbol(ref name) => return match name.as_str() {
+                _ if bound.contains_key(name) => Ok(bound[name].clone()),
+                "true" => Ok(top()),
+                "false" => Ok(bottom()),
+                _ => self.apply(t, name, vec!())
+            },
+            SExprKind::List(ref items) if !items.is_empty() => items,
+            _ => return Err(t.error(format!("Expected a boolean term, found {}", t)))
+        };
+
+        let name = match items[0].kind {
+            SExprKind::Symbol(ref n) => n.as_str(),
+            SExprKind::List(ref index) =>
+                return cardinality(&items[0], index, translate_all(&items[1..])?),
+            _ => return Err(items[0].error(format!("Expected a function name, found {}", items[0])))
+        };
+        let arguments = &items[1..];
+
+        let at_least = |n: usize| if arguments.len() < n {
+            let plural = if n == 1 { "" } else { "s" };
+            Err(t.error(format!("Expected at least {} argument{} to \"{}\"", n, plural, name)))
+        } else {
+            Ok(())
+        };
+
+        match name {
+            "not" => match arguments {
+                [a] => Ok(not(self.translate(a, bound)?)),
+                _ => Err(t.error("Expected one argument to \"not\"".to_string()))
+            },
+            "and" => {
+                at_least(1)?;
+                Ok(and_all(translate_all(arguments)?))
+            },
+            "or" => {
+                at_least(1)?;
+                Ok(or_all(translate_all(arguments)?))
+            },
+            "xor" => {
+                at_least(2)?;
+                let mut props = translate_all(arguments)?.into_iter();
+                let first = props.next().unwrap();
+                Ok(props.fold(first, xor))
+            },
+            "=>" => {
+                at_least(2)?;
+                let mut props = translate_all(arguments)?;
+                let last = props.pop().unwrap();
+                Ok(props.into_iter().rev().fold(last, |acc, p| implies(p, acc)))
+            },
+            "=" => {
+                at_least(2)?;
+                let props = translate_all(arguments)?;
+                Ok(and_all(props.windows(2)
+                    .map(|w| iff(w[0].clone(), w[1].clone()))
+                    .collect()))
+            },
+            "distinct" => {
+                at_least(2)?;
+                let props = translate_all(arguments)?;
+                let mut pairs = vec!();
+                for i in 0..props.len() {
+                    for j in (i + 1)..props.len() {
+                        pairs.push(xor(props[i].clone(), props[j].clone()));
+                    }
+                }
+                Ok(and_all(pairs))
+            },
+            "ite" => match arguments {
+                [c, a, b] => Ok(ite(
+                    self.translate(c, bound)?,
+                    self.translate(a, bound)?,
+                    self.translate(b, bound)?
+                )),
+                _ => Err(t.error("Expected three arguments to \"ite\"".to_string()))
+            },
+            "let" => match arguments {
+                [SExpr { kind: SExprKind::List(ref bindings), .. }, body] => {
+                    // The bound terms are all translated in the outer scope
+                    let mut inner = bound.clone();
+                    for b in bindings {
+                        match b.kind {
+                            SExprKind::List(ref pair) if pair.len() == 2 => {
+                                let value = self.translate(&pair[1], bound)?;
+                                inner.insert(symbol_name(&pair[0])?, value);
+                            },
+                            _ => return Err(b.error("Expected a variable and a term".to_string()))
+                        }
+                    }
+                    self.translate(body, &inner)
+                },
+                _ => Err(t.error("Expected bindings and a term".to_string()))
+            },
+            "!" => match arguments.first() {
+                Some(a) => self.translate(a, bound),
+                None => Err(t.error("Expected a term to annotate".to_string()))
+            },
+            _ => self.apply(t, name, translate_all(arguments)?)
+        }
+    }
+
+    /// Converts an application of the given declared constant or defined
+    /// function into a proposition.
+    fn apply(&self, t: &SExpr, name: &str, arguments: Vec<Proposition>) -> Result<Proposition, ParseError> {
+        if let Some(f) = self.function(name) {
+            if f.parameters.len() != arguments.len() {
+                return Err(t.error(format!(
+                    "Expected {} arguments to \"{}\"", f.parameters.len(), name
+                )))
+            }
+
+            let bound = f.parameters.iter().cloned().zip(arguments).collect();
+            return self.translate(&f.body, &bound)
+        }
+
+        if self.is_constant(name) && arguments.is_empty() {
+            Ok(term(name.to_string()))
+        } else if self.is_constant(name) {
+            Err(t.error(format!("\"{}\" is a constant and takes no arguments", name)))
+        } else {
+            Err(t.error(format!("Unknown symbol \"{}\"", name)))
+        }
+    }
+}
+
+/// A parsed S-expression along with the line and column where it starts.
+#[derive(Clone)]
+#[derive(Debug)]
+struct SExpr {
+    kind: SExprKind,
+    line: usize,
+    column: usize
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum SExprKind {
+    Symbol(String),
+    Keyword(String),
+    String(String),
+    Numeral(usize),
+    List(Vec<SExpr>)
+}
+
+impl PartialEq for SExpr {
+    fn eq(&self, other: &SExpr) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl SExpr {
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            message,
+            line: self.line,
+            column: self.column
+        }
+    }
+}
+
+impl fmt::Display for SExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            SExprKind::Symbol(ref s) => write!(f, "{}", format_symbol(s)),
+            SExprKind::Keyword(ref k) => write!(f, "{}", k),
+            SExprKind::String(ref s) => write!(f, "\"{}\"", s.replace('"', "\"\"")),
+            SExprKind::Numeral(n) => write!(f, "{}", n),
+            SExprKind::List(ref items) => {
+                let items: Vec<String> = items.iter().map(|i| i.to_string()).collect();
+                write!(f, "({})", items.join(" "))
+            }
+        }
+    }
+}
+
+/// Returns the name of the given symbol.
+fn symbol_name(symbol: &SExpr) -> Result<String, ParseError> {
+    match symbol.kind {
+        SExprKind::Symbol(ref n) => Ok(n.clone()),
+        _ => Err(symbol.error(format!("Expected a symbol, found {}", symbol)))
+    }
+}
+
+/// Applies the indexed function `(_ at-most k)` or `(_ at-least k)` to the
+/// given arguments.
+fn cardinality(function: &SExpr, index: &[SExpr], props: Vec<Proposition>) -> Result<Proposition, ParseError> {
+    match index {
+        [SExpr { kind: SExprKind::Symbol(ref u), .. }, name, SExpr { kind: SExprKind::Numeral(k), .. }] if u == "_" => {
+            match symbol_name(name)?.as_str() {
+                "at-most" => Ok(at_most_k(props, *k as i64)),
+                "at-least" => Ok(at_least_k(props, *k as i64)),
+                n => Err(name.error(format!("Unsupported indexed function \"{}\"", n)))
+            }
+        },
+        _ => Err(function.error(format!("Expected a function name, found {}", function)))
+    }
+}
+
+/// Checks that the given sort is `Bool`, the only sort supported.
+fn expect_bool(sort: &SExpr) -> Result<(), ParseError> {
+    match sort.kind {
+        SExprKind::Symbol(ref s) if s == "Bool" => Ok(()),
+        _ => Err(sort.error(format!("Unsupported sort {}, only Bool is supported", sort)))
+    }
+}
+
+fn parse_bool(value: &SExpr) -> Result<bool, ParseError> {
+    match value.kind {
+        SExprKind::Symbol(ref s) if s == "true" => Ok(true),
+        SExprKind::Symbol(ref s) if s == "false" => Ok(false),
+        _ => Err(value.error(format!("Expected true or false, found {}", value)))
+    }
+}
+
+/// Writes the given name as a symbol, surrounding it with bars unless it is
+/// a simple symbol.
+fn format_symbol(name: &str) -> String {
+    let simple = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "~!@$%^&*_-+=<>.?/".contains(c));
+
+    if simple {
+        name.to_string()
+    } else {
+        format!("|{}|", name)
+    }
+}
+
+fn escape_string(text: &str) -> String {
+    text.replace('"', "\"\"")
+}
+
+/// Parses the given input into a sequence of S-expressions, skipping
+/// whitespace and comments.
+fn parse_sexprs(input: &str) -> Result<Vec<SExpr>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+
+    // Each open list along with where it starts
+    let mut stack: Vec<(Vec<SExpr>, usize, usize)> = vec!((vec!(), 1, 1));
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i - line_start + 1;
+        let start = line;
+        let error = move |message: String| ParseError { message, line: start, column };
+
+        if c == '\n' {
+            i += 1;
+            line += 1;
+            line_start = i;
+            continue
+        } else if c.is_whitespace() {
+            i += 1;
+            continue
+        } else if c == ';' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue
+        } else if c == '(' {
+            stack.push((vec!(), line, column));
+            i += 1;
+            continue
+        } else if c == ')' {
+            if stack.len() == 1 {
+                return Err(error("Unexpected \")\"".to_string()))
+            }
+            let (items, l, col) = stack.pop().unwrap();
+            let last = stack.len() - 1;
+            stack[last].0.push(SExpr { kind: SExprKind::List(items), line: l, column: col });
+            i += 1;
+            continue
+        }
+
+        let kind = if c == '"' || c == '|' {
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    // A doubled quote stands for a single one within a string
+                    Some('"') if c == '"' && chars.get(i + 1) == Some(&'"') => {
+                        text.push('"');
+                        i += 2;
+                    },
+                    Some(&d) if d == c => {
+                        i += 1;
+                        break
+                    },
+                    Some(&d) => {
+                        if d == '\n' {
+                            line += 1;
+                            line_start = i + 1;
+                        }
+                        text.push(d);
+                        i += 1;
+                    },
+                    None => return Err(error("Unterminated string or quoted symbol".to_string()))
+                }
+            }
+            if c == '"' {
+                SExprKind::String(text)
+            } else {
+                SExprKind::Symbol(text)
+            }
+        } else {
+            let text: String = chars[i..].iter()
+                .take_while(|d| !d.is_whitespace() && !"()\";|".contains(**d))
+                .collect();
+            i += text.chars().count();
+
+            if text.starts_with(':') {
+                SExprKind::Keyword(text)
+            } else if text.chars().all(|d| d.is_ascii_digit()) {
+                match text.parse() {
+                    Ok(n) => SExprKind::Numeral(n),
+                    Err(_) => return Err(error(format!("Numeral {} is too large", text)))
+                }
+            } else {
+                SExprKind::Symbol(text)
+            }
+        };
+
+        let last = stack.len() - 1;
+        stack[last].0.push(SExpr { kind, line: start, column });
+    }
+
+    if stack.len() > 1 {
+        let (_, l, col) = stack.swap_remove(1);
+        return Err(ParseError {
+            message: "Unclosed \"(\"".to_string(),
+            line: l,
+            column: col
+        })
+    }
+
+    Ok(stack.pop().unwrap().0)
+}
+
+#[cfg(test)]
+mod tests {
+    use smtlib::*;
+
+    #[test]
+    fn smt_connectives() {
+        let mut smt = SmtInterpreter::new();
+
+        let output = smt.run("
+            (declare-fun a () Bool)
+            (declare-const b Bool)
+            (declare-const |c d| Bool)
+            (assert (xor a b (not |c d|)))
+            (assert (=> a b |c d|))
+            (assert (distinct a b))
+            (assert (= b (not |c d|) (ite a false true)))
+            (assert (let ((x a) (y (not b))) (! (and x y) :named both)))
+            (check-sat)
+            (get-model)
+            (get-value (a (or b |c d|)))
+        ");
+
+        let expected = "\
+sat
+(
+  (define-fun a () Bool true)
+  (define-fun b () Bool false)
+  (define-fun |c d| () Bool true)
+)
+((a true) ((or b |c d|) true))
+";
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn smt_cardinality() {
+        let mut smt = SmtInterpreter::new();
+
+        let output = smt.run("
+            (declare-const a Bool)
+            (declare-const b Bool)
+            (declare-const c Bool)
+            (assert ((_ at-least 2) a b c))
+            (assert ((_ at-most 1) a b))
+            (check-sat)
+            (get-value (c))
+            (assert ((_ at-most 1) b c))
+            (assert ((_ at-most 0) a))
+            (check-sat)
+            (assert ((_ pble 1) a))
+        ");
+
+        let expected = "\
+sat
+((c true))
+unsat
+(error \"12:25: Unsupported indexed function \"\"pble\"\"\")
+";
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn smt_push_pop_scopes_declarations() {
+        let mut smt = SmtInterpreter::new();
+
+        let output = smt.run("
+            (set-option :print-success true)
+            (push 2)
+            (declare-const p Bool)
+            (assert (not p))
+            (pop 2)
+            (assert p)
+            (pop 1)
+        ");
+
+        let expected = "\
+success
+success
+success
+success
+success
+(error \"7:21: Unknown symbol \"\"p\"\"\")
+(error \"8:13: Cannot pop 1 levels, only 0 have been pushed\")
+";
+
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn smt_check_sat_assuming() {
+        let mut smt = SmtInterpreter::new();
+
+        let output = smt.run("
+            (declare-const p Bool)
+            (declare-const q Bool)
+            (define-fun r () Bool (or p q))
+            (assert r)
+            (check-sat-assuming ((not p) (not q)))
+            (check-sat-assuming ((not p)))
+            (get-value (q r))
+        ");
+
+        assert_eq!(output, "unsat\nsat\n((q true) (r true))\n");
+    }
+
+    #[test]
+    fn smt_errors() {
+        let mut smt = SmtInterpreter::new();
+
+        let output = smt.run("
+            (declare-const x Int)
+            (declare-fun f (Bool) Bool)
+            (declare-const p Bool)
+            (declare-const p Bool)
+            (assert (p p))
+            (assert (and))
+            (assert (or))
+            (get-model)
+            (check-sat)
+            (get-proof)
+            (exit)
+            (check-sat)
+        ");
+
+        let expected = "\
+(error \"2:30: Unsupported sort Int, only Bool is supported\")
+(error \"3:28: Only constants can be declared\")
+(error \"5:28: \"\"p\"\" is already declared\")
+(error \"6:21: \"\"p\"\" is a constant and takes no arguments\")
+(error \"7:21: Expected at least 1 argument to \"\"and\"\"\")
+(error \"8:21: Expected at least 1 argument to \"\"or\"\"\")
+(error \"9:13: No model is available\")
+sat
+unsupported
+";
+
+        assert_eq!(output, expected);
+        assert_eq!(smt.run("(check-sat)"), "");
+    }
+
+    #[test]
+    fn smt_unbalanced() {
+        let mut smt = SmtInterpreter::new();
+
+        assert_eq!(smt.run("(check-sat))"), "(error \"1:12: Unexpected \"\")\"\"\")\n");
+        assert_eq!(smt.run("\n  (assert (and"), "(error \"2:3: Unclosed \"\"(\"\"\")\n");
+    }
+}
@@ -0,0 +1,956 @@
+use clauses::*;
+use parser::*;
+use proofs::*;
+use propositions::*;
+use sat::*;
+
+use std::fmt;
+
+/// A problem read from the TPTP format, made up of named, annotated
+/// formulas.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct TptpProblem {
+    pub formulas: Vec<TptpFormula>
+}
+
+/// A single `fof` or `cnf` formula of a TPTP problem.
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct TptpFormula {
+    pub name: String,
+    pub role: TptpRole,
+    pub formula: Proposition
+}
+
+/// The role of a TPTP formula, which decides whether it is assumed or is to
+/// be proved.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(PartialEq)]
+pub enum TptpRole {
+    Axiom,
+    Hypothesis,
+    Definition,
+    Assumption,
+    Lemma,
+    Theorem,
+    Corollary,
+    Plain,
+    Conjecture,
+    NegatedConjecture
+}
+
+impl fmt::Display for TptpRole {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            TptpRole::Axiom => "axiom",
+            TptpRole::Hypothesis => "hypothesis",
+            TptpRole::Definition => "definition",
+            TptpRole::Assumption => "assumption",
+            TptpRole::Lemma => "lemma",
+            TptpRole::Theorem => "theorem",
+            TptpRole::Corollary => "corollary",
+            TptpRole::Plain => "plain",
+            TptpRole::Conjecture => "conjecture",
+            TptpRole::NegatedConjecture => "negated_conjecture",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The outcome of attempting a problem, named as in the SZS ontology that
+/// automated theorem provers use to report their results.
+///
+/// `Theorem` and `CounterSatisfiable` answer problems with a conjecture,
+/// while `Unsatisfiable` and `Satisfiable` answer problems without one,
+/// including those whose conjecture has already been negated. `Timeout`
+/// means the attempt ran out of time, and `GaveUp` that it stopped without
+/// an answer for any other reason.
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(Eq)]
+#[derive(PartialEq)]
+pub enum SzsStatus {
+    Theorem,
+    CounterSatisfiable,
+    Unsatisfiable,
+    Satisfiable,
+    Timeout,
+    GaveUp
+}
+
+impl fmt::Display for SzsStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl TptpProblem {
+    /// Converts the problem into assumptions and a goal.
+    ///
+    /// Formulas with the `conjecture` role become the goal, and every formula
+    /// with another role becomes an assumption, apart from negated
+    /// conjectures. When there is no conjecture, the goal is the negation of
+    /// the negated conjectures, so that refutations start from them. When
+    /// there are neither, the goal is the negation of all of the formulas,
+    /// which is provable without assumptions exactly when they are
+    /// unsatisfiable.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let tptp = parse_tptp("
+    ///     fof(rule, axiom, p => q).
+    ///     fof(fact, hypothesis, p).
+    ///     fof(goal, conjecture, q).
+    /// ").unwrap();
+    ///
+    /// let problem = tptp.to_problem();
+    ///
+    /// assert_eq!(problem.assumptions.len(), 2);
+    /// assert_eq!(problem.goal, term("q".to_string()));
+    /// assert!(resolve(problem.assumptions, problem.goal));
+    /// ```
+    pub fn to_problem(&self) -> Problem {
+        let assumptions = self.assumed().iter()
+            .map(|f| f.formula.clone())
+            .collect();
+
+        let goal = match self.conjecture() {
+            Some(c) => c.formula.clone(),
+            None => not(and_all(self.refuted().iter()
+                .map(|f| f.formula.clone())
+                .collect()))
+        };
+
+        Problem { assumptions, goal }
+    }
+
+    /// Decides the problem, returning its SZS status along with a refutation
+    /// if the conjecture was proved or the formulas were found to be
+    /// unsatisfiable.
+    ///
+    /// The assumptions and the negated goal are refuted by the same search as
+    /// `refute`, which always finds a refutation when they are
+    /// unsatisfiable.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let tptp = parse_tptp("
+    ///     fof(rule, axiom, p => q).
+    ///     fof(goal, conjecture, q).
+    /// ").unwrap();
+    ///
+    /// let (status, proof) = tptp.attempt();
+    ///
+    /// assert_eq!(status, SzsStatus::CounterSatisfiable);
+    /// assert_eq!(proof, None);
+    /// ```
+    pub fn attempt(&self) -> (SzsStatus, Option<Proof>) {
+        let problem = self.to_problem();
+        let conjecture = self.conjecture().is_some();
+
+        let mut props = problem.assumptions.clone();
+        props.push(not(problem.goal.clone()));
+
+        match refute(&props) {
+            Some(refutation) => {
+                let status = if conjecture {
+                    SzsStatus::Theorem
+                } else {
+                    SzsStatus::Unsatisfiable
+                };
+
+                // The last of the refuted propositions is the negated goal
+                let negated_goal = Justification::Assumption(problem.assumptions.len());
+                let steps = refutation.steps.into_iter()
+                    .map(|s| if s.justification == negated_goal {
+                        ProofStep { justification: Justification::NegatedGoal, ..s }
+                    } else {
+                        s
+                    })
+                    .collect();
+
+                let proof = Proof {
+                    assumptions: problem.assumptions,
+                    goal: problem.goal,
+                    steps
+                };
+                (status, Some(proof))
+            },
+            None => {
+                let status = if conjecture {
+                    SzsStatus::CounterSatisfiable
+                } else {
+                    SzsStatus::Satisfiable
+                };
+                (status, None)
+            }
+        }
+    }
+
+    fn conjecture(&self) -> Option<&TptpFormula> {
+        self.formulas.iter().find(|f| f.role == TptpRole::Conjecture)
+    }
+
+    /// Returns the formulas that become assumptions, in order.
+    fn assumed(&self) -> Vec<&TptpFormula> {
+        if self.formulas.iter().any(|f| is_goal(f.role)) {
+            self.formulas.iter().filter(|f| !is_goal(f.role)).collect()
+        } else {
+            vec!()
+        }
+    }
+
+    /// Returns the formulas whose conjunction is negated to give the goal
+    /// when there is no conjecture.
+    fn refuted(&self) -> Vec<&TptpFormula> {
+        let negated: Vec<&TptpFormula> = self.formulas.iter()
+            .filter(|f| f.role == TptpRole::NegatedConjecture)
+            .collect();
+
+        if negated.is_empty() {
+            self.formulas.iter().collect()
+        } else {
+            negated
+        }
+    }
+}
+
+/// Checks whether formulas with the given role are proved rather than
+/// assumed.
+fn is_goal(role: TptpRole) -> bool {
+    role == TptpRole::Conjecture || role == TptpRole::NegatedConjecture
+}
+
+impl Proof {
+    /// Writes the proof as a TSTP derivation of the given problem, which
+    /// must be the problem that the proof was found for.
+    ///
+    /// The input formulas used by the proof are listed first, followed by
+    /// the negated conjecture, the clauses taken from them and every
+    /// resolvent, ending in `$false`. Each clause is annotated with the
+    /// inference that produced it and the names of its parents.
+    ///
+    /// ```
+    /// use resolution_prover::*;
+    ///
+    /// let tptp = parse_tptp("
+    ///     fof(rule, axiom, p => q).
+    ///     fof(fact, axiom, p).
+    ///     fof(goal, conjecture, q).
+    /// ").unwrap();
+    ///
+    /// let (_, proof) = tptp.attempt();
+    /// let tstp = proof.unwrap().to_tstp(&tptp);
+    ///
+    /// assert!(tstp.contains("fof(goal, conjecture, q)."));
+    /// assert!(tstp.contains("$false, inference(resolution, [status(thm)]"));
+    /// ```
+    pub fn to_tstp(&self, problem: &TptpProblem) -> String {
+        let assumptions = problem.assumed();
+        let conjecture = problem.conjecture();
+
+        let used = self.used_assumptions();
+        let negated_goal_used = self.steps.iter()
+            .any(|s| s.justification == Justification::NegatedGoal);
+
+        let mut lines = vec!();
+        for &i in &used {
+            lines.push(fof_line(assumptions[i]));
+        }
+
+        let negated_goal_parents = match conjecture {
+            Some(c) => {
+                if negated_goal_used {
+                    lines.push(fof_line(c));
+                    lines.push(format!(
+                        "fof(negated_goal, negated_conjecture, {}, inference(negate_conjecture, [status(cth)], [{}])).",
+                        tptp_formula(&not(c.formula.clone())),
+                        tptp_name(&c.name)
+                    ));
+                }
+                "negated_goal".to_string()
+            },
+            None => {
+                let negated = problem.refuted();
+                if negated_goal_used {
+                    negated.iter().for_each(|f| lines.push(fof_line(f)));
+                }
+                negated.iter()
+                    .map(|f| tptp_name(&f.name))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            }
+        };
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let clause = if step.clause.parts.is_empty() {
+                "$false".to_string()
+            } else {
+                step.clause.parts.iter()
+                    .map(|p| match *p {
+                        ClausePart::Term(ref n) => tptp_name(n),
+                        ClausePart::NegatedTerm(ref n) => format!("~{}", tptp_name(n)),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" | ")
+            };
+
+            let (role, source) = match step.justification {
+                Justification::Assumption(a) => (
+                    "plain",
+                    format!(
+                        "inference(cnf_transformation, [status(esa)], [{}])",
+                        tptp_name(&assumptions[a].name)
+                    )
+                ),
+                Justification::NegatedGoal => (
+                    "negated_conjecture",
+                    format!(
+                        "inference(cnf_transformation, [status(esa)], [{}])",
+                        negated_goal_parents
+                    )
+                ),
+                Justification::Resolution { left, right, .. } => (
+                    "plain",
+                    format!("inference(resolution, [status(thm)], [s{}, s{}])", left, right)
+                ),
+            };
+
+            lines.push(format!("cnf(s{}, {}, {}, {}).", i, role, clause, source));
+        }
+
+        lines.push("".to_string());
+        lines.join("\n")
+    }
+}
+
+/// Writes the given formula as a TPTP `fof` line.
+fn fof_line(formula: &TptpFormula) -> String {
+    format!(
+        "fof({}, {}, {}).",
+        tptp_name(&formula.name),
+        formula.role,
+        tptp_formula(&formula.formula)
+    )
+}
+
+/// Writes the given proposition using TPTP syntax, with every binary
+/// connective surrounded by parentheses. Conditional choices, which TPTP's
+/// first-order form does not have, are written as a pair of implications,
+/// and linear constraints are expanded into the choices that meet them.
+fn tptp_formula(prop: &Proposition) -> String {
+    let binary = |op: &str, a: &Proposition, b: &Proposition|
+        format!("({} {} {})", tptp_formula(a), op, tptp_formula(b));
+
+    match *prop {
+        Proposition::Or(ref a, ref b) => binary("|", a, b),
+        Proposition::And(ref a, ref b) => binary("&", a, b),
+        Proposition::Implies(ref a, ref b) => binary("=>", a, b),
+        Proposition::Iff(ref a, ref b) => binary("<=>", a, b),
+        Proposition::Xor(ref a, ref b) => binary("<~>", a, b),
+        Proposition::Nand(ref a, ref b) => binary("~&", a, b),
+        Proposition::Nor(ref a, ref b) => binary("~|", a, b),
+        Proposition::Ite(ref c, ref t, ref e) => format!(
+            "(({} => {}) & (~{} => {}))",
+            tptp_formula(c), tptp_formula(t), tptp_formula(c), tptp_formula(e)
+        ),
+        Proposition::Linear(ref terms, comparison, bound) =>
+            tptp_formula(&Clause::expand_linear(terms, comparison, bound)),
+        Proposition::Not(ref a) => format!("~{}", tptp_formula(a)),
+        Proposition::Term(ref a) => tptp_name(a),
+        Proposition::Top => "$true".to_string(),
+        Proposition::Bottom => "$false".to_string(),
+    }
+}
+
+/// Writes the given name as a TPTP atom, quoting it unless it is a word
+/// starting with a lower case letter.
+fn tptp_name(name: &str) -> String {
+    let lower_word = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if lower_word {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\\', "\\\\").replace('\'', "\\'"))
+    }
+}
+
+/// Parses a propositional problem written in the TPTP format.
+///
+/// The problem is made up of `fof` and `cnf` formulas, each with a name, a
+/// role and an optional annotation, which is ignored. Formulas may use the
+/// connectives `~`, `&`, `|`, `=>`, `<=`, `<=>`, `<~>`, `~&` and `~|`, and
+/// the constants `$true` and `$false`. Comments starting with `%` or written
+/// between `/*` and `*/` are ignored.
+///
+/// Quantifiers, variables, predicates with arguments, equality and `include`
+/// directives are not supported, and a problem may have at most one
+/// conjecture.
+///
+/// ```
+/// use resolution_prover::*;
+///
+/// let tptp = parse_tptp("
+///     % Modus ponens
+///     cnf(rule, axiom, ~p | q).
+///     cnf(fact, axiom, p).
+///     cnf(goal, negated_conjecture, ~q).
+/// ").unwrap();
+///
+/// assert_eq!(tptp.formulas.len(), 3);
+/// assert_eq!(tptp.formulas[2].role, TptpRole::NegatedConjecture);
+/// assert_eq!(tptp.attempt().0, SzsStatus::Unsatisfiable);
+/// ```
+pub fn parse_tptp(input: &str) -> Result<TptpProblem, ParseError> {
+    let mut parser = TptpParser {
+        tokens: tokenize_tptp(input)?,
+        position: 0,
+        end: end_position(input)
+    };
+
+    let mut formulas: Vec<TptpFormula> = vec!();
+    while parser.peek().is_some() {
+        let (line, column) = parser.location();
+        let formula = parser.parse_annotated()?;
+
+        if formula.role == TptpRole::Conjecture
+            && formulas.iter().any(|f| f.role == TptpRole::Conjecture) {
+            return Err(ParseError {
+                message: "Expected at most one conjecture".to_string(),
+                line,
+                column
+            })
+        }
+
+        // A conjecture is negated to give the negated conjectures, so the
+        // two kinds cannot be mixed
+        let other = match formula.role {
+            TptpRole::Conjecture => Some(TptpRole::NegatedConjecture),
+            TptpRole::NegatedConjecture => Some(TptpRole::Conjecture),
+            _ => None
+        };
+        if other.is_some() && formulas.iter().any(|f| Some(f.role) == other) {
+            return Err(ParseError {
+                message: "Expected either a conjecture or negated conjectures, but not both".to_string(),
+                line,
+                column
+            })
+        }
+
+        formulas.push(formula);
+    }
+
+    Ok(TptpProblem { formulas })
+}
+
+#[derive(Clone)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+enum TptpToken {
+    LowerWord(String),
+    UpperWord(String),
+    DollarWord(String),
+    Quoted(String),
+    Number(String),
+    Other(String)
+}
+
+impl fmt::Display for TptpToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TptpToken::LowerWord(ref a)
+                | TptpToken::UpperWord(ref a)
+                | TptpToken::DollarWord(ref a)
+                | TptpToken::Number(ref a) => write!(f, "\"{}\"", a),
+            TptpToken::Quoted(ref a) => write!(f, "\"'{}'\"", a),
+            TptpToken::Other(ref a) => write!(f, "\"{}\"", a),
+        }
+    }
+}
+
+/// The symbols of the TPTP syntax, with longer symbols before those that
+/// they start with.
+const SYMBOLS: [&str; 20] = [
+    "<=>", "<~>", "=>", "<=", "~&", "~|", "!=", "(", ")", "[", "]", ",", ".",
+    ":", "~", "&", "|", "!", "?", "="
+];
+
+/// Recursive descent parser over the tokens of a TPTP problem.
+struct TptpParser {
+    tokens: Vec<(TptpToken, usize, usize)>,
+    position: usize,
+    end: (usize, usize)
+}
+
+impl TptpParser {
+    fn peek(&self) -> Option<&TptpToken> {
+        self.tokens.get(self.position).map(|(t, _, _)| t)
+    }
+
+    fn location(&self) -> (usize, usize) {
+        self.tokens.get(self.position)
+            .map(|&(_, l, c)| (l, c))
+            .unwrap_or(self.end)
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        let (line, column) = self.location();
+        ParseError {
+            message,
+            line,
+            column
+        }
+    }
+
+    fn next(&mut self) -> Result<TptpToken, ParseError> {
+        match self.peek().cloned() {
+            Some(t) => {
+                self.position += 1;
+                Ok(t)
+            },
+            None => Err(self.error("Unexpected end of input".to_string()))
+        }
+    }
+
+    fn accept(&mut self, symbol: &str) -> bool {
+        if self.peek() == Some(&TptpToken::Other(symbol.to_string())) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), ParseError> {
+        if self.accept(symbol) {
+            Ok(())
+        } else {
+            Err(self.error(format!("Expected \"{}\"", symbol)))
+        }
+    }
+
+    fn parse_annotated(&mut self) -> Result<TptpFormula, ParseError> {
+        match self.peek().cloned() {
+            Some(TptpToken::LowerWord(ref k)) if k == "fof" || k == "cnf" => (),
+            Some(TptpToken::LowerWord(ref k)) if k == "include" =>
+                return Err(self.error("Include directives are not supported".to_string())),
+            Some(TptpToken::LowerWord(ref k)) if k == "tff" || k == "thf" || k == "tcf" =>
+                return Err(self.error(format!("Only fof and cnf formulas are supported, not {}", k))),
+            Some(t) => return Err(self.error(format!("Unexpected {}", t))),
+            None => return Err(self.error("Unexpected end of input".to_string()))
+        }
+        self.position += 1;
+
+        self.expect("(")?;
+        let name = match self.next()? {
+            TptpToken::LowerWord(n) | TptpToken::Quoted(n) | TptpToken::Number(n) => n,
+            t => {
+                self.position -= 1;
+                return Err(self.error(format!("Expected a formula name, found {}", t)))
+            }
+        };
+
+        self.expect(",")?;
+        let role = match self.next()? {
+            TptpToken::LowerWord(ref r) => match r.as_str() {
+                "axiom" => TptpRole::Axiom,
+                "hypothesis" => TptpRole::Hypothesis,
+                "definition" => TptpRole::Definition,
+                "assumption" => TptpRole::Assumption,
+                "lemma" => TptpRole::Lemma,
+                "theorem" => TptpRole::Theorem,
+                "corollary" => TptpRole::Corollary,
+                "plain" => TptpRole::Plain,
+                "conjecture" => TptpRole::Conjecture,
+                "negated_conjecture" => TptpRole::NegatedConjecture,
+                _ => {
+                    self.position -= 1;
+                    return Err(self.error(format!("Unsupported role \"{}\"", r)))
+                }
+            },
+            t => {
+                self.position -= 1;
+                return Err(self.error(format!("Expected a role, found {}", t)))
+            }
+        };
+
+        self.expect(",")?;
+        let formula = self.parse_formula()?;
+
+        // Annotations give the source of a formula, which is not needed
+        if self.accept(",") {
+            let mut depth = 0;
+            loop {
+                match self.next()? {
+                    TptpToken::Other(ref s) if s == "(" || s == "[" => depth += 1,
+                    TptpToken::Other(ref s) if s == ")" && depth == 0 => {
+                        self.position -= 1;
+                        break
+                    },
+                    TptpToken::Other(ref s) if s == ")" || s == "]" => depth -= 1,
+                    _ => ()
+                }
+            }
+        }
+
+        self.expect(")")?;
+        self.expect(".")?;
+
+        Ok(TptpFormula { name, role, formula })
+    }
+
+    /// Parses a formula, in which binary connectives other than `&` and `|`
+    /// cannot be chained, and different connectives cannot be mixed without
+    /// parentheses.
+    fn parse_formula(&mut self) -> Result<Proposition, ParseError> {
+        let left = self.parse_unitary()?;
+
+        let op = match self.peek() {
+            Some(TptpToken::Other(s)) if is_binary(s) => s.clone(),
+            _ => return Ok(left)
+        };
+        self.position += 1;
+
+        let right = self.parse_unitary()?;
+        let mut prop = match op.as_str() {
+            "|" => or(left, right),
+            "&" => and(left, right),
+            "=>" => implies(left, right),
+            "<=" => implies(right, left),
+            "<=>" => iff(left, right),
+            "<~>" => xor(left, right),
+            "~&" => nand(left, right),
+            _ => nor(left, right),
+        };
+
+        if op == "|" || op == "&" {
+            while self.accept(&op) {
+                let next = self.parse_unitary()?;
+                prop = if op == "|" { or(prop, next) } else { and(prop, next) };
+            }
+        }
+
+        match self.peek() {
+            Some(TptpToken::Other(s)) if is_binary(s) => Err(self.error(format!(
+                "Expected parentheses around the operands of \"{}\"", op
+            ))),
+            _ => Ok(prop)
+        }
+    }
+
+    fn parse_unitary(&mut self) -> Result<Proposition, ParseError> {
+        let token = self.next()?;
+
+        match token {
+            TptpToken::Other(ref s) if s == "~" => Ok(not(self.parse_unitary()?)),
+            TptpToken::Other(ref s) if s == "(" => {
+                let prop = self.parse_formula()?;
+                self.expect(")")?;
+                Ok(prop)
+            },
+            TptpToken::Other(ref s) if s == "!" || s == "?" => {
+                self.position -= 1;
+                Err(self.error("Quantifiers are not supported in propositional problems".to_string()))
+            },
+            TptpToken::UpperWord(_) => {
+                self.position -= 1;
+                Err(self.error("Variables are not supported in propositional problems".to_string()))
+            },
+            TptpToken::DollarWord(ref w) if w == "$true" => Ok(top()),
+            TptpToken::DollarWord(ref w) if w == "$false" => Ok(bottom()),
+            TptpToken::LowerWord(name) | TptpToken::Quoted(name) => {
+                match self.peek() {
+                    Some(TptpToken::Other(ref s)) if s == "(" =>
+                        Err(self.error("Predicates with arguments are not supported in propositional problems".to_string())),
+                    Some(TptpToken::Other(ref s)) if s == "=" || s == "!=" =>
+                        Err(self.error("Equality is not supported in propositional problems".to_string())),
+                    _ => Ok(term(name))
+                }
+            },
+            t => {
+                self.position -= 1;
+                Err(self.error(format!("Unexpected {}", t)))
+            }
+        }
+    }
+}
+
+/// Checks whether the given symbol is a binary connective.
+fn is_binary(symbol: &str) -> bool {
+    ["|", "&", "=>", "<=", "<=>", "<~>", "~&", "~|"].contains(&symbol)
+}
+
+/// Returns the line and column just after the end of the given input.
+fn end_position(input: &str) -> (usize, usize) {
+    let line = input.split('\n').count();
+    let column = input.split('\n').next_back().map_or(0, |l| l.chars().count()) + 1;
+    (line, column)
+}
+
+/// Splits the given input into tokens, paired with the line and column that
+/// each token starts at, skipping whitespace and comments.
+fn tokenize_tptp(input: &str) -> Result<Vec<(TptpToken, usize, usize)>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec!();
+    let mut i = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+
+    while i < chars.len() {
+        let column = i - line_start + 1;
+        let c = chars[i];
+        let start = line;
+        let error = move |message: String| ParseError { message, line: start, column };
+
+        if c == '\n' {
+            i += 1;
+            line += 1;
+            line_start = i;
+            continue
+        } else if c.is_whitespace() {
+            i += 1;
+            continue
+        } else if c == '%' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            loop {
+                if i + 1 >= chars.len() {
+                    return Err(error("Unterminated comment".to_string()))
+                }
+                if chars[i] == '*' && chars[i + 1] == '/' {
+                    i += 2;
+                    break
+                }
+                if chars[i] == '\n' {
+                    line += 1;
+                    line_start = i + 1;
+                }
+                i += 1;
+            }
+            continue
+        }
+
+        let word = |start: usize| -> String {
+            chars[start..].iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                .collect()
+        };
+
+        let (token, length) = if c.is_ascii_lowercase() {
+            let w = word(i);
+            let length = w.len();
+            (TptpToken::LowerWord(w), length)
+        } else if c.is_ascii_uppercase() {
+            let w = word(i);
+            let length = w.len();
+            (TptpToken::UpperWord(w), length)
+        } else if c.is_ascii_digit() {
+            let w: String = chars[i..].iter().take_while(|c| c.is_ascii_digit()).collect();
+            let length = w.len();
+            (TptpToken::Number(w), length)
+        } else if c == '$' {
+            let w = format!("${}", word(i + 1));
+            let length = w.len();
+            (TptpToken::DollarWord(w), length)
+        } else if c == '\'' || c == '"' {
+            let mut text = String::new();
+            let mut j = i + 1;
+            loop {
+                match chars.get(j) {
+                    Some('\\') if j + 1 < chars.len() => {
+                        text.push(chars[j + 1]);
+                        j += 2;
+                    },
+                    Some(&d) if d == c => break,
+                    Some('\n') | None =>
+                        return Err(error("Unterminated quoted name".to_string())),
+                    Some(&d) => {
+                        text.push(d);
+                        j += 1;
+                    }
+                }
+            }
+            let token = if c == '\'' {
+                TptpToken::Quoted(text)
+            } else {
+                TptpToken::Other(format!("\"{}\"", text))
+            };
+            (token, j + 1 - i)
+        } else {
+            let rest: String = chars[i..].iter().take(3).collect();
+            match SYMBOLS.iter().find(|s| rest.starts_with(*s)) {
+                Some(s) => (TptpToken::Other(s.to_string()), s.len()),
+                None => return Err(error(format!("Unexpected character '{}'", c)))
+            }
+        };
+
+        tokens.push((token, line, column));
+        i += length;
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use tptp::*;
+
+    #[test]
+    fn parse_tptp_connectives() {
+        let tptp = parse_tptp("
+            fof(a, axiom, (p & q & r) <= ~s).
+            fof(b, hypothesis, (p <~> q) ~| ($true ~& 'Big name')).
+            fof(c, conjecture, ((a | b) <=> c) => d, file('x.p', c)).
+        ").unwrap();
+
+        let expected = vec!(
+            implies(
+                not(term("s".to_string())),
+                and(
+                    and(term("p".to_string()), term("q".to_string())),
+                    term("r".to_string())
+                )
+            ),
+            nor(
+                xor(term("p".to_string()), term("q".to_string())),
+                nand(top(), term("Big name".to_string()))
+            ),
+            implies(
+                iff(
+                    or(term("a".to_string()), term("b".to_string())),
+                    term("c".to_string())
+                ),
+                term("d".to_string())
+            )
+        );
+
+        let formulas: Vec<Proposition> = tptp.formulas.iter()
+            .map(|f| f.formula.clone())
+            .collect();
+
+        assert_eq!(formulas, expected);
+        assert_eq!(tptp.formulas[1].role, TptpRole::Hypothesis);
+        assert_eq!(tptp.formulas[2].name, "c");
+    }
+
+    #[test]
+    fn parse_tptp_comments() {
+        let tptp = parse_tptp("
+            % A line comment
+            /* A block comment
+               over two lines */
+            cnf(1, axiom, p | ~q).
+        ").unwrap();
+
+        assert_eq!(tptp.formulas.len(), 1);
+        assert_eq!(tptp.formulas[0].name, "1");
+    }
+
+    #[test]
+    fn parse_tptp_errors() {
+        let cases = vec!(
+            ("fof(a, axiom, ![X]: p(X)).", 1, 15, "Quantifiers are not supported in propositional problems"),
+            ("fof(a, axiom, p(a)).", 1, 16, "Predicates with arguments are not supported in propositional problems"),
+            ("cnf(a, axiom, X | p).", 1, 15, "Variables are not supported in propositional problems"),
+            ("fof(a, axiom, p | q & r).", 1, 21, "Expected parentheses around the operands of \"|\""),
+            ("fof(a, axiom, p => q => r).", 1, 22, "Expected parentheses around the operands of \"=>\""),
+            ("include('Axioms/SYN000-0.ax').", 1, 1, "Include directives are not supported"),
+            ("fof(a, type, p).", 1, 8, "Unsupported role \"type\""),
+            ("fof(a, axiom, p)", 1, 17, "Expected \".\""),
+            ("fof(a, conjecture, p).\nfof(b, conjecture, q).", 2, 1, "Expected at most one conjecture"),
+            ("fof(a, conjecture, p).\ncnf(b, negated_conjecture, ~q).", 2, 1, "Expected either a conjecture or negated conjectures, but not both"),
+            ("cnf(a, negated_conjecture, ~q).\nfof(b, conjecture, p).", 2, 1, "Expected either a conjecture or negated conjectures, but not both"),
+        );
+
+        for (input, line, column, message) in cases {
+            let expected = ParseError {
+                message: message.to_string(),
+                line,
+                column
+            };
+
+            assert_eq!(parse_tptp(input), Err(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn attempt_statuses() {
+        let cases = vec!(
+            ("fof(a, axiom, p => q). fof(b, axiom, p). fof(c, conjecture, q).", SzsStatus::Theorem),
+            ("fof(a, axiom, p => q). fof(c, conjecture, q).", SzsStatus::CounterSatisfiable),
+            ("cnf(a, axiom, p). cnf(b, negated_conjecture, ~p).", SzsStatus::Unsatisfiable),
+            ("cnf(a, axiom, p | q). cnf(b, negated_conjecture, ~p).", SzsStatus::Satisfiable),
+            ("fof(a, axiom, p => q). fof(b, axiom, p). fof(c, axiom, ~q).", SzsStatus::Unsatisfiable),
+            ("fof(a, axiom, p <~> q). fof(b, axiom, p => q).", SzsStatus::Satisfiable),
+            ("fof(a, axiom, $false).", SzsStatus::Unsatisfiable),
+        );
+
+        for (input, status) in cases {
+            let tptp = parse_tptp(input).unwrap();
+
+            assert_eq!(tptp.attempt().0, status, "{}", input);
+        }
+    }
+
+    #[test]
+    fn display_statuses() {
+        assert_eq!(SzsStatus::CounterSatisfiable.to_string(), "CounterSatisfiable");
+        assert_eq!(SzsStatus::Timeout.to_string(), "Timeout");
+        assert_eq!(SzsStatus::GaveUp.to_string(), "GaveUp");
+    }
+
+    #[test]
+    fn to_tstp_derivation() {
+        let tptp = parse_tptp("
+            fof(unused, axiom, r).
+            fof(rule, axiom, p => q).
+            fof(fact, axiom, p).
+            fof(goal, conjecture, q).
+        ").unwrap();
+
+        let (status, proof) = tptp.attempt();
+        assert_eq!(status, SzsStatus::Theorem);
+
+        let expected = "\
+fof(rule, axiom, (p => q)).
+fof(fact, axiom, p).
+fof(goal, conjecture, q).
+fof(negated_goal, negated_conjecture, ~q, inference(negate_conjecture, [status(cth)], [goal])).
+cnf(s0, plain, ~p | q, inference(cnf_transformation, [status(esa)], [rule])).
+cnf(s1, negated_conjecture, ~q, inference(cnf_transformation, [status(esa)], [negated_goal])).
+cnf(s2, plain, ~p, inference(resolution, [status(thm)], [s1, s0])).
+cnf(s3, plain, p, inference(cnf_transformation, [status(esa)], [fact])).
+cnf(s4, plain, $false, inference(resolution, [status(thm)], [s3, s2])).
+";
+
+        assert_eq!(proof.unwrap().to_tstp(&tptp), expected);
+    }
+
+    #[test]
+    fn to_tstp_round_trip() {
+        let prop = ite(
+            term("c".to_string()),
+            nand(term("Upper".to_string()), bottom()),
+            xor(term("a".to_string()), not(term("b".to_string())))
+        );
+
+        let tptp = parse_tptp(&format!("fof(a, axiom, {}).", tptp_formula(&prop))).unwrap();
+        let model = find_model(&[xor(prop, tptp.formulas[0].formula.clone())]);
+
+        assert_eq!(model, None);
+    }
+}